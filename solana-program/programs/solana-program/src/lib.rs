@@ -1,26 +1,414 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::program::{invoke_signed, set_return_data};
 use anchor_spl::{
     associated_token::AssociatedToken,
+    token::Token,
+    token_2022::spl_token_2022::instruction::AuthorityType,
+    token_2022::Token2022,
     token_interface::{
-        transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+        burn, close_account, revoke, set_authority, transfer_checked, Burn, CloseAccount, Mint,
+        Revoke, SetAuthority, TokenAccount, TokenInterface, TransferChecked,
     },
 };
+use solana_instructions_sysvar::{load_current_index_checked, load_instruction_at_checked};
+use solana_sha256_hasher::hashv;
 
 declare_id!("LUMPd26Acz4wqS8EBuoxPN2zhwCUF4npbkrqhLbM9AL");
 
 /// Seed prefix for user vault PDAs
+#[constant]
 pub const VAULT_SEED: &[u8] = b"vault";
 
 /// SNS Name Service Program ID
+#[constant]
 pub const NAME_SERVICE_PROGRAM_ID: Pubkey = pubkey!("namesLPneVptA9Z5rqUDD9tMTWEJwofgaYwp8cawRkX");
 
 /// SNS Records V2 Program ID (correct mainnet address)
+#[constant]
 pub const SNS_RECORDS_PROGRAM_ID: Pubkey = pubkey!("HP3D4D1ZCmohQGFVms2SS4LCANgJyksBf5s1F77FuFjZ");
 
+/// SNS Name Tokenizer Program ID (mints wrapped domain NFTs)
+#[constant]
+pub const NAME_TOKENIZER_PROGRAM_ID: Pubkey = pubkey!("nftD3vbNkNqfj2Sd3HZwbpw4BxxKWr4AjGb9X38JeZk");
+
+/// Seed prefix the Name Tokenizer uses for a wrapped domain's mint
+pub const TOKENIZED_NAME_SEED: &[u8] = b"tokenized_name";
+
+/// Root `.sol` TLD name account, parent of every .sol domain
+pub const ROOT_DOMAIN_ACCOUNT: Pubkey = pubkey!("58PwtjSDuFHuUkYjH9BYnnQKHfwo9reZhC2zMJv9JPkx");
+
+/// Compute Budget program ID, the only foreign program allowed in isolated transactions
+pub const COMPUTE_BUDGET_PROGRAM_ID: Pubkey = pubkey!("ComputeBudget111111111111111111111111111111");
+
 /// Record V2 discriminator/class for key derivation
+#[constant]
 pub const RECORD_V2_CLASS: u8 = 2;
 
+/// SPL Governance Program ID
+#[constant]
+pub const SPL_GOVERNANCE_PROGRAM_ID: Pubkey = pubkey!("GovER5Lthms3bLBqWub97yVrMmEogzX7xNjdXpPPCVZw");
+
+/// Seed prefix of the SPL Governance native treasury PDA
+pub const GOVERNANCE_NATIVE_TREASURY_SEED: &[u8] = b"native-treasury";
+
+/// SPL Governance account types of a realm (RealmV1, RealmV2)
+pub const GOVERNANCE_REALM_ACCOUNT_TYPES: [u8; 2] = [1, 16];
+
+/// SPL Governance account types of a governance (V1 and V2 of generic/program/mint/token governances)
+pub const GOVERNANCE_ACCOUNT_TYPES: [u8; 8] = [3, 4, 9, 10, 18, 19, 20, 21];
+
+/// Seed for the global protocol config PDA
+#[constant]
+pub const CONFIG_SEED: &[u8] = b"config";
+
+/// Seed prefix for queued parameter change PDAs
+#[constant]
+pub const PARAM_CHANGE_SEED: &[u8] = b"param_change";
+
+/// Seed prefix for per-mint deposit policy PDAs
+#[constant]
+pub const MINT_POLICY_SEED: &[u8] = b"mint_policy";
+
+/// Seed for the protocol fee treasury PDA
+#[constant]
+pub const TREASURY_SEED: &[u8] = b"treasury";
+
+/// Seed for the global protocol stats PDA
+#[constant]
+pub const STATS_SEED: &[u8] = b"stats";
+
+/// Seed prefix for per-user points ledger PDAs
+#[constant]
+pub const POINTS_SEED: &[u8] = b"points";
+
+/// Seed prefix for per-domain entry PDAs
+#[constant]
+pub const ENTRY_SEED: &[u8] = b"entry";
+
+/// Seed prefix for quarantined vault token account flags
+#[constant]
+pub const QUARANTINE_SEED: &[u8] = b"quarantine";
+
+/// Seed prefix for vault auto-sweep policy PDAs
+#[constant]
+pub const SWEEP_POLICY_SEED: &[u8] = b"sweep_policy";
+
+/// Maximum number of mints a sweep policy can forward
+pub const MAX_SWEEP_MINTS: usize = 8;
+
+/// Maximum number of weighted destinations a sweep policy can split between
+pub const MAX_SWEEP_DESTINATIONS: usize = 4;
+
+/// Sweep destination weights must sum to this many basis points
+pub const SWEEP_TOTAL_WEIGHT_BPS: u16 = 10_000;
+
+/// Seed prefix for vault sweep denomination bucket PDAs
+#[constant]
+pub const SWEEP_BUCKETS_SEED: &[u8] = b"sweep_buckets";
+
+/// Maximum number of denominations a vault can sweep in
+pub const MAX_SWEEP_DENOMINATIONS: usize = 6;
+
+/// Longest randomized delay between two bucket releases
+#[constant]
+pub const MAX_SWEEP_BUCKET_DELAY_SECONDS: u32 = 7 * 24 * 60 * 60;
+
+/// Seed prefix for domain payment endpoint PDAs
+#[constant]
+pub const PAYMENT_ENDPOINT_SEED: &[u8] = b"payment_endpoint";
+
+/// Seed prefix for payment receipt PDAs
+#[constant]
+pub const RECEIPT_SEED: &[u8] = b"receipt";
+
+/// Maximum length of a payment endpoint's memo schema
+pub const MAX_MEMO_SCHEMA_LEN: usize = 64;
+
+/// Seed prefix for record template PDAs
+#[constant]
+pub const RECORD_TEMPLATE_SEED: &[u8] = b"record_template";
+
+/// Maximum number of records in a record template
+pub const MAX_TEMPLATE_RECORDS: usize = 8;
+
+/// Maximum length of a record name (without the V2 prefix)
+pub const MAX_RECORD_NAME_LEN: usize = 32;
+
+/// Maximum length of a templated record's content
+pub const MAX_TEMPLATE_CONTENT_LEN: usize = 128;
+
+/// Maximum length of UTF-8 text record content
+pub const MAX_RECORD_TEXT_LEN: usize = 256;
+
+/// Maximum length of an IPFS CID record content
+pub const MAX_RECORD_CID_LEN: usize = 128;
+
+/// Maximum length of a hostname (CNAME record content)
+pub const MAX_HOSTNAME_LEN: usize = 253;
+
+/// Length of an Arweave transaction id (base64url of 32 bytes)
+pub const ARWEAVE_TX_ID_LEN: usize = 43;
+
+/// Seed prefix for social record verification PDAs
+#[constant]
+pub const SOCIAL_VERIFICATION_SEED: &[u8] = b"social_verification";
+
+/// Seed prefix for per-domain record operator grant PDAs
+#[constant]
+pub const RECORD_OPERATOR_SEED: &[u8] = b"record_operator";
+
+/// Seed prefix for flash record session policy PDAs
+#[constant]
+pub const RECORD_SESSION_SEED: &[u8] = b"record_session";
+
+/// Maximum number of records a flash record session may write
+pub const MAX_SESSION_RECORDS: usize = 8;
+
+/// Time after expiry before a lapsed auxiliary account can be reaped by anyone
+pub const REAP_GRACE_SECONDS: i64 = 7 * 86_400;
+
+/// `check_vault_health` issue: the entries passed don't match the vault's domain counter
+#[constant]
+pub const HEALTH_COUNT_MISMATCH: u32 = 1 << 0;
+
+/// `check_vault_health` issue: an entry belongs to another vault or was passed twice
+#[constant]
+pub const HEALTH_INVALID_ENTRY: u32 = 1 << 1;
+
+/// `check_vault_health` issue: a domain tracked by an entry is not held by the vault
+#[constant]
+pub const HEALTH_NOT_CUSTODIED: u32 = 1 << 2;
+
+/// `check_vault_health` issue: a SOL record doesn't resolve to the vault with a verified ROA
+#[constant]
+pub const HEALTH_RECORD_UNVERIFIED: u32 = 1 << 3;
+
+/// `check_vault_health` issue: a SOL record's staleness isn't validated for the vault
+#[constant]
+pub const HEALTH_RECORD_STALE: u32 = 1 << 4;
+
+/// `check_vault_health` issue: the vault is flagged as mid-CPI
+#[constant]
+pub const HEALTH_IN_FLIGHT: u32 = 1 << 5;
+
+/// Seed prefix for vault backup blob PDAs
+#[constant]
+pub const BACKUP_BLOB_SEED: &[u8] = b"backup_blob";
+
+/// Maximum size of an owner-encrypted backup blob
+pub const MAX_BACKUP_BLOB_LEN: usize = 1024;
+
+/// Seed prefix for vault notification endpoint PDAs
+#[constant]
+pub const NOTIFICATION_ENDPOINTS_SEED: &[u8] = b"notifications";
+
+/// Maximum number of opaque webhook identifiers per vault
+pub const MAX_NOTIFICATION_WEBHOOKS: usize = 4;
+
+/// Notification alert: a vaulted domain nears expiry
+#[constant]
+pub const ALERT_EXPIRY: u8 = 1 << 0;
+
+/// Notification alert: a withdrawal was attempted or made
+#[constant]
+pub const ALERT_WITHDRAWAL: u8 = 1 << 1;
+
+/// Notification alert: a record of a vaulted domain changed
+#[constant]
+pub const ALERT_RECORD_CHANGE: u8 = 1 << 2;
+
+/// Notification alert: an authority (co-signer) change was queued or applied
+#[constant]
+pub const ALERT_AUTHORITY_CHANGE: u8 = 1 << 3;
+
+/// All defined notification alert bits
+pub const ALERT_ALL: u8 =
+    ALERT_EXPIRY | ALERT_WITHDRAWAL | ALERT_RECORD_CHANGE | ALERT_AUTHORITY_CHANGE;
+
+/// Seed prefix for vault voting delegation PDAs
+#[constant]
+pub const VOTING_DELEGATION_SEED: &[u8] = b"voting_delegation";
+
+/// Seed prefix for pending vault co-signer change PDAs
+#[constant]
+pub const CO_SIGNER_CHANGE_SEED: &[u8] = b"co_signer_change";
+
+/// Seed prefix for vault withdrawal confirmation phrase PDAs
+#[constant]
+pub const WITHDRAWAL_PHRASE_SEED: &[u8] = b"withdrawal_phrase";
+
+/// Maximum length of a withdrawal confirmation phrase
+pub const MAX_CONFIRMATION_PHRASE_LEN: usize = 64;
+
+/// Delay before a queued co-signer change can be applied (2 days)
+pub const CO_SIGNER_DELAY_SECONDS: i64 = 2 * 86_400;
+
+/// Seed prefix for domain bundle PDAs
+#[constant]
+pub const BUNDLE_SEED: &[u8] = b"bundle";
+
+/// Maximum number of domains in a bundle
+pub const MAX_BUNDLE_DOMAINS: usize = 16;
+
+/// Maximum length of a bundle name
+pub const MAX_BUNDLE_NAME_LEN: usize = 32;
+
+/// Seed for the domain valuation table PDA
+#[constant]
+pub const VALUATION_TABLE_SEED: &[u8] = b"valuation_table";
+
+/// Number of name length tiers in the valuation table (the last covers longer names)
+pub const VALUATION_LENGTH_TIERS: usize = 8;
+
+/// Seed for the outflow circuit breaker PDA
+#[constant]
+pub const OUTFLOW_BREAKER_SEED: &[u8] = b"outflow_breaker";
+
+/// Seed for the global security log PDA
+#[constant]
+pub const SECURITY_LOG_SEED: &[u8] = b"security_log";
+
+/// Entries kept by the security log before the oldest is overwritten
+pub const SECURITY_LOG_CAPACITY: usize = 32;
+
+/// Seed prefix for scheduled operation PDAs
+#[constant]
+pub const SCHEDULE_SEED: &[u8] = b"schedule";
+
+/// Shortest interval between runs of a scheduled operation
+#[constant]
+pub const MIN_SCHEDULE_INTERVAL_SECONDS: i64 = 3600;
+
+/// Seed for the keeper bounty schedule PDA
+#[constant]
+pub const KEEPER_BOUNTIES_SEED: &[u8] = b"keeper_bounties";
+
+/// Number of `CrankKind` variants
+pub const CRANK_KIND_COUNT: usize = 4;
+
+/// Seed prefix for vault summary PDAs
+#[constant]
+pub const VAULT_SUMMARY_SEED: &[u8] = b"vault_summary";
+
+/// Seed prefix for custody snapshot PDAs
+#[constant]
+pub const CUSTODY_SNAPSHOT_SEED: &[u8] = b"custody_snapshot";
+
+/// Maximum number of entries sampled against a snapshot root per commit
+pub const MAX_SNAPSHOT_SAMPLES: usize = 8;
+
+/// Maximum depth of a custody snapshot merkle proof
+pub const MAX_SNAPSHOT_PROOF_LEN: usize = 24;
+
+/// Seed prefix for burner subdomain PDAs
+#[constant]
+pub const BURNER_SUBDOMAIN_SEED: &[u8] = b"burner";
+
+/// Maximum length of a burner subdomain label
+pub const MAX_BURNER_LABEL_LEN: usize = 32;
+
+/// Longest a burner subdomain may live before it can be reaped
+#[constant]
+pub const MAX_BURNER_LIFETIME_SECONDS: i64 = 30 * 24 * 60 * 60;
+
+/// Size of an SNS name registry header (parent, owner, class)
+pub const NAME_REGISTRY_HEADER_LEN: usize = 96;
+
+/// Seed prefix for staged SOL record update PDAs
+#[constant]
+pub const STAGED_RECORD_SEED: &[u8] = b"staged_record";
+
+/// Largest slot gap a staged record update may require
+#[constant]
+pub const MAX_RECORD_STAGE_SLOT_GAP: u64 = 216_000;
+
+/// Seed for the program info PDA
+#[constant]
+pub const PROGRAM_INFO_SEED: &[u8] = b"program_info";
+
+/// Deployed semantic version as [major, minor, patch], taken from the crate version
+pub const PROGRAM_VERSION: [u16; 3] = [
+    parse_version_part(env!("CARGO_PKG_VERSION_MAJOR")),
+    parse_version_part(env!("CARGO_PKG_VERSION_MINOR")),
+    parse_version_part(env!("CARGO_PKG_VERSION_PATCH")),
+];
+
+/// Feature bits published in `ProgramInfo::features`; bits are never reused
+pub const FEATURE_WRAPPED_DOMAINS: u64 = 1 << 0;
+pub const FEATURE_UNWRAPPED_DOMAINS: u64 = 1 << 1;
+pub const FEATURE_RECORDS: u64 = 1 << 2;
+pub const FEATURE_RECORD_SESSIONS: u64 = 1 << 3;
+pub const FEATURE_STAKING: u64 = 1 << 4;
+pub const FEATURE_PAYMENTS: u64 = 1 << 5;
+pub const FEATURE_AUTO_SWEEP: u64 = 1 << 6;
+pub const FEATURE_SWEEP_BUCKETS: u64 = 1 << 7;
+pub const FEATURE_BUNDLES: u64 = 1 << 8;
+pub const FEATURE_SCHEDULED_OPERATIONS: u64 = 1 << 9;
+pub const FEATURE_KEEPER_BOUNTIES: u64 = 1 << 10;
+pub const FEATURE_OUTFLOW_BREAKER: u64 = 1 << 11;
+pub const FEATURE_CUSTODY_SNAPSHOTS: u64 = 1 << 12;
+pub const FEATURE_VOTING_DELEGATION: u64 = 1 << 13;
+pub const FEATURE_BURNER_SUBDOMAINS: u64 = 1 << 14;
+pub const FEATURE_STAGED_RECORD_UPDATES: u64 = 1 << 15;
+pub const FEATURE_VAULT_SIGNATURE_CLAIMS: u64 = 1 << 16;
+
+/// Features this build of the program supports
+pub const ENABLED_FEATURES: u64 = FEATURE_WRAPPED_DOMAINS
+    | FEATURE_UNWRAPPED_DOMAINS
+    | FEATURE_RECORDS
+    | FEATURE_RECORD_SESSIONS
+    | FEATURE_STAKING
+    | FEATURE_PAYMENTS
+    | FEATURE_AUTO_SWEEP
+    | FEATURE_SWEEP_BUCKETS
+    | FEATURE_BUNDLES
+    | FEATURE_SCHEDULED_OPERATIONS
+    | FEATURE_KEEPER_BOUNTIES
+    | FEATURE_OUTFLOW_BREAKER
+    | FEATURE_CUSTODY_SNAPSHOTS
+    | FEATURE_VOTING_DELEGATION
+    | FEATURE_BURNER_SUBDOMAINS
+    | FEATURE_STAGED_RECORD_UPDATES
+    | FEATURE_VAULT_SIGNATURE_CLAIMS;
+
+/// Bytes reserved at the end of per-user accounts for future fields
+pub const RESERVED_BYTES: usize = 64;
+
+/// Bytes still reserved in `UserVault` after `co_signer` was carved from its reserve
+pub const VAULT_RESERVED_BYTES: usize = RESERVED_BYTES - 32;
+
+/// Bytes still reserved in `DomainEntry` after `memo_hash` was carved from its reserve
+pub const ENTRY_RESERVED_BYTES: usize = RESERVED_BYTES - 32;
+
+/// Account layout version that `admin_migrate_account` migrates to; bump on every layout change
+#[constant]
+pub const ACCOUNT_LAYOUT_VERSION: u8 = 2;
+
+/// Seed for the staking reward pool PDA
+#[constant]
+pub const REWARD_POOL_SEED: &[u8] = b"reward_pool";
+
+/// Upper bound for the unstake cooldown (30 days)
+pub const MAX_UNSTAKE_COOLDOWN_SECONDS: i64 = 30 * 24 * 60 * 60;
+
+/// Maximum number of allowlisted airdrop claim programs
+pub const MAX_CLAIM_PROGRAMS: usize = 8;
+
+/// Maximum number of programs on the CPI allowlist
+pub const MAX_CPI_PROGRAMS: usize = 8;
+
+/// Upper bound for any single operation fee (1 SOL)
+pub const MAX_FEE_LAMPORTS: u64 = 1_000_000_000;
+
+/// Seconds in a day, the unit of custody points accrual
+pub const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// Number of operations in the fee schedule
+pub const FEE_OPERATION_COUNT: usize = 3;
+
+/// Upper bound for the config timelock (30 days) so governance can't lock itself out
+pub const MAX_TIMELOCK_SECONDS: i64 = 30 * 24 * 60 * 60;
+
 #[program]
 pub mod solana_program {
     use super::*;
@@ -32,79 +420,258 @@ pub mod solana_program {
         vault.owner = ctx.accounts.owner.key();
         vault.bump = ctx.bumps.vault;
         vault.domains_count = 0;
+        vault.in_flight = false;
+        vault.co_signer = Pubkey::default();
+        vault._reserved = [0; VAULT_RESERVED_BYTES];
+        let stats = &mut ctx.accounts.stats;
+        stats.total_vaults = stats.total_vaults.saturating_add(1);
+        record_stats(stats, StatsCounter::InitializeVault, 0);
 
         msg!("Vault initialized for user: {}", vault.owner);
         Ok(())
     }
 
-    /// Deposit an SNS domain into the user's vault
-    /// The domain NFT is transferred to a token account owned by the vault PDA
-    pub fn deposit_domain(ctx: Context<DepositDomain>) -> Result<()> {
-        // Transfer the SNS domain (NFT) from user to vault's token account
-        let cpi_accounts = TransferChecked {
-            from: ctx.accounts.user_token_account.to_account_info(),
-            to: ctx.accounts.vault_token_account.to_account_info(),
-            authority: ctx.accounts.owner.to_account_info(),
-            mint: ctx.accounts.domain_mint.to_account_info(),
-        };
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_context = CpiContext::new(cpi_program, cpi_accounts);
-
-        // SNS domains are NFTs with 0 decimals and amount of 1
-        transfer_checked(cpi_context, 1, 0)?;
+    /// Grow a vault created by an older program version to the current layout
+    /// New fields are zero-initialized; does nothing if the vault is already current
+    pub fn upgrade_vault(ctx: Context<UpgradeVault>) -> Result<()> {
+        let vault_info = ctx.accounts.vault.to_account_info();
+        {
+            let data = vault_info.try_borrow_data()?;
+            require!(
+                data.len() >= 40
+                    && data[..8] == *UserVault::DISCRIMINATOR
+                    && data[8..40] == ctx.accounts.owner.key().to_bytes(),
+                VaultError::UnauthorizedAccess
+            );
+        }
 
-        let vault = &mut ctx.accounts.vault;
-        vault.domains_count = vault.domains_count.checked_add(1).unwrap();
+        let new_len = 8 + UserVault::INIT_SPACE;
+        let old_len = migrate_account_layout(
+            &vault_info,
+            new_len,
+            &ctx.accounts.owner.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+        if old_len >= new_len {
+            msg!("Vault already up to date");
+            return Ok(());
+        }
 
-        msg!(
-            "Domain {} deposited to vault. Total domains: {}",
-            ctx.accounts.domain_mint.key(),
-            vault.domains_count
-        );
+        msg!("Vault upgraded from {} to {} bytes", old_len, new_len);
         Ok(())
     }
 
-    /// Withdraw an SNS domain from the user's vault
-    /// Only the original owner can withdraw their domains
-    pub fn withdraw_domain(ctx: Context<WithdrawDomain>) -> Result<()> {
-        let vault = &ctx.accounts.vault;
-
-        // Verify the vault has domains
-        require!(vault.domains_count > 0, VaultError::NoDomains);
-
-        // Build PDA signer seeds
-        let owner_key = ctx.accounts.owner.key();
-        let bump = vault.bump;
-        let signer_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, owner_key.as_ref(), &[bump]]];
+    /// Migrate a legacy account of this program to the current layout (upgrade authority only)
+    /// Only built with the `migrations` feature; `layout_version` must match
+    /// `ACCOUNT_LAYOUT_VERSION` so a stale migration script can't run against a newer program
+    #[cfg(feature = "migrations")]
+    pub fn admin_migrate_account(
+        ctx: Context<AdminMigrateAccount>,
+        account_type: MigratableAccount,
+        layout_version: u8,
+    ) -> Result<()> {
+        require!(
+            layout_version == ACCOUNT_LAYOUT_VERSION,
+            VaultError::LayoutVersionMismatch
+        );
 
-        // Transfer the SNS domain back to user
-        let cpi_accounts = TransferChecked {
-            from: ctx.accounts.vault_token_account.to_account_info(),
-            to: ctx.accounts.user_token_account.to_account_info(),
-            authority: ctx.accounts.vault.to_account_info(),
-            mint: ctx.accounts.domain_mint.to_account_info(),
+        let target = ctx.accounts.target.to_account_info();
+        let (discriminator, new_len) = match account_type {
+            MigratableAccount::UserVault => (UserVault::DISCRIMINATOR, 8 + UserVault::INIT_SPACE),
+            MigratableAccount::DomainEntry => {
+                (DomainEntry::DISCRIMINATOR, 8 + DomainEntry::INIT_SPACE)
+            }
         };
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+        {
+            let data = target.try_borrow_data()?;
+            require!(
+                data.len() >= 8 && data[..8] == *discriminator,
+                VaultError::InvalidMigrationTarget
+            );
+        }
 
-        // SNS domains are NFTs with 0 decimals and amount of 1
-        transfer_checked(cpi_context, 1, 0)?;
-
-        // Update domains count
-        let vault = &mut ctx.accounts.vault;
-        vault.domains_count = vault.domains_count.checked_sub(1).unwrap();
+        let old_len = migrate_account_layout(
+            &target,
+            new_len,
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+        if old_len >= new_len {
+            msg!("{:?} {} already up to date", account_type, target.key());
+            return Ok(());
+        }
 
+        emit!(AccountMigrated {
+            account: target.key(),
+            account_type,
+            layout_version,
+            old_len: old_len as u32,
+            new_len: new_len as u32,
+        });
         msg!(
-            "Domain {} withdrawn from vault. Remaining domains: {}",
-            ctx.accounts.domain_mint.key(),
-            vault.domains_count
+            "{:?} {} migrated to layout {} ({} -> {} bytes)",
+            account_type,
+            target.key(),
+            layout_version,
+            old_len,
+            new_len
         );
         Ok(())
     }
 
+    /// Deposit an SNS domain into the user's vault
+    /// The domain NFT is transferred to a token account owned by the vault PDA
+    pub fn deposit_domain(ctx: Context<DepositDomain>, memo_hash: Option<[u8; 32]>) -> Result<()> {
+        let points_bump = ctx.bumps.user_points;
+        let entry_bump = ctx.bumps.domain_entry;
+        let accounts = ctx.accounts;
+        deposit_wrapped_domain(
+            DepositDomainAccounts {
+                owner: &accounts.owner,
+                vault: &mut accounts.vault,
+                config: &accounts.config,
+                treasury: &mut accounts.treasury,
+                stats: &mut accounts.stats,
+                vault_summary: &accounts.vault_summary,
+                user_points: &mut accounts.user_points,
+                domain_mint: &accounts.domain_mint,
+                name_account: &accounts.name_account,
+                mint_policy: &accounts.mint_policy,
+                user_token_account: &accounts.user_token_account,
+                vault_token_account: &accounts.vault_token_account,
+                domain_entry: &mut accounts.domain_entry,
+                token_program: &accounts.token_program,
+                system_program: &accounts.system_program,
+            },
+            points_bump,
+            entry_bump,
+            memo_hash,
+        )
+    }
+
+    /// `deposit_domain` without account creation besides the entry, for a predictable size and
+    /// CU profile when bundled with other instructions
+    /// The vault ATA and points ledger must already exist (see `prepare_deposit`)
+    pub fn deposit_domain_thin(
+        ctx: Context<DepositDomainThin>,
+        memo_hash: Option<[u8; 32]>,
+    ) -> Result<()> {
+        let points_bump = ctx.accounts.user_points.bump;
+        let entry_bump = ctx.bumps.domain_entry;
+        let accounts = ctx.accounts;
+        deposit_wrapped_domain(
+            DepositDomainAccounts {
+                owner: &accounts.owner,
+                vault: &mut accounts.vault,
+                config: &accounts.config,
+                treasury: &mut accounts.treasury,
+                stats: &mut accounts.stats,
+                vault_summary: &accounts.vault_summary,
+                user_points: &mut accounts.user_points,
+                domain_mint: &accounts.domain_mint,
+                name_account: &accounts.name_account,
+                mint_policy: &accounts.mint_policy,
+                user_token_account: &accounts.user_token_account,
+                vault_token_account: &accounts.vault_token_account,
+                domain_entry: &mut accounts.domain_entry,
+                token_program: &accounts.token_program,
+                system_program: &accounts.system_program,
+            },
+            points_bump,
+            entry_bump,
+            memo_hash,
+        )
+    }
+
+    /// Withdraw an SNS domain from the user's vault
+    /// Only the original owner can withdraw their domains
+    pub fn withdraw_domain(
+        ctx: Context<WithdrawDomain>,
+        memo_hash: Option<[u8; 32]>,
+        confirmation_phrase: Option<String>,
+    ) -> Result<()> {
+        let points_bump = ctx.bumps.user_points;
+        let accounts = ctx.accounts;
+        withdraw_wrapped_domain(
+            WithdrawDomainAccounts {
+                owner: &accounts.owner,
+                vault: &mut accounts.vault,
+                config: accounts.config.as_ref(),
+                stats: accounts.stats.as_mut(),
+                vault_summary: &accounts.vault_summary,
+                outflow_breaker: &accounts.outflow_breaker,
+                security_log: &accounts.security_log,
+                user_points: &mut accounts.user_points,
+                domain_mint: &accounts.domain_mint,
+                vault_token_account: &accounts.vault_token_account,
+                user_token_account: &accounts.user_token_account,
+                domain_entry: &accounts.domain_entry,
+                token_program: &accounts.token_program,
+                withdrawal_phrase: &accounts.withdrawal_phrase,
+                co_signer: accounts.co_signer.as_ref(),
+            },
+            points_bump,
+            memo_hash,
+            confirmation_phrase,
+        )
+    }
+
+    /// `withdraw_domain` without account creation, for a predictable size and CU profile
+    /// The owner's token account and points ledger must already exist
+    pub fn withdraw_domain_thin(
+        ctx: Context<WithdrawDomainThin>,
+        memo_hash: Option<[u8; 32]>,
+        confirmation_phrase: Option<String>,
+    ) -> Result<()> {
+        let points_bump = ctx.accounts.user_points.bump;
+        let accounts = ctx.accounts;
+        withdraw_wrapped_domain(
+            WithdrawDomainAccounts {
+                owner: &accounts.owner,
+                vault: &mut accounts.vault,
+                config: accounts.config.as_ref(),
+                stats: accounts.stats.as_mut(),
+                vault_summary: &accounts.vault_summary,
+                outflow_breaker: &accounts.outflow_breaker,
+                security_log: &accounts.security_log,
+                user_points: &mut accounts.user_points,
+                domain_mint: &accounts.domain_mint,
+                vault_token_account: &accounts.vault_token_account,
+                user_token_account: &accounts.user_token_account,
+                domain_entry: &accounts.domain_entry,
+                token_program: &accounts.token_program,
+                withdrawal_phrase: &accounts.withdrawal_phrase,
+                co_signer: accounts.co_signer.as_ref(),
+            },
+            points_bump,
+            memo_hash,
+            confirmation_phrase,
+        )
+    }
+
     /// Deposit an unwrapped SNS domain into the user's vault
     /// Transfers name registry ownership to the vault PDA
-    pub fn deposit_unwrapped_domain(ctx: Context<DepositUnwrappedDomain>) -> Result<()> {
+    pub fn deposit_unwrapped_domain(
+        ctx: Context<DepositUnwrappedDomain>,
+        memo_hash: Option<[u8; 32]>,
+    ) -> Result<()> {
+        require!(!ctx.accounts.config.paused, VaultError::ProtocolPaused);
+        check_sol_domain_registry(&ctx.accounts.name_account.try_borrow_data()?)?;
+        check_cpi_program(&ctx.accounts.config, ctx.accounts.name_service_program.key)?;
+        charge_fee(
+            &ctx.accounts.config,
+            FeeOperation::Deposit,
+            &ctx.accounts.owner,
+            &mut ctx.accounts.treasury,
+            &ctx.accounts.system_program,
+        )?;
+        credit_points(
+            &mut ctx.accounts.user_points,
+            Some(&ctx.accounts.config),
+            &ctx.accounts.vault,
+            ctx.bumps.user_points,
+        )?;
+
         let vault = &ctx.accounts.vault;
         
         // Build instruction data: 1 byte instruction index + 32 bytes new owner pubkey
@@ -130,18 +697,39 @@ pub mod solana_program {
         };
 
         // Execute the CPI call
-        anchor_lang::solana_program::program::invoke(
+        invoke_sns(
             &transfer_ix,
             &[
                 ctx.accounts.name_account.to_account_info(),
                 ctx.accounts.owner.to_account_info(),
                 ctx.accounts.name_service_program.to_account_info(),
             ],
+            &[],
+            SnsCpiStep::Transfer,
+            ctx.accounts.name_account.key(),
+        )?;
+
+        init_domain_entry(
+            &mut ctx.accounts.domain_entry,
+            ctx.accounts.vault.key(),
+            ctx.accounts.name_account.key(),
+            DomainKind::Unwrapped,
+            ctx.bumps.domain_entry,
+            ctx.accounts.owner.key(),
         )?;
 
         // Update domains count
         let vault = &mut ctx.accounts.vault;
         vault.domains_count = vault.domains_count.checked_add(1).unwrap();
+        update_vault_summary(&ctx.accounts.vault_summary, 0, 1)?;
+        record_stats(&mut ctx.accounts.stats, StatsCounter::DepositUnwrappedDomain, 1);
+        ctx.accounts.domain_entry.memo_hash = memo_hash.unwrap_or_default();
+        emit!(DomainDeposited {
+            vault: vault.key(),
+            domain: ctx.accounts.domain_entry.domain,
+            kind: ctx.accounts.domain_entry.kind,
+            memo_hash,
+        });
 
         msg!(
             "Unwrapped domain {} deposited to vault. Total domains: {}",
@@ -161,9 +749,52 @@ pub mod solana_program {
         Ok(())
     }
 
+    /// Pre-create the accounts an upcoming deposit of `domain_mint` would otherwise create,
+    /// keeping the owner's deposit transaction small
+    /// Permissionless: any payer may fund the vault ATA and the owner's points ledger. The
+    /// domain entry is still created by the deposit itself, so a prepared vault never looks
+    /// like it holds a domain it doesn't
+    pub fn prepare_deposit(ctx: Context<PrepareDeposit>) -> Result<()> {
+        check_vault_destination_account(
+            &ctx.accounts.vault_token_account,
+            &ctx.accounts.domain_mint.key(),
+            &ctx.accounts.vault.key(),
+        )?;
+        ctx.accounts.user_points.bump = ctx.bumps.user_points;
+
+        msg!(
+            "Deposit of {} into vault {} prepared by {}",
+            ctx.accounts.domain_mint.key(),
+            ctx.accounts.vault.key(),
+            ctx.accounts.payer.key()
+        );
+        Ok(())
+    }
+
     /// Withdraw an unwrapped SNS domain from the user's vault
     /// Transfers name registry ownership back to the user
-    pub fn withdraw_unwrapped_domain(ctx: Context<WithdrawUnwrappedDomain>) -> Result<()> {
+    pub fn withdraw_unwrapped_domain(
+        ctx: Context<WithdrawUnwrappedDomain>,
+        memo_hash: Option<[u8; 32]>,
+        confirmation_phrase: Option<String>,
+    ) -> Result<()> {
+        check_co_signer(&ctx.accounts.vault, ctx.accounts.co_signer.as_ref())?;
+        check_withdrawal_phrase(&ctx.accounts.withdrawal_phrase, confirmation_phrase.as_ref())?;
+        record_outflow(&ctx.accounts.outflow_breaker, &ctx.accounts.security_log, 1, true)?;
+        if let Some(config) = &ctx.accounts.config {
+            check_cpi_program(config, ctx.accounts.name_service_program.key)?;
+        }
+        require!(
+            ctx.accounts.domain_entry.kind == DomainKind::Unwrapped,
+            VaultError::UnsupportedDomainKind
+        );
+        credit_points(
+            &mut ctx.accounts.user_points,
+            ctx.accounts.config.as_deref(),
+            &ctx.accounts.vault,
+            ctx.bumps.user_points,
+        )?;
+
         let vault = &ctx.accounts.vault;
 
         // Verify the vault has domains
@@ -197,7 +828,7 @@ pub mod solana_program {
         };
 
         // Execute the CPI call with PDA signer
-        invoke_signed(
+        invoke_sns(
             &transfer_ix,
             &[
                 ctx.accounts.name_account.to_account_info(),
@@ -205,11 +836,24 @@ pub mod solana_program {
                 ctx.accounts.name_service_program.to_account_info(),
             ],
             &[signer_seeds],
+            SnsCpiStep::Transfer,
+            ctx.accounts.name_account.key(),
         )?;
 
         // Update domains count
         let vault = &mut ctx.accounts.vault;
         vault.domains_count = vault.domains_count.checked_sub(1).unwrap();
+        update_vault_summary(&ctx.accounts.vault_summary, 0, -1)?;
+        if let Some(stats) = &mut ctx.accounts.stats {
+            record_stats(stats, StatsCounter::WithdrawUnwrappedDomain, -1);
+        }
+        emit!(DomainWithdrawn {
+            vault: vault.key(),
+            domain: ctx.accounts.domain_entry.domain,
+            kind: ctx.accounts.domain_entry.kind,
+            memo_hash,
+            confirmation_phrase: confirmation_phrase.clone(),
+        });
 
         msg!(
             "Unwrapped domain {} withdrawn from vault. Remaining domains: {}",
@@ -224,7 +868,35 @@ pub mod solana_program {
     /// 1. Transfers domain ownership to the vault PDA  
     /// 2. Creates/updates the SOL record V2 with the vault PDA address
     /// 3. Writes ROA (Right of Association) to verify the record
-    pub fn deposit_domain_with_record(ctx: Context<DepositDomainWithRecord>) -> Result<()> {
+    pub fn deposit_domain_with_record(
+        ctx: Context<DepositDomainWithRecord>,
+        memo_hash: Option<[u8; 32]>,
+    ) -> Result<()> {
+        require!(!ctx.accounts.config.paused, VaultError::ProtocolPaused);
+        check_sol_domain_registry(&ctx.accounts.name_account.try_borrow_data()?)?;
+        check_cpi_program(&ctx.accounts.config, ctx.accounts.name_service_program.key)?;
+        check_cpi_program(&ctx.accounts.config, ctx.accounts.sns_records_program.key)?;
+        charge_fee(
+            &ctx.accounts.config,
+            FeeOperation::Deposit,
+            &ctx.accounts.owner,
+            &mut ctx.accounts.treasury,
+            &ctx.accounts.system_program,
+        )?;
+        charge_fee(
+            &ctx.accounts.config,
+            FeeOperation::RecordUpdate,
+            &ctx.accounts.owner,
+            &mut ctx.accounts.treasury,
+            &ctx.accounts.system_program,
+        )?;
+        credit_points(
+            &mut ctx.accounts.user_points,
+            Some(&ctx.accounts.config),
+            &ctx.accounts.vault,
+            ctx.bumps.user_points,
+        )?;
+
         let vault = &ctx.accounts.vault;
         let owner_key = ctx.accounts.owner.key();
         let bump = vault.bump;
@@ -253,13 +925,16 @@ pub mod solana_program {
             data: transfer_data,
         };
 
-        anchor_lang::solana_program::program::invoke(
+        invoke_sns(
             &transfer_ix,
             &[
                 ctx.accounts.name_account.to_account_info(),
                 ctx.accounts.owner.to_account_info(),
                 ctx.accounts.name_service_program.to_account_info(),
             ],
+            &[],
+            SnsCpiStep::Transfer,
+            ctx.accounts.name_account.key(),
         )?;
 
         msg!("Domain ownership transferred to vault PDA");
@@ -322,7 +997,7 @@ pub mod solana_program {
             data: allocate_data,
         };
 
-        invoke_signed(
+        invoke_sns(
             &allocate_ix,
             &[
                 ctx.accounts.system_program.to_account_info(),
@@ -335,6 +1010,8 @@ pub mod solana_program {
                 ctx.accounts.sns_records_program.to_account_info(), // Program being invoked
             ],
             &[signer_seeds],
+            SnsCpiStep::Allocate,
+            ctx.accounts.sol_record_v2.key(),
         )?;
 
         msg!("SOL record V2 created with vault PDA address: {}", vault_key);
@@ -387,7 +1064,7 @@ pub mod solana_program {
             data: roa_data,
         };
 
-        invoke_signed(
+        invoke_sns(
             &write_roa_ix,
             &[
                 ctx.accounts.system_program.to_account_info(),
@@ -400,6 +1077,8 @@ pub mod solana_program {
                 ctx.accounts.sns_records_program.to_account_info(),
             ],
             &[signer_seeds],
+            SnsCpiStep::WriteRoa,
+            ctx.accounts.sol_record_v2.key(),
         )?;
 
         msg!("ROA written with vault PDA as roaId");
@@ -459,7 +1138,7 @@ pub mod solana_program {
             data: validate_data,
         };
 
-        invoke_signed(
+        invoke_sns(
             &validate_ix,
             &[
                 ctx.accounts.system_program.to_account_info(),
@@ -473,14 +1152,34 @@ pub mod solana_program {
                 ctx.accounts.sns_records_program.to_account_info(),
             ],
             &[signer_seeds],
+            SnsCpiStep::Validate,
+            ctx.accounts.sol_record_v2.key(),
         )?;
 
         msg!("SOL record verified - ROA validation upgraded to Solana(1)");
 
+        init_domain_entry(
+            &mut ctx.accounts.domain_entry,
+            ctx.accounts.vault.key(),
+            name_account_key,
+            DomainKind::Unwrapped,
+            ctx.bumps.domain_entry,
+            ctx.accounts.owner.key(),
+        )?;
+
         // Update domains count
         let vault = &mut ctx.accounts.vault;
         vault.domains_count = vault.domains_count.checked_add(1).unwrap();
+        update_vault_summary(&ctx.accounts.vault_summary, 0, 1)?;
         let domains_count = vault.domains_count;
+        record_stats(&mut ctx.accounts.stats, StatsCounter::DepositDomainWithRecord, 1);
+        ctx.accounts.domain_entry.memo_hash = memo_hash.unwrap_or_default();
+        emit!(DomainDeposited {
+            vault: vault.key(),
+            domain: ctx.accounts.domain_entry.domain,
+            kind: ctx.accounts.domain_entry.kind,
+            memo_hash,
+        });
 
         msg!(
             "Domain {} secured with verified SOL record pointing to vault {}. Total domains: {}",
@@ -491,163 +1190,9279 @@ pub mod solana_program {
         
         Ok(())
     }
-}
 
-/// Pre-computed sha256 hash of "SPL Name Service" + "\x02SOL"
-/// This is used for SOL Record V2 PDA derivation
-/// sha256("SPL Name Service\x02SOL") = 30ecde95b64ef547d89fde3987039f70b53937a8ffbcc10a285b826fdfa076bd
-pub const SOL_RECORD_V2_HASHED_NAME: [u8; 32] = [
-    0x30, 0xec, 0xde, 0x95, 0xb6, 0x4e, 0xf5, 0x47,
-    0xd8, 0x9f, 0xde, 0x39, 0x87, 0x03, 0x9f, 0x70,
-    0xb5, 0x39, 0x37, 0xa8, 0xff, 0xbc, 0xc1, 0x0a,
-    0x28, 0x5b, 0x82, 0x6f, 0xdf, 0xa0, 0x76, 0xbd
-];
+    /// Register the entry of a wrapped domain deposited before domain entries existed
+    pub fn register_wrapped_domain_entry(ctx: Context<RegisterWrappedDomainEntry>) -> Result<()> {
+        require!(
+            ctx.accounts.vault_token_account.amount == 1,
+            VaultError::DomainNotInVault
+        );
+        check_wrapped_domain(&ctx.accounts.domain_mint.key(), &ctx.accounts.name_account)?;
 
-/// Helper function to derive the SOL record V2 PDA for a domain
-/// Uses SNS SDK derivation: findProgramAddressSync([hashedName, centralState, domainKey], NAME_PROGRAM_ID)
-pub fn get_sol_record_v2_key(domain_name_account: &Pubkey) -> (Pubkey, u8) {
-    let (central_state, _) = get_central_state_key();
-    
-    // Record V2 derivation uses NAME_PROGRAM_ID (not SNS_RECORDS_PROGRAM_ID!)
-    Pubkey::find_program_address(
-        &[
-            &SOL_RECORD_V2_HASHED_NAME,
-            central_state.as_ref(),
-            domain_name_account.as_ref(),
-        ],
-        &NAME_SERVICE_PROGRAM_ID,
-    )
-}
+        init_domain_entry(
+            &mut ctx.accounts.domain_entry,
+            ctx.accounts.vault.key(),
+            ctx.accounts.domain_mint.key(),
+            wrapped_domain_kind(ctx.accounts.token_program.key),
+            ctx.bumps.domain_entry,
+            ctx.accounts.owner.key(),
+        )?;
 
-/// Helper function to get the SNS Records V2 central state PDA
-/// Central state is derived using the program ID itself as seed
-pub fn get_central_state_key() -> (Pubkey, u8) {
-    Pubkey::find_program_address(
-        &[SNS_RECORDS_PROGRAM_ID.as_ref()],
-        &SNS_RECORDS_PROGRAM_ID,
-    )
-}
+        msg!("Entry registered for wrapped domain {}", ctx.accounts.domain_mint.key());
+        Ok(())
+    }
 
-/// User's vault account that stores metadata
-#[account]
-#[derive(InitSpace)]
-pub struct UserVault {
-    /// The owner of this vault (user's public key)
-    pub owner: Pubkey,
-    /// PDA bump seed for signing
-    pub bump: u8,
-    /// Number of domains currently in the vault
-    pub domains_count: u64,
-}
+    /// Register the entry of an unwrapped domain deposited before domain entries existed
+    pub fn register_unwrapped_domain_entry(ctx: Context<RegisterUnwrappedDomainEntry>) -> Result<()> {
+        // Name registry layout: parent_name (32) + owner (32) + class (32) + data
+        {
+            let data = ctx.accounts.name_account.try_borrow_data()?;
+            require!(
+                data.len() >= 96 && data[32..64] == ctx.accounts.vault.key().to_bytes(),
+                VaultError::DomainNotInVault
+            );
+            check_sol_domain_registry(&data)?;
+        }
 
-#[derive(Accounts)]
-pub struct InitializeVault<'info> {
-    /// The user initializing their vault
-    #[account(mut)]
-    pub owner: Signer<'info>,
+        init_domain_entry(
+            &mut ctx.accounts.domain_entry,
+            ctx.accounts.vault.key(),
+            ctx.accounts.name_account.key(),
+            DomainKind::Unwrapped,
+            ctx.bumps.domain_entry,
+            ctx.accounts.owner.key(),
+        )?;
 
-    /// The vault PDA - derived from the owner's public key
-    #[account(
-        init,
-        payer = owner,
-        space = 8 + UserVault::INIT_SPACE,
-        seeds = [VAULT_SEED, owner.key().as_ref()],
-        bump
-    )]
-    pub vault: Account<'info, UserVault>,
+        msg!("Entry registered for unwrapped domain {}", ctx.accounts.name_account.key());
+        Ok(())
+    }
 
-    pub system_program: Program<'info, System>,
-}
+    /// Stake a vaulted domain to accrue rewards from the reward pool
+    /// Staked domains can't be withdrawn until unstaked
+    pub fn stake_domain(ctx: Context<UpdateDomainEntry>) -> Result<()> {
+        let domain_entry = &mut ctx.accounts.domain_entry;
+        require!(!domain_entry.staked, VaultError::DomainStaked);
 
-#[derive(Accounts)]
-pub struct DepositDomain<'info> {
-    /// The owner depositing a domain
-    #[account(mut)]
-    pub owner: Signer<'info>,
+        domain_entry.staked = true;
+        domain_entry.reward_checkpoint = Clock::get()?.unix_timestamp;
+        domain_entry.unstake_requested_at = 0;
+        domain_entry.unstake_available_at = 0;
 
-    /// The user's vault
-    #[account(
-        mut,
-        seeds = [VAULT_SEED, owner.key().as_ref()],
-        bump = vault.bump,
-        has_one = owner @ VaultError::UnauthorizedAccess
-    )]
-    pub vault: Account<'info, UserVault>,
+        msg!("Domain {} staked", domain_entry.domain);
+        Ok(())
+    }
+
+    /// Request to unstake a domain; rewards stop accruing and the cooldown starts
+    pub fn request_unstake(ctx: Context<RequestUnstake>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let domain_entry = &mut ctx.accounts.domain_entry;
+        require!(domain_entry.staked, VaultError::DomainNotStaked);
+        require!(
+            domain_entry.unstake_requested_at == 0,
+            VaultError::UnstakeAlreadyRequested
+        );
+
+        settle_rewards(domain_entry, &ctx.accounts.config, now);
+        domain_entry.unstake_requested_at = now;
+        domain_entry.unstake_available_at = now
+            .checked_add(ctx.accounts.config.staking.unstake_cooldown_seconds)
+            .unwrap();
+
+        msg!(
+            "Unstake requested for domain {}, available at {}",
+            domain_entry.domain,
+            domain_entry.unstake_available_at
+        );
+        Ok(())
+    }
+
+    /// Complete unstaking once the cooldown has elapsed
+    /// Unclaimed rewards stay claimable until the domain is withdrawn
+    pub fn unstake_domain(ctx: Context<UpdateDomainEntry>) -> Result<()> {
+        let domain_entry = &mut ctx.accounts.domain_entry;
+        require!(
+            domain_entry.staked && domain_entry.unstake_requested_at > 0,
+            VaultError::DomainNotStaked
+        );
+        require!(
+            Clock::get()?.unix_timestamp >= domain_entry.unstake_available_at,
+            VaultError::UnstakeCooldownActive
+        );
+
+        domain_entry.staked = false;
+        domain_entry.unstake_requested_at = 0;
+        domain_entry.unstake_available_at = 0;
+
+        msg!("Domain {} unstaked", domain_entry.domain);
+        Ok(())
+    }
+
+    /// Claim staking rewards of a domain from the reward pool
+    pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let domain_entry = &mut ctx.accounts.domain_entry;
+        settle_rewards(domain_entry, &ctx.accounts.config, now);
+
+        let amount = domain_entry.unclaimed_rewards;
+        require!(amount > 0, VaultError::NoRewards);
+
+        let reward_pool_info = ctx.accounts.reward_pool.to_account_info();
+        let rent_exempt_minimum = Rent::get()?.minimum_balance(reward_pool_info.data_len());
+        let available = reward_pool_info
+            .lamports()
+            .saturating_sub(rent_exempt_minimum);
+        require!(amount <= available, VaultError::RewardPoolDepleted);
+
+        domain_entry.unclaimed_rewards = 0;
+        ctx.accounts.reward_pool.sub_lamports(amount)?;
+        ctx.accounts.owner.add_lamports(amount)?;
+
+        msg!(
+            "Claimed {} lamports of rewards for domain {}",
+            amount,
+            ctx.accounts.domain_entry.domain
+        );
+        Ok(())
+    }
+
+    /// Create the staking reward pool
+    pub fn initialize_reward_pool(ctx: Context<InitializeRewardPool>) -> Result<()> {
+        let reward_pool = &mut ctx.accounts.reward_pool;
+        reward_pool.total_funded = 0;
+        reward_pool.bump = ctx.bumps.reward_pool;
+
+        msg!("Reward pool initialized: {}", reward_pool.key());
+        Ok(())
+    }
+
+    /// Fund the staking reward pool with lamports (anyone can fund it)
+    pub fn fund_reward_pool(ctx: Context<FundRewardPool>, amount: u64) -> Result<()> {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.funder.to_account_info(),
+                    to: ctx.accounts.reward_pool.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let reward_pool = &mut ctx.accounts.reward_pool;
+        reward_pool.total_funded = reward_pool.total_funded.checked_add(amount).unwrap();
+
+        msg!("Reward pool funded with {} lamports", amount);
+        Ok(())
+    }
+
+    /// Claim an airdrop owed to the vault by CPI-ing into an allowlisted claim program
+    /// The vault PDA signs wherever it appears in the remaining accounts, which are forwarded as-is.
+    /// Vault-owned token and name accounts passed along must not lose assets during the call.
+    pub fn claim_with_vault_signature<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimWithVaultSignature<'info>>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        let claim_program = ctx.accounts.claim_program.key();
+        require!(
+            ctx.accounts.config.claim_programs.contains(&claim_program)
+                && !is_protected_program(&claim_program),
+            VaultError::ClaimProgramNotAllowed
+        );
+
+        let vault_key = ctx.accounts.vault.key();
+        let owner_key = ctx.accounts.owner.key();
+        let bump = ctx.accounts.vault.bump;
+        let signer_seeds: &[&[u8]] = &[VAULT_SEED, owner_key.as_ref(), &[bump]];
+
+        let remaining_accounts = ctx.remaining_accounts;
+        let before = snapshot_vault_assets(&vault_key, remaining_accounts)?;
+
+        // Persist the reentrancy guard before handing control to the external program
+        ctx.accounts.vault.in_flight = true;
+        ctx.accounts.vault.exit(&crate::ID)?;
+
+        let claim_ix = anchor_lang::solana_program::instruction::Instruction {
+            program_id: claim_program,
+            accounts: remaining_accounts
+                .iter()
+                .map(|account| anchor_lang::solana_program::instruction::AccountMeta {
+                    pubkey: account.key(),
+                    is_signer: account.is_signer || account.key() == vault_key,
+                    is_writable: account.is_writable,
+                })
+                .collect(),
+            data,
+        };
+
+        let mut account_infos = remaining_accounts.to_vec();
+        account_infos.push(ctx.accounts.claim_program.to_account_info());
+        invoke_signed(&claim_ix, &account_infos, &[signer_seeds])?;
+
+        ctx.accounts.vault.in_flight = false;
+
+        for (index, amount_before, authorities_before) in before {
+            let account = &remaining_accounts[index];
+            let amount_after = vault_asset_amount(&vault_key, account)?;
+            require!(
+                amount_after >= amount_before,
+                VaultError::VaultAssetsDecreased
+            );
+            require!(
+                token_account_authorities(account)? == authorities_before,
+                VaultError::VaultAssetAuthorityChanged
+            );
+        }
+
+        msg!("Claim executed through program {} for vault {}", claim_program, vault_key);
+        Ok(())
+    }
+
+    /// Quarantine an unsolicited token account of the vault
+    /// Quarantined accounts are hidden from vault holdings and can be burned and closed
+    pub fn quarantine_token_account(ctx: Context<QuarantineTokenAccount>) -> Result<()> {
+        // Never quarantine a vaulted domain, including ones deposited before entries existed
+        require!(
+            ctx.accounts.domain_entry.data_is_empty()
+                && !may_hold_domain(&ctx.accounts.vault_token_account, &ctx.accounts.token_mint),
+            VaultError::CannotQuarantineDomain
+        );
+
+        let quarantine = &mut ctx.accounts.quarantine;
+        quarantine.vault = ctx.accounts.vault.key();
+        quarantine.token_account = ctx.accounts.vault_token_account.key();
+        quarantine.mint = ctx.accounts.token_mint.key();
+        quarantine.quarantined_at = Clock::get()?.unix_timestamp;
+        quarantine.bump = ctx.bumps.quarantine;
+        log_security_event(
+            &ctx.accounts.security_log,
+            SecurityEventKind::TokenAccountQuarantined,
+            SecuritySeverity::Warning,
+            quarantine.token_account,
+        )?;
+
+        msg!(
+            "Token account {} (mint {}) quarantined",
+            quarantine.token_account,
+            quarantine.mint
+        );
+        Ok(())
+    }
+
+    /// Lift the quarantine of a vault token account
+    pub fn release_token_account(ctx: Context<ReleaseTokenAccount>) -> Result<()> {
+        msg!(
+            "Token account {} released from quarantine",
+            ctx.accounts.quarantine.token_account
+        );
+        Ok(())
+    }
+
+    /// Strip any delegate or close authority from a vault token account
+    /// Permissionless: it only ever removes authorities, so anyone may sanitize a vault
+    pub fn sanitize_vault_token_account(ctx: Context<SanitizeVaultTokenAccount>) -> Result<()> {
+        check_cpi_program(&ctx.accounts.config, ctx.accounts.token_program.key)?;
+
+        let owner_key = ctx.accounts.vault.owner;
+        let bump = ctx.accounts.vault.bump;
+        clear_vault_token_authorities(
+            &ctx.accounts.vault_token_account,
+            &ctx.accounts.vault.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            &[VAULT_SEED, owner_key.as_ref(), &[bump]],
+        )?;
+
+        msg!("Sanitized vault token account {}", ctx.accounts.vault_token_account.key());
+        Ok(())
+    }
+
+    /// Burn the whole balance of a quarantined vault token account
+    pub fn burn_dust(ctx: Context<BurnDust>) -> Result<()> {
+        check_cpi_program(&ctx.accounts.config, ctx.accounts.token_program.key)?;
+        let amount = ctx.accounts.vault_token_account.amount;
+        require!(amount > 0, VaultError::NothingToBurn);
+        // Also covers accounts quarantined before this check existed
+        require!(
+            !may_hold_domain(&ctx.accounts.vault_token_account, &ctx.accounts.token_mint),
+            VaultError::CannotQuarantineDomain
+        );
+
+        let owner_key = ctx.accounts.owner.key();
+        let bump = ctx.accounts.vault.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, owner_key.as_ref(), &[bump]]];
+
+        let cpi_accounts = Burn {
+            mint: ctx.accounts.token_mint.to_account_info(),
+            from: ctx.accounts.vault_token_account.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        burn(
+            CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds),
+            amount,
+        )?;
+
+        msg!(
+            "Burned {} dust tokens from {}",
+            amount,
+            ctx.accounts.vault_token_account.key()
+        );
+        Ok(())
+    }
+
+    /// Close an empty quarantined vault token account, returning its rent to the owner
+    pub fn close_dust(ctx: Context<CloseDust>) -> Result<()> {
+        check_cpi_program(&ctx.accounts.config, ctx.accounts.token_program.key)?;
+        let owner_key = ctx.accounts.owner.key();
+        let bump = ctx.accounts.vault.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, owner_key.as_ref(), &[bump]]];
+
+        let cpi_accounts = CloseAccount {
+            account: ctx.accounts.vault_token_account.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        close_account(CpiContext::new_with_signer(
+            cpi_program,
+            cpi_accounts,
+            signer_seeds,
+        ))?;
+
+        msg!(
+            "Closed quarantined token account {}",
+            ctx.accounts.vault_token_account.key()
+        );
+        Ok(())
+    }
+
+    /// Create or update the vault's auto-sweep policy
+    /// Balances of allowed mints at or above their own threshold can be split between the
+    /// destinations by anyone; thresholds are per mint since mints differ in decimals
+    pub fn set_sweep_policy(
+        ctx: Context<SetSweepPolicy>,
+        destinations: Vec<SweepDestination>,
+        allowed_mints: Vec<SweepMint>,
+    ) -> Result<()> {
+        require!(
+            allowed_mints.len() <= MAX_SWEEP_MINTS,
+            VaultError::TooManySweepMints
+        );
+        require!(
+            allowed_mints.iter().all(|m| m.threshold > 0),
+            VaultError::InvalidSweepThreshold
+        );
+        require!(
+            allowed_mints
+                .iter()
+                .enumerate()
+                .all(|(i, m)| allowed_mints[..i].iter().all(|other| other.mint != m.mint)),
+            VaultError::InvalidSweepMints
+        );
+        require!(
+            !destinations.is_empty() && destinations.len() <= MAX_SWEEP_DESTINATIONS,
+            VaultError::InvalidSweepDestinations
+        );
+        require!(
+            destinations.iter().all(|d| d.weight_bps > 0),
+            VaultError::InvalidSweepDestinations
+        );
+        let total_weight: u32 = destinations.iter().map(|d| d.weight_bps as u32).sum();
+        require!(
+            total_weight == SWEEP_TOTAL_WEIGHT_BPS as u32,
+            VaultError::InvalidSweepDestinations
+        );
+
+        let sweep_policy = &mut ctx.accounts.sweep_policy;
+        sweep_policy.vault = ctx.accounts.vault.key();
+        sweep_policy.destinations = destinations;
+        sweep_policy.allowed_mints = allowed_mints;
+        sweep_policy.bump = ctx.bumps.sweep_policy;
+
+        msg!(
+            "Sweep policy set: {} mints to {} destinations",
+            sweep_policy.allowed_mints.len(),
+            sweep_policy.destinations.len()
+        );
+        Ok(())
+    }
+
+    /// Remove the vault's auto-sweep policy
+    pub fn remove_sweep_policy(_ctx: Context<RemoveSweepPolicy>) -> Result<()> {
+        msg!("Sweep policy removed");
+        Ok(())
+    }
+
+    /// Make the sweep crank forward fixed denominations of `mint` after randomized delays
+    /// `denominations` must be strictly increasing; `max_delay_seconds` bounds the delay
+    /// Only `keeper` may release buckets: whoever cranks picks the slot the randomness derives
+    /// from, so the keeper (and the slot leader) can steer it; observers can't
+    pub fn set_sweep_buckets(
+        ctx: Context<SetSweepBuckets>,
+        mint: Pubkey,
+        denominations: Vec<u64>,
+        max_delay_seconds: u32,
+        keeper: Pubkey,
+    ) -> Result<()> {
+        require!(
+            !denominations.is_empty()
+                && denominations.len() <= MAX_SWEEP_DENOMINATIONS
+                && denominations[0] > 0
+                && denominations.windows(2).all(|w| w[0] < w[1]),
+            VaultError::InvalidSweepBuckets
+        );
+        require!(
+            max_delay_seconds <= MAX_SWEEP_BUCKET_DELAY_SECONDS,
+            VaultError::InvalidSweepBuckets
+        );
+
+        // next_release_at and releases survive updates, so reconfiguring can't skip a delay
+        let sweep_buckets = &mut ctx.accounts.sweep_buckets;
+        sweep_buckets.vault = ctx.accounts.vault.key();
+        sweep_buckets.mint = mint;
+        sweep_buckets.keeper = keeper;
+        sweep_buckets.denominations = denominations;
+        sweep_buckets.max_delay_seconds = max_delay_seconds;
+        sweep_buckets.bump = ctx.bumps.sweep_buckets;
+
+        msg!(
+            "Sweep buckets for {} set: {} denominations, up to {}s apart",
+            mint,
+            sweep_buckets.denominations.len(),
+            max_delay_seconds
+        );
+        Ok(())
+    }
+
+    /// Remove a mint's sweep buckets, returning it to plain weighted sweeps
+    pub fn remove_sweep_buckets(_ctx: Context<RemoveSweepBuckets>) -> Result<()> {
+        msg!("Sweep buckets removed");
+        Ok(())
+    }
+
+    /// Split a vault token balance between the sweep destinations once it reaches the mint threshold
+    /// Permissionless crank; never touches vaulted domains or quarantined accounts
+    /// With sweep buckets set for the mint, one denomination goes to one weighted-random
+    /// destination instead, and only the buckets' keeper may crank
+    /// remaining_accounts: one token account per policy destination, in policy order
+    pub fn auto_sweep<'info>(ctx: Context<'_, '_, 'info, 'info, AutoSweep<'info>>) -> Result<()> {
+        check_cpi_program(&ctx.accounts.config, ctx.accounts.token_program.key)?;
+        let sweep_policy = &ctx.accounts.sweep_policy;
+        let mint_key = ctx.accounts.token_mint.key();
+        let threshold = sweep_policy
+            .allowed_mints
+            .iter()
+            .find(|m| m.mint == mint_key)
+            .map(|m| m.threshold)
+            .ok_or(VaultError::MintNotSweepable)?;
+        require!(
+            ctx.accounts.domain_entry.data_is_empty(),
+            VaultError::MintNotSweepable
+        );
+        require!(
+            ctx.accounts.quarantine.data_is_empty(),
+            VaultError::MintNotSweepable
+        );
+
+        let balance = ctx.accounts.vault_token_account.amount;
+        require!(balance >= threshold, VaultError::SweepThresholdNotMet);
+
+        require!(
+            ctx.remaining_accounts.len() == sweep_policy.destinations.len(),
+            VaultError::InvalidSweepDestination
+        );
+
+        let bucket = release_sweep_bucket(
+            &ctx.accounts.sweep_buckets,
+            ctx.accounts.keeper.as_ref(),
+            balance,
+            &sweep_policy.destinations,
+        )?;
+        let amount = bucket.map_or(balance, |(denomination, _)| denomination);
+
+        let owner_key = ctx.accounts.vault.owner;
+        let bump = ctx.accounts.vault.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, owner_key.as_ref(), &[bump]]];
+        let token_program_key = ctx.accounts.token_program.key();
+
+        // Every share but the last is rounded down; the last destination takes the remainder
+        let mut remaining = amount;
+        let last = sweep_policy.destinations.len() - 1;
+        for (i, (destination, account_info)) in sweep_policy
+            .destinations
+            .iter()
+            .zip(ctx.remaining_accounts.iter())
+            .enumerate()
+        {
+            require!(
+                *account_info.owner == token_program_key,
+                VaultError::InvalidSweepDestination
+            );
+            let destination_token_account =
+                InterfaceAccount::<TokenAccount>::try_from(account_info)?;
+            require!(
+                destination_token_account.mint == mint_key
+                    && destination_token_account.owner == destination.wallet,
+                VaultError::InvalidSweepDestination
+            );
+
+            let share = match bucket {
+                Some((_, chosen)) if i == chosen => amount,
+                Some(_) => 0,
+                None if i == last => remaining,
+                None => {
+                    (amount as u128 * destination.weight_bps as u128
+                        / SWEEP_TOTAL_WEIGHT_BPS as u128) as u64
+                }
+            };
+            remaining = remaining.checked_sub(share).unwrap();
+            if share == 0 {
+                continue;
+            }
+
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.vault_token_account.to_account_info(),
+                to: account_info.clone(),
+                authority: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            transfer_checked(
+                CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds),
+                share,
+                ctx.accounts.token_mint.decimals,
+            )?;
+
+            msg!("Swept {} of mint {} to {}", share, mint_key, destination.wallet);
+        }
+
+        Ok(())
+    }
+
+    /// Publish a price for paying a vaulted domain
+    /// `period_seconds` > 0 makes each payment a subscription covering that long
+    pub fn set_payment_endpoint(
+        ctx: Context<SetPaymentEndpoint>,
+        amount: u64,
+        period_seconds: i64,
+        memo_schema: String,
+    ) -> Result<()> {
+        require!(amount > 0, VaultError::InvalidPaymentAmount);
+        require!(period_seconds >= 0, VaultError::InvalidPaymentPeriod);
+        require!(
+            memo_schema.len() <= MAX_MEMO_SCHEMA_LEN,
+            VaultError::MemoTooLong
+        );
+
+        let payment_endpoint = &mut ctx.accounts.payment_endpoint;
+        payment_endpoint.vault = ctx.accounts.vault.key();
+        payment_endpoint.domain = ctx.accounts.domain_entry.domain;
+        payment_endpoint.mint = ctx.accounts.payment_mint.key();
+        payment_endpoint.amount = amount;
+        payment_endpoint.period_seconds = period_seconds;
+        payment_endpoint.memo_schema = memo_schema;
+        payment_endpoint.bump = ctx.bumps.payment_endpoint;
+
+        msg!(
+            "Payment endpoint for {} set: {} of mint {}",
+            payment_endpoint.domain,
+            amount,
+            payment_endpoint.mint
+        );
+        Ok(())
+    }
+
+    /// Remove a domain's payment endpoint
+    pub fn close_payment_endpoint(ctx: Context<ClosePaymentEndpoint>) -> Result<()> {
+        msg!(
+            "Payment endpoint for {} closed",
+            ctx.accounts.payment_endpoint.domain
+        );
+        Ok(())
+    }
+
+    /// Pay a vaulted domain's published price straight into the vault and record a receipt
+    /// Nothing is escrowed: the vault owner can spend the payment as soon as it lands
+    /// `expected_amount`/`expected_mint` pin the price the payer saw, so a racing
+    /// `set_payment_endpoint` can't change what gets charged
+    /// Only the SHA-256 of the memo/invoice id is passed; the memo itself travels off-chain
+    pub fn pay_domain(
+        ctx: Context<PayDomain>,
+        expected_amount: u64,
+        expected_mint: Pubkey,
+        memo_hash: [u8; 32],
+    ) -> Result<()> {
+        require!(!ctx.accounts.config.paused, VaultError::ProtocolPaused);
+        require!(
+            ctx.accounts.payment_endpoint.amount == expected_amount,
+            VaultError::PaymentPriceChanged
+        );
+        require_keys_eq!(
+            ctx.accounts.payment_endpoint.mint,
+            expected_mint,
+            VaultError::PaymentPriceChanged
+        );
+        check_cpi_program(&ctx.accounts.config, ctx.accounts.token_program.key)?;
+        check_token_account_state(
+            &ctx.accounts.vault_token_account,
+            &ctx.accounts.payment_mint.key(),
+            &ctx.accounts.vault.key(),
+        )?;
+
+        let amount = ctx.accounts.payment_endpoint.amount;
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.payer_token_account.to_account_info(),
+            to: ctx.accounts.vault_token_account.to_account_info(),
+            authority: ctx.accounts.payer.to_account_info(),
+            mint: ctx.accounts.payment_mint.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        transfer_checked(
+            CpiContext::new(cpi_program, cpi_accounts),
+            amount,
+            ctx.accounts.payment_mint.decimals,
+        )?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let payment_endpoint = &mut ctx.accounts.payment_endpoint;
+        let receipt_id = payment_endpoint.next_receipt_id;
+        payment_endpoint.next_receipt_id = receipt_id.checked_add(1).unwrap();
+
+        let receipt = &mut ctx.accounts.receipt;
+        receipt.endpoint = payment_endpoint.key();
+        receipt.id = receipt_id;
+        receipt.payer = ctx.accounts.payer.key();
+        receipt.mint = payment_endpoint.mint;
+        receipt.amount = amount;
+        receipt.paid_at = now;
+        receipt.valid_until = if payment_endpoint.period_seconds > 0 {
+            now.checked_add(payment_endpoint.period_seconds).unwrap()
+        } else {
+            0
+        };
+        receipt.memo_hash = memo_hash;
+        receipt.bump = ctx.bumps.receipt;
+
+        record_stats(&mut ctx.accounts.stats, StatsCounter::PayDomain, 0);
+
+        emit!(DomainPaid {
+            endpoint: receipt.endpoint,
+            domain: payment_endpoint.domain,
+            receipt_id,
+            payer: receipt.payer,
+            mint: receipt.mint,
+            amount,
+            valid_until: receipt.valid_until,
+            memo_hash,
+        });
+
+        msg!(
+            "Domain {} paid {} (receipt {})",
+            payment_endpoint.domain,
+            amount,
+            receipt_id
+        );
+        Ok(())
+    }
+
+    /// Close a payment receipt, returning its rent to the payer
+    /// Subscription receipts can only be closed once the period they paid for has ended
+    pub fn close_payment_receipt(ctx: Context<ClosePaymentReceipt>) -> Result<()> {
+        let receipt = &ctx.accounts.receipt;
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            receipt.valid_until == 0 || now >= receipt.valid_until,
+            VaultError::ReceiptStillValid
+        );
+
+        msg!("Receipt {} of {} closed", receipt.id, receipt.endpoint);
+        Ok(())
+    }
+
+    /// Store a hash of the domain's current Record V2 accounts in its entry
+    /// remaining_accounts: the domain's record accounts, sorted by key
+    pub fn snapshot_records(ctx: Context<SnapshotRecords>) -> Result<()> {
+        let name_account = &ctx.accounts.name_account;
+        check_entry_name_account(&ctx.accounts.domain_entry, name_account)?;
+        let records_hash = hash_record_accounts(name_account.key, ctx.remaining_accounts)?;
+
+        let domain_entry = &mut ctx.accounts.domain_entry;
+        domain_entry.records_hash = records_hash;
+        domain_entry.records_snapshot_at = Clock::get()?.unix_timestamp;
+
+        msg!(
+            "Snapshot of {} records taken for {}",
+            ctx.remaining_accounts.len(),
+            domain_entry.domain
+        );
+        Ok(())
+    }
+
+    /// Check the domain's current Record V2 accounts against the last snapshot
+    /// remaining_accounts: the domain's record accounts, sorted by key
+    /// The hash commits to the snapshotted record keys, so the same set must be passed back and a
+    /// changed or deleted record fails; records created after the snapshot can't be enumerated
+    /// on-chain and are not detected
+    pub fn verify_records(ctx: Context<VerifyRecords>) -> Result<()> {
+        let domain_entry = &ctx.accounts.domain_entry;
+        require!(domain_entry.records_snapshot_at != 0, VaultError::NoRecordSnapshot);
+
+        let name_account = &ctx.accounts.name_account;
+        check_entry_name_account(domain_entry, name_account)?;
+        let records_hash = hash_record_accounts(name_account.key, ctx.remaining_accounts)?;
+        require!(
+            records_hash == domain_entry.records_hash,
+            VaultError::RecordsChanged
+        );
+
+        msg!(
+            "Records of {} match the snapshot from {}",
+            domain_entry.domain,
+            domain_entry.records_snapshot_at
+        );
+        Ok(())
+    }
+
+    /// Lock or unlock modification of a vaulted domain's records
+    /// While locked, no instruction (owner, delegate or lease) may change the domain's records
+    pub fn set_records_locked(ctx: Context<UpdateDomainEntry>, locked: bool) -> Result<()> {
+        let domain_entry = &mut ctx.accounts.domain_entry;
+        domain_entry.records_locked = locked;
+
+        msg!(
+            "Records of {} {}",
+            domain_entry.domain,
+            if locked { "locked" } else { "unlocked" }
+        );
+        Ok(())
+    }
+
+    /// Write the Right of Association of one of a vaulted domain's records
+    /// `roa_id` may be any key (e.g. a business's key), which then validates via validate_roa_external
+    pub fn write_record_roa(ctx: Context<WriteRecordRoa>, roa_id: Pubkey) -> Result<()> {
+        require!(!ctx.accounts.config.paused, VaultError::ProtocolPaused);
+        check_cpi_program(&ctx.accounts.config, ctx.accounts.sns_records_program.key)?;
+        let domain_entry = &ctx.accounts.domain_entry;
+        require!(
+            domain_entry.kind == DomainKind::Unwrapped,
+            VaultError::RecordsRequireUnwrappedDomain
+        );
+        require!(!domain_entry.records_locked, VaultError::RecordsLocked);
+        let name_account_key = ctx.accounts.name_account.key();
+        check_entry_name_account(domain_entry, &ctx.accounts.name_account)?;
+        check_record_account(
+            &ctx.accounts.record,
+            &name_account_key,
+            ctx.accounts.central_state.key,
+        )?;
+
+        let owner_key = ctx.accounts.owner.key();
+        let vault_key = ctx.accounts.vault.key();
+        let bump = ctx.accounts.vault.bump;
+        let signer_seeds: &[&[u8]] = &[VAULT_SEED, owner_key.as_ref(), &[bump]];
+
+        // writeRoa (tag 6): roaId as a length-prefixed byte string
+        let mut roa_data = vec![6u8];
+        roa_data.extend_from_slice(&(32u32).to_le_bytes());
+        roa_data.extend_from_slice(roa_id.as_ref());
+
+        let write_roa_ix = sns_records_instruction(
+            roa_data,
+            owner_key,
+            ctx.accounts.record.key(),
+            name_account_key,
+            vault_key,
+            true,
+            ctx.accounts.central_state.key(),
+            None,
+        );
+        invoke_sns(
+            &write_roa_ix,
+            &[
+                ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.name_service_program.to_account_info(),
+                ctx.accounts.owner.to_account_info(),
+                ctx.accounts.record.to_account_info(),
+                ctx.accounts.name_account.to_account_info(),
+                ctx.accounts.vault.to_account_info(),
+                ctx.accounts.central_state.to_account_info(),
+                ctx.accounts.sns_records_program.to_account_info(),
+            ],
+            &[signer_seeds],
+            SnsCpiStep::WriteRoa,
+            ctx.accounts.record.key(),
+        )?;
+
+        msg!("ROA of record {} set to {}", ctx.accounts.record.key(), roa_id);
+        Ok(())
+    }
+
+    /// Stage the SOL record update of a vaulted unwrapped domain, to be completed by a keeper
+    /// once `min_slot_gap` slots have passed; the reorg-safe split of `deposit_domain_with_record`
+    /// `escrow_lamports` reimburses the keeper's record rent, and the rest goes back to the owner
+    pub fn stage_record_update(
+        ctx: Context<StageRecordUpdate>,
+        min_slot_gap: u64,
+        escrow_lamports: u64,
+    ) -> Result<()> {
+        require!(!ctx.accounts.config.paused, VaultError::ProtocolPaused);
+        require!(
+            (1..=MAX_RECORD_STAGE_SLOT_GAP).contains(&min_slot_gap),
+            VaultError::InvalidRecordStage
+        );
+        let domain_entry = &ctx.accounts.domain_entry;
+        require!(
+            domain_entry.kind == DomainKind::Unwrapped,
+            VaultError::RecordsRequireUnwrappedDomain
+        );
+        require!(!domain_entry.records_locked, VaultError::RecordsLocked);
+        check_entry_name_account(domain_entry, &ctx.accounts.name_account)?;
+        charge_fee(
+            &ctx.accounts.config,
+            FeeOperation::RecordUpdate,
+            &ctx.accounts.owner,
+            &mut ctx.accounts.treasury,
+            &ctx.accounts.system_program,
+        )?;
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.owner.to_account_info(),
+                    to: ctx.accounts.staged_record.to_account_info(),
+                },
+            ),
+            escrow_lamports,
+        )?;
+
+        let staged_record = &mut ctx.accounts.staged_record;
+        staged_record.vault = ctx.accounts.vault.key();
+        staged_record.name_account = ctx.accounts.name_account.key();
+        staged_record.staged_slot = Clock::get()?.slot;
+        staged_record.min_slot_gap = min_slot_gap;
+        staged_record.escrow_lamports = escrow_lamports;
+        staged_record.bump = ctx.bumps.staged_record;
+
+        msg!(
+            "SOL record update of {} staged at slot {}, due after {} slots",
+            staged_record.name_account,
+            staged_record.staged_slot,
+            min_slot_gap
+        );
+        Ok(())
+    }
+
+    /// Cancel a staged SOL record update, refunding its escrow
+    pub fn cancel_staged_record_update(ctx: Context<CancelStagedRecordUpdate>) -> Result<()> {
+        msg!(
+            "Staged SOL record update of {} cancelled",
+            ctx.accounts.staged_record.name_account
+        );
+        Ok(())
+    }
+
+    /// Point the domain's SOL record at the vault, write its ROA and validate it, once the slot
+    /// gap has passed
+    /// Permissionless; the keeper pays record rent and is reimbursed from the escrow
+    pub fn complete_staged_record_update(ctx: Context<CompleteStagedRecordUpdate>) -> Result<()> {
+        require!(!ctx.accounts.config.paused, VaultError::ProtocolPaused);
+        check_cpi_program(&ctx.accounts.config, ctx.accounts.sns_records_program.key)?;
+        let staged_record = &ctx.accounts.staged_record;
+        let due_slot = staged_record
+            .staged_slot
+            .saturating_add(staged_record.min_slot_gap);
+        require!(Clock::get()?.slot >= due_slot, VaultError::RecordStageNotDue);
+        let domain_entry = &ctx.accounts.domain_entry;
+        require!(!domain_entry.records_locked, VaultError::RecordsLocked);
+
+        let keeper_key = ctx.accounts.keeper.key();
+        let owner_key = ctx.accounts.vault.owner;
+        let vault_key = ctx.accounts.vault.key();
+        let name_account_key = ctx.accounts.name_account.key();
+        let record_key = ctx.accounts.sol_record_v2.key();
+        let signer_seeds: &[&[u8]] = &[VAULT_SEED, owner_key.as_ref(), &[ctx.accounts.vault.bump]];
+        let infos = [
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.name_service_program.to_account_info(),
+            ctx.accounts.keeper.to_account_info(),
+            ctx.accounts.sol_record_v2.to_account_info(),
+            ctx.accounts.name_account.to_account_info(),
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.central_state.to_account_info(),
+            ctx.accounts.sns_records_program.to_account_info(),
+        ];
+        let keeper_lamports_before = ctx.accounts.keeper.lamports();
+
+        // allocateAndPostRecord (tag 1): "\x02SOL" record holding the vault key
+        let record_name: &[u8] = &[0x02, b'S', b'O', b'L'];
+        let mut allocate_data = vec![1u8];
+        allocate_data.extend_from_slice(&(record_name.len() as u32).to_le_bytes());
+        allocate_data.extend_from_slice(record_name);
+        allocate_data.extend_from_slice(&(32u32).to_le_bytes());
+        allocate_data.extend_from_slice(vault_key.as_ref());
+        let allocate_ix = sns_records_instruction(
+            allocate_data,
+            keeper_key,
+            record_key,
+            name_account_key,
+            vault_key,
+            true,
+            ctx.accounts.central_state.key(),
+            None,
+        );
+        invoke_sns(
+            &allocate_ix,
+            &infos,
+            &[signer_seeds],
+            SnsCpiStep::Allocate,
+            record_key,
+        )?;
+
+        // writeRoa (tag 6) with the vault as roaId
+        let mut roa_data = vec![6u8];
+        roa_data.extend_from_slice(&(32u32).to_le_bytes());
+        roa_data.extend_from_slice(vault_key.as_ref());
+        let write_roa_ix = sns_records_instruction(
+            roa_data,
+            keeper_key,
+            record_key,
+            name_account_key,
+            vault_key,
+            true,
+            ctx.accounts.central_state.key(),
+            None,
+        );
+        invoke_sns(
+            &write_roa_ix,
+            &infos,
+            &[signer_seeds],
+            SnsCpiStep::WriteRoa,
+            record_key,
+        )?;
+
+        // validateSolanaSignature (tag 3) with staleness, upgrading the ROA to Solana(1)
+        revalidate_sol_record_staleness(
+            &ctx.accounts.keeper.to_account_info(),
+            &ctx.accounts.sol_record_v2.to_account_info(),
+            &ctx.accounts.name_account.to_account_info(),
+            &ctx.accounts.vault.to_account_info(),
+            &ctx.accounts.central_state.to_account_info(),
+            &ctx.accounts.name_service_program.to_account_info(),
+            &ctx.accounts.sns_records_program.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            signer_seeds,
+        )?;
+
+        // The staged account is closed to the owner afterwards, taking the unspent escrow along
+        let spent = keeper_lamports_before.saturating_sub(ctx.accounts.keeper.lamports());
+        let reimbursement = spent.min(ctx.accounts.staged_record.escrow_lamports);
+        if reimbursement > 0 {
+            ctx.accounts
+                .staged_record
+                .to_account_info()
+                .sub_lamports(reimbursement)?;
+            ctx.accounts.keeper.add_lamports(reimbursement)?;
+        }
+
+        msg!(
+            "Staged SOL record of {} now points to vault {}; keeper reimbursed {} lamports",
+            name_account_key,
+            vault_key,
+            reimbursement
+        );
+        Ok(())
+    }
+
+    /// Validate a vaulted domain record's ROA with the signature of its (non-vault) roaId
+    /// The SNS Records program rejects the validation unless `verifier` is the record's roaId
+    pub fn validate_roa_external(ctx: Context<ValidateRoaExternal>) -> Result<()> {
+        require!(!ctx.accounts.config.paused, VaultError::ProtocolPaused);
+        check_cpi_program(&ctx.accounts.config, ctx.accounts.sns_records_program.key)?;
+        let domain_entry = &ctx.accounts.domain_entry;
+        require!(
+            domain_entry.kind == DomainKind::Unwrapped,
+            VaultError::RecordsRequireUnwrappedDomain
+        );
+        require!(!domain_entry.records_locked, VaultError::RecordsLocked);
+        let name_account_key = ctx.accounts.name_account.key();
+        check_entry_name_account(domain_entry, &ctx.accounts.name_account)?;
+        check_record_account(
+            &ctx.accounts.record,
+            &name_account_key,
+            ctx.accounts.central_state.key,
+        )?;
+
+        let verifier_key = ctx.accounts.verifier.key();
+        // validateSolanaSignature (tag 3) without staleness: only the ROA is validated
+        let validate_ix = sns_records_instruction(
+            vec![3u8, 0u8],
+            verifier_key,
+            ctx.accounts.record.key(),
+            name_account_key,
+            ctx.accounts.vault.key(),
+            false,
+            ctx.accounts.central_state.key(),
+            Some(verifier_key),
+        );
+        invoke_sns(
+            &validate_ix,
+            &[
+                ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.name_service_program.to_account_info(),
+                ctx.accounts.verifier.to_account_info(),
+                ctx.accounts.record.to_account_info(),
+                ctx.accounts.name_account.to_account_info(),
+                ctx.accounts.vault.to_account_info(),
+                ctx.accounts.central_state.to_account_info(),
+                ctx.accounts.verifier.to_account_info(),
+                ctx.accounts.sns_records_program.to_account_info(),
+            ],
+            &[],
+            SnsCpiStep::Validate,
+            ctx.accounts.record.key(),
+        )?;
+
+        msg!(
+            "ROA of record {} validated by {}",
+            ctx.accounts.record.key(),
+            verifier_key
+        );
+        Ok(())
+    }
+
+    /// Re-validate staleness of a vaulted domain's SOL record
+    /// Repairs resolution after the registry owner changed under an existing record
+    pub fn refresh_sol_record_staleness(ctx: Context<RefreshSolRecordStaleness>) -> Result<()> {
+        check_cpi_program(&ctx.accounts.config, ctx.accounts.sns_records_program.key)?;
+        require!(
+            !ctx.accounts.domain_entry.records_locked,
+            VaultError::RecordsLocked
+        );
+
+        let owner_key = ctx.accounts.owner.key();
+        let bump = ctx.accounts.vault.bump;
+        let signer_seeds: &[&[u8]] = &[VAULT_SEED, owner_key.as_ref(), &[bump]];
+
+        revalidate_sol_record_staleness(
+            &ctx.accounts.owner.to_account_info(),
+            &ctx.accounts.sol_record_v2.to_account_info(),
+            &ctx.accounts.name_account.to_account_info(),
+            &ctx.accounts.vault.to_account_info(),
+            &ctx.accounts.central_state.to_account_info(),
+            &ctx.accounts.name_service_program.to_account_info(),
+            &ctx.accounts.sns_records_program.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            signer_seeds,
+        )?;
+
+        msg!(
+            "SOL record of {} re-validated for vault {}",
+            ctx.accounts.name_account.key(),
+            ctx.accounts.vault.key()
+        );
+        Ok(())
+    }
+
+    /// Set the owner-defined tag/category of a vaulted domain (all zeroes clears it)
+    pub fn set_domain_tag(ctx: Context<UpdateDomainEntry>, tag: [u8; 32]) -> Result<()> {
+        let domain_entry = &mut ctx.accounts.domain_entry;
+        domain_entry.tag = tag;
+
+        msg!("Tag of {} updated", domain_entry.domain);
+        Ok(())
+    }
+
+    /// Create or replace a record template of the vault
+    pub fn set_record_template(
+        ctx: Context<SetRecordTemplate>,
+        template_id: u8,
+        records: Vec<TemplateRecord>,
+    ) -> Result<()> {
+        require!(
+            !records.is_empty() && records.len() <= MAX_TEMPLATE_RECORDS,
+            VaultError::InvalidRecordTemplate
+        );
+        require!(
+            records.iter().all(|r| !r.name.is_empty()
+                && r.name.len() <= MAX_RECORD_NAME_LEN
+                && r.content.len() <= MAX_TEMPLATE_CONTENT_LEN),
+            VaultError::InvalidRecordTemplate
+        );
+
+        let record_template = &mut ctx.accounts.record_template;
+        record_template.vault = ctx.accounts.vault.key();
+        record_template.id = template_id;
+        record_template.records = records;
+        record_template.bump = ctx.bumps.record_template;
+
+        msg!(
+            "Record template {} set with {} records",
+            template_id,
+            record_template.records.len()
+        );
+        Ok(())
+    }
+
+    /// Close a record template of the vault
+    pub fn close_record_template(ctx: Context<CloseRecordTemplate>) -> Result<()> {
+        msg!("Record template {} closed", ctx.accounts.record_template.id);
+        Ok(())
+    }
+
+    /// Write every record of a template to a batch of vaulted (unwrapped) domains
+    /// remaining_accounts, per domain: name account, domain entry, then one record account
+    /// per template record, in template order
+    pub fn apply_record_template<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ApplyRecordTemplate<'info>>,
+    ) -> Result<()> {
+        require!(!ctx.accounts.config.paused, VaultError::ProtocolPaused);
+        check_cpi_program(&ctx.accounts.config, ctx.accounts.sns_records_program.key)?;
+
+        let records = ctx.accounts.record_template.records.clone();
+        let group_len = 2 + records.len();
+        let remaining_accounts = ctx.remaining_accounts;
+        require!(
+            !remaining_accounts.is_empty() && remaining_accounts.len() % group_len == 0,
+            VaultError::InvalidRecordAccount
+        );
+
+        let vault_key = ctx.accounts.vault.key();
+        let owner_key = ctx.accounts.owner.key();
+        let bump = ctx.accounts.vault.bump;
+        let signer_seeds: &[&[u8]] = &[VAULT_SEED, owner_key.as_ref(), &[bump]];
+
+        let owner_info = ctx.accounts.owner.to_account_info();
+        let vault_info = ctx.accounts.vault.to_account_info();
+        let central_state_info = ctx.accounts.central_state.to_account_info();
+        let name_service_info = ctx.accounts.name_service_program.to_account_info();
+        let sns_records_info = ctx.accounts.sns_records_program.to_account_info();
+        let system_info = ctx.accounts.system_program.to_account_info();
+
+        for group in remaining_accounts.chunks(group_len) {
+            let name_account = &group[0];
+            let domain_entry = Account::<DomainEntry>::try_from(&group[1])?;
+            require_keys_eq!(domain_entry.vault, vault_key, VaultError::DomainNotInVault);
+            require!(
+                domain_entry.kind == DomainKind::Unwrapped,
+                VaultError::RecordsRequireUnwrappedDomain
+            );
+            require!(!domain_entry.records_locked, VaultError::RecordsLocked);
+            check_entry_name_account(&domain_entry, name_account)?;
+
+            charge_fee(
+                &ctx.accounts.config,
+                FeeOperation::RecordUpdate,
+                &ctx.accounts.owner,
+                &mut ctx.accounts.treasury,
+                &ctx.accounts.system_program,
+            )?;
+
+            for (record, template_record) in group[2..].iter().zip(records.iter()) {
+                write_record_v2(
+                    &owner_info,
+                    record,
+                    name_account,
+                    &vault_info,
+                    &central_state_info,
+                    &name_service_info,
+                    &sns_records_info,
+                    &system_info,
+                    &template_record.name,
+                    &template_record.content,
+                    signer_seeds,
+                )?;
+            }
+
+            msg!("Record template applied to {}", name_account.key());
+        }
+
+        msg!(
+            "Record template {} applied to {} domains",
+            ctx.accounts.record_template.id,
+            remaining_accounts.len() / group_len
+        );
+        Ok(())
+    }
+
+    /// Grant a third party record-only rights over one vaulted domain
+    /// `expires_at` of 0 means the grant never expires
+    /// With `isolated_only`, the operator's record writes must be top-level instructions in a
+    /// transaction containing nothing but this program and compute budget instructions
+    pub fn grant_record_operator(
+        ctx: Context<GrantRecordOperator>,
+        operator: Pubkey,
+        expires_at: i64,
+        isolated_only: bool,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            expires_at == 0 || expires_at > now,
+            VaultError::InvalidOperatorExpiry
+        );
+        require!(
+            ctx.accounts.domain_entry.kind == DomainKind::Unwrapped,
+            VaultError::RecordsRequireUnwrappedDomain
+        );
+
+        let operator_grant = &mut ctx.accounts.operator_grant;
+        operator_grant.vault = ctx.accounts.vault.key();
+        operator_grant.domain = ctx.accounts.domain_entry.domain;
+        operator_grant.operator = operator;
+        operator_grant.granted_at = now;
+        operator_grant.expires_at = expires_at;
+        operator_grant.isolated_only = isolated_only;
+        operator_grant.bump = ctx.bumps.operator_grant;
+
+        msg!(
+            "Record operator {} granted on {} until {}",
+            operator,
+            operator_grant.domain,
+            expires_at
+        );
+        Ok(())
+    }
+
+    /// Revoke a record operator grant
+    pub fn revoke_record_operator(ctx: Context<RevokeRecordOperator>) -> Result<()> {
+        msg!(
+            "Record operator {} revoked on {}",
+            ctx.accounts.operator_grant.operator,
+            ctx.accounts.operator_grant.domain
+        );
+        Ok(())
+    }
+
+    /// Create or overwrite one Record V2 of a vaulted domain
+    /// Signed by the vault owner or by an unexpired operator of that domain
+    pub fn update_record(
+        ctx: Context<UpdateRecord>,
+        name: String,
+        content: RecordContent,
+    ) -> Result<()> {
+        require!(!ctx.accounts.config.paused, VaultError::ProtocolPaused);
+        check_cpi_program(&ctx.accounts.config, ctx.accounts.sns_records_program.key)?;
+        require!(
+            !name.is_empty() && name.len() <= MAX_RECORD_NAME_LEN,
+            VaultError::InvalidRecordAccount
+        );
+        let content = encode_record_content(&name, &content)?;
+
+        let domain_entry = &ctx.accounts.domain_entry;
+        require!(
+            domain_entry.kind == DomainKind::Unwrapped,
+            VaultError::RecordsRequireUnwrappedDomain
+        );
+        require!(!domain_entry.records_locked, VaultError::RecordsLocked);
+        check_entry_name_account(domain_entry, &ctx.accounts.name_account)?;
+
+        let authority_key = ctx.accounts.authority.key();
+        let owner_key = ctx.accounts.vault.owner;
+        if authority_key != owner_key {
+            let operator_grant = ctx
+                .accounts
+                .operator_grant
+                .as_ref()
+                .ok_or(VaultError::UnauthorizedAccess)?;
+            let now = Clock::get()?.unix_timestamp;
+            require!(
+                operator_grant.expires_at == 0 || now < operator_grant.expires_at,
+                VaultError::OperatorGrantExpired
+            );
+            if operator_grant.isolated_only {
+                check_isolated_transaction(&ctx.accounts.instructions_sysvar)?;
+            }
+        }
+
+        charge_fee(
+            &ctx.accounts.config,
+            FeeOperation::RecordUpdate,
+            &ctx.accounts.authority,
+            &mut ctx.accounts.treasury,
+            &ctx.accounts.system_program,
+        )?;
+
+        let bump = ctx.accounts.vault.bump;
+        let signer_seeds: &[&[u8]] = &[VAULT_SEED, owner_key.as_ref(), &[bump]];
+        write_record_v2(
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.record.to_account_info(),
+            &ctx.accounts.name_account.to_account_info(),
+            &ctx.accounts.vault.to_account_info(),
+            &ctx.accounts.central_state.to_account_info(),
+            &ctx.accounts.name_service_program.to_account_info(),
+            &ctx.accounts.sns_records_program.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &name,
+            &content,
+            signer_seeds,
+        )?;
+
+        msg!(
+            "Record {} of {} updated by {}",
+            name,
+            ctx.accounts.name_account.key(),
+            authority_key
+        );
+        Ok(())
+    }
+
+    /// Create or overwrite a DNS-style record (A, AAAA, CNAME, TXT) of a vaulted domain
+    /// Same rights and fees as update_record; addresses and hostnames are parsed on-chain
+    pub fn update_dns_record(ctx: Context<UpdateRecord>, record: DnsRecord) -> Result<()> {
+        let (name, content) = dns_record_content(record)?;
+        update_record(ctx, name.to_string(), content)
+    }
+
+    /// Point a vaulted domain at decentralized site content (IPFS or ARWV record), optionally
+    /// writing a gateway hostname hint to its TXT record in the same call
+    pub fn set_content_record(
+        ctx: Context<SetContentRecord>,
+        content: ContentRecord,
+        gateway_hint: Option<String>,
+    ) -> Result<()> {
+        require!(!ctx.accounts.config.paused, VaultError::ProtocolPaused);
+        check_cpi_program(&ctx.accounts.config, ctx.accounts.sns_records_program.key)?;
+
+        let domain_entry = &ctx.accounts.domain_entry;
+        require!(
+            domain_entry.kind == DomainKind::Unwrapped,
+            VaultError::RecordsRequireUnwrappedDomain
+        );
+        require!(!domain_entry.records_locked, VaultError::RecordsLocked);
+        check_entry_name_account(domain_entry, &ctx.accounts.name_account)?;
+
+        let (name, content) = match content {
+            ContentRecord::Ipfs(cid) => ("IPFS", RecordContent::IpfsCid(cid)),
+            ContentRecord::Arweave(id) => ("ARWV", RecordContent::ArweaveTxId(id)),
+        };
+        let content = encode_record_content(name, &content)?;
+        let name_key = ctx.accounts.name_account.key();
+        require_keys_eq!(
+            ctx.accounts.record.key(),
+            get_record_v2_key(&name_key, name).0,
+            VaultError::InvalidRecordAccount
+        );
+
+        charge_fee(
+            &ctx.accounts.config,
+            FeeOperation::RecordUpdate,
+            &ctx.accounts.owner,
+            &mut ctx.accounts.treasury,
+            &ctx.accounts.system_program,
+        )?;
+
+        let owner_key = ctx.accounts.owner.key();
+        let bump = ctx.accounts.vault.bump;
+        let signer_seeds: &[&[u8]] = &[VAULT_SEED, owner_key.as_ref(), &[bump]];
+        let mut records = vec![(ctx.accounts.record.to_account_info(), name, content)];
+        if let Some(hint) = gateway_hint {
+            let txt_record = ctx
+                .accounts
+                .txt_record
+                .as_ref()
+                .ok_or(VaultError::InvalidRecordAccount)?;
+            require_keys_eq!(
+                txt_record.key(),
+                get_record_v2_key(&name_key, "TXT").0,
+                VaultError::InvalidRecordAccount
+            );
+            require!(is_hostname(&hint), VaultError::InvalidRecordContent);
+            records.push((txt_record.to_account_info(), "TXT", hint.into_bytes()));
+        }
+
+        for (record, name, content) in records {
+            write_record_v2(
+                &ctx.accounts.owner.to_account_info(),
+                &record,
+                &ctx.accounts.name_account.to_account_info(),
+                &ctx.accounts.vault.to_account_info(),
+                &ctx.accounts.central_state.to_account_info(),
+                &ctx.accounts.name_service_program.to_account_info(),
+                &ctx.accounts.sns_records_program.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                name,
+                &content,
+                signer_seeds,
+            )?;
+        }
+
+        msg!("Content record {} of {} set", name, name_key);
+        Ok(())
+    }
+
+    /// Post a social record (twitter, discord, github) of a vaulted domain and stage it as
+    /// pending external verification; reposting resets the status to pending
+    pub fn post_social_record(
+        ctx: Context<PostSocialRecord>,
+        platform: SocialPlatform,
+        handle: String,
+    ) -> Result<()> {
+        require!(!ctx.accounts.config.paused, VaultError::ProtocolPaused);
+        check_cpi_program(&ctx.accounts.config, ctx.accounts.sns_records_program.key)?;
+        require!(is_social_handle(platform, &handle), VaultError::InvalidRecordContent);
+
+        let domain_entry = &ctx.accounts.domain_entry;
+        require!(
+            domain_entry.kind == DomainKind::Unwrapped,
+            VaultError::RecordsRequireUnwrappedDomain
+        );
+        require!(!domain_entry.records_locked, VaultError::RecordsLocked);
+        check_entry_name_account(domain_entry, &ctx.accounts.name_account)?;
+
+        let name = social_record_name(platform);
+        require_keys_eq!(
+            ctx.accounts.record.key(),
+            get_record_v2_key(ctx.accounts.name_account.key, name).0,
+            VaultError::InvalidRecordAccount
+        );
+
+        charge_fee(
+            &ctx.accounts.config,
+            FeeOperation::RecordUpdate,
+            &ctx.accounts.owner,
+            &mut ctx.accounts.treasury,
+            &ctx.accounts.system_program,
+        )?;
+
+        let owner_key = ctx.accounts.owner.key();
+        let bump = ctx.accounts.vault.bump;
+        let signer_seeds: &[&[u8]] = &[VAULT_SEED, owner_key.as_ref(), &[bump]];
+        write_record_v2(
+            &ctx.accounts.owner.to_account_info(),
+            &ctx.accounts.record.to_account_info(),
+            &ctx.accounts.name_account.to_account_info(),
+            &ctx.accounts.vault.to_account_info(),
+            &ctx.accounts.central_state.to_account_info(),
+            &ctx.accounts.name_service_program.to_account_info(),
+            &ctx.accounts.sns_records_program.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            name,
+            handle.as_bytes(),
+            signer_seeds,
+        )?;
+
+        let social_verification = &mut ctx.accounts.social_verification;
+        social_verification.domain_entry = ctx.accounts.domain_entry.key();
+        social_verification.record = ctx.accounts.record.key();
+        social_verification.platform = platform;
+        social_verification.status = VerificationStatus::Pending;
+        social_verification.posted_at = Clock::get()?.unix_timestamp;
+        social_verification.reviewed_at = 0;
+        social_verification.bump = ctx.bumps.social_verification;
+
+        emit!(SocialVerificationChanged {
+            domain: ctx.accounts.domain_entry.domain,
+            platform,
+            status: VerificationStatus::Pending,
+        });
+        msg!("Social record {} posted, pending verification", name);
+        Ok(())
+    }
+
+    /// Record the outcome of a pending social record's external verification (admin only)
+    pub fn finalize_social_verification(
+        ctx: Context<FinalizeSocialVerification>,
+        verified: bool,
+    ) -> Result<()> {
+        let social_verification = &mut ctx.accounts.social_verification;
+        require!(
+            social_verification.status == VerificationStatus::Pending,
+            VaultError::VerificationNotPending
+        );
+
+        social_verification.status = if verified {
+            VerificationStatus::Verified
+        } else {
+            VerificationStatus::Rejected
+        };
+        social_verification.reviewed_at = Clock::get()?.unix_timestamp;
+
+        emit!(SocialVerificationChanged {
+            domain: ctx.accounts.domain_entry.domain,
+            platform: social_verification.platform,
+            status: social_verification.status,
+        });
+        msg!(
+            "Social record {} verification: {:?}",
+            social_record_name(social_verification.platform),
+            social_verification.status
+        );
+        Ok(())
+    }
+
+    /// Approve a single flash record session for a third party on one vaulted domain
+    /// The session may only write the Record V2 accounts in `allowed_records`, and must end
+    /// with the domain's records hashing to `expected_records_hash` (see snapshot_records for
+    /// the hash) before `expires_at`
+    pub fn approve_record_session(
+        ctx: Context<ApproveRecordSession>,
+        delegate: Pubkey,
+        allowed_records: Vec<Pubkey>,
+        expected_records_hash: [u8; 32],
+        expires_at: i64,
+    ) -> Result<()> {
+        require!(
+            !allowed_records.is_empty() && allowed_records.len() <= MAX_SESSION_RECORDS,
+            VaultError::InvalidSessionRecords
+        );
+        require!(
+            expires_at > Clock::get()?.unix_timestamp,
+            VaultError::InvalidOperatorExpiry
+        );
+        require!(
+            ctx.accounts.domain_entry.kind == DomainKind::Unwrapped,
+            VaultError::RecordsRequireUnwrappedDomain
+        );
+
+        let session_policy = &mut ctx.accounts.session_policy;
+        session_policy.vault = ctx.accounts.vault.key();
+        session_policy.domain = ctx.accounts.domain_entry.domain;
+        session_policy.delegate = delegate;
+        session_policy.allowed_records = allowed_records;
+        session_policy.expected_records_hash = expected_records_hash;
+        session_policy.expires_at = expires_at;
+        session_policy.active = false;
+        session_policy.bump = ctx.bumps.session_policy;
+
+        msg!(
+            "Record session on {} approved for {} until {}",
+            session_policy.domain,
+            delegate,
+            expires_at
+        );
+        Ok(())
+    }
+
+    /// Revoke an approved record session that hasn't been used
+    pub fn revoke_record_session(ctx: Context<RevokeRecordSession>) -> Result<()> {
+        msg!(
+            "Record session on {} revoked",
+            ctx.accounts.session_policy.domain
+        );
+        Ok(())
+    }
+
+    /// Open an approved record session
+    /// Only succeeds if a later instruction of the same transaction ends this session
+    pub fn begin_record_session(ctx: Context<BeginRecordSession>) -> Result<()> {
+        let session_policy = &ctx.accounts.session_policy;
+        require!(!session_policy.active, VaultError::RecordSessionActive);
+        require!(
+            Clock::get()?.unix_timestamp < session_policy.expires_at,
+            VaultError::OperatorGrantExpired
+        );
+        require!(
+            !ctx.accounts.domain_entry.records_locked,
+            VaultError::RecordsLocked
+        );
+
+        // Find end_record_session for this policy after the current instruction
+        let instructions = ctx.accounts.instructions_sysvar.to_account_info();
+        let current_index = load_current_index_checked(&instructions)? as usize;
+        let session_key = session_policy.key();
+        let mut index = current_index + 1;
+        let mut ended = false;
+        while let Ok(ix) = load_instruction_at_checked(index, &instructions) {
+            if ix.program_id == crate::ID
+                && ix.data.starts_with(crate::instruction::EndRecordSession::DISCRIMINATOR)
+                && ix.accounts.first().map(|meta| meta.pubkey) == Some(session_key)
+            {
+                ended = true;
+                break;
+            }
+            index += 1;
+        }
+        require!(ended, VaultError::RecordSessionNotEnded);
+
+        ctx.accounts.session_policy.active = true;
+
+        msg!(
+            "Record session on {} opened by {}",
+            ctx.accounts.session_policy.domain,
+            ctx.accounts.delegate.key()
+        );
+        Ok(())
+    }
+
+    /// Write a record of the domain inside an open record session
+    pub fn session_update_record(
+        ctx: Context<SessionUpdateRecord>,
+        name: String,
+        content: RecordContent,
+    ) -> Result<()> {
+        require!(!ctx.accounts.config.paused, VaultError::ProtocolPaused);
+        check_cpi_program(&ctx.accounts.config, ctx.accounts.sns_records_program.key)?;
+        require!(
+            !name.is_empty() && name.len() <= MAX_RECORD_NAME_LEN,
+            VaultError::InvalidRecordAccount
+        );
+        let content = encode_record_content(&name, &content)?;
+        require!(
+            ctx.accounts.session_policy.active,
+            VaultError::RecordSessionNotActive
+        );
+        require!(
+            !ctx.accounts.domain_entry.records_locked,
+            VaultError::RecordsLocked
+        );
+        check_session_record(&ctx.accounts.session_policy, ctx.accounts.record.key)?;
+
+        charge_fee(
+            &ctx.accounts.config,
+            FeeOperation::RecordUpdate,
+            &ctx.accounts.delegate,
+            &mut ctx.accounts.treasury,
+            &ctx.accounts.system_program,
+        )?;
+
+        let owner_key = ctx.accounts.vault.owner;
+        let bump = ctx.accounts.vault.bump;
+        let signer_seeds: &[&[u8]] = &[VAULT_SEED, owner_key.as_ref(), &[bump]];
+        write_record_v2(
+            &ctx.accounts.delegate.to_account_info(),
+            &ctx.accounts.record.to_account_info(),
+            &ctx.accounts.name_account.to_account_info(),
+            &ctx.accounts.vault.to_account_info(),
+            &ctx.accounts.central_state.to_account_info(),
+            &ctx.accounts.name_service_program.to_account_info(),
+            &ctx.accounts.sns_records_program.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &name,
+            &content,
+            signer_seeds,
+        )?;
+
+        msg!(
+            "Record {} of {} updated in session",
+            name,
+            ctx.accounts.name_account.key()
+        );
+        Ok(())
+    }
+
+    /// Close a record session, checking the domain is still in the vault and its records
+    /// match the owner-approved hash; the policy is single-use and refunded to the owner
+    /// remaining_accounts: the domain's record accounts, sorted by key
+    pub fn end_record_session(ctx: Context<EndRecordSession>) -> Result<()> {
+        require!(
+            ctx.accounts.session_policy.active,
+            VaultError::RecordSessionNotActive
+        );
+
+        let name_account = &ctx.accounts.name_account;
+        {
+            let data = name_account.try_borrow_data()?;
+            require!(
+                data.len() >= 64 && data[32..64] == ctx.accounts.vault.key().to_bytes(),
+                VaultError::DomainNotInVault
+            );
+        }
+
+        let records_hash = hash_record_accounts(name_account.key, ctx.remaining_accounts)?;
+        require!(
+            records_hash == ctx.accounts.session_policy.expected_records_hash,
+            VaultError::RecordsChanged
+        );
+
+        msg!(
+            "Record session on {} ended",
+            ctx.accounts.session_policy.domain
+        );
+        Ok(())
+    }
+
+    /// Close an operator grant that expired more than the grace period ago
+    /// Permissionless; rent goes back to the vault owner who paid it
+    pub fn reap_expired_operator_grant(ctx: Context<ReapExpiredOperatorGrant>) -> Result<()> {
+        let operator_grant = &ctx.accounts.operator_grant;
+        require!(
+            operator_grant.expires_at != 0
+                && Clock::get()?.unix_timestamp
+                    >= operator_grant.expires_at.checked_add(REAP_GRACE_SECONDS).unwrap(),
+            VaultError::NotReapable
+        );
+        pay_keeper_bounty(
+            &ctx.accounts.keeper_bounties,
+            &ctx.accounts.keeper,
+            &ctx.accounts.operator_grant.to_account_info(),
+            CrankKind::ReapOperatorGrant,
+            true,
+        )?;
+
+        msg!(
+            "Reaped expired operator grant of {} on {}",
+            operator_grant.operator,
+            operator_grant.domain
+        );
+        Ok(())
+    }
+
+    /// Close an unused record session policy that expired more than the grace period ago
+    /// Permissionless; rent goes back to the vault owner who paid it
+    pub fn reap_expired_record_session(ctx: Context<ReapExpiredRecordSession>) -> Result<()> {
+        let session_policy = &ctx.accounts.session_policy;
+        require!(
+            !session_policy.active
+                && Clock::get()?.unix_timestamp
+                    >= session_policy.expires_at.checked_add(REAP_GRACE_SECONDS).unwrap(),
+            VaultError::NotReapable
+        );
+        pay_keeper_bounty(
+            &ctx.accounts.keeper_bounties,
+            &ctx.accounts.keeper,
+            &ctx.accounts.session_policy.to_account_info(),
+            CrankKind::ReapRecordSession,
+            true,
+        )?;
+
+        msg!(
+            "Reaped expired record session on {}",
+            session_policy.domain
+        );
+        Ok(())
+    }
+
+    /// Check a vault's invariants and return a bitmask of `HEALTH_*` issues as return data
+    /// remaining_accounts: every domain entry of the vault, each followed by its custody account
+    /// (the vault token account for wrapped domains, the name registry for unwrapped ones);
+    /// unwrapped entries are further followed by the domain's SOL record V2 account
+    pub fn check_vault_health<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CheckVaultHealth<'info>>,
+    ) -> Result<u32> {
+        let vault_key = ctx.accounts.vault.key();
+        let mut issues = 0u32;
+        if ctx.accounts.vault.in_flight {
+            issues |= HEALTH_IN_FLIGHT;
+        }
+
+        let mut seen: Vec<Pubkey> = Vec::new();
+        let mut accounts = ctx.remaining_accounts.iter();
+        while let Some(entry_info) = accounts.next() {
+            let domain_entry = Account::<DomainEntry>::try_from(entry_info)?;
+            let custody = accounts.next().ok_or(VaultError::InvalidHealthCheckAccounts)?;
+
+            if domain_entry.vault != vault_key || seen.contains(entry_info.key) {
+                issues |= HEALTH_INVALID_ENTRY;
+            } else {
+                seen.push(*entry_info.key);
+            }
+
+            if matches!(
+                domain_entry.kind,
+                DomainKind::WrappedSplToken | DomainKind::WrappedToken2022
+            ) {
+                let held = InterfaceAccount::<TokenAccount>::try_from(custody).is_ok_and(|t| {
+                    t.mint == domain_entry.domain && t.owner == vault_key && t.amount == 1
+                });
+                if !held {
+                    issues |= HEALTH_NOT_CUSTODIED;
+                }
+            } else {
+                require!(
+                    domain_entry.kind == DomainKind::Unwrapped,
+                    VaultError::UnsupportedDomainKind
+                );
+                let sol_record = accounts.next().ok_or(VaultError::InvalidHealthCheckAccounts)?;
+                require_keys_eq!(
+                    *custody.key,
+                    domain_entry.domain,
+                    VaultError::InvalidHealthCheckAccounts
+                );
+                require_keys_eq!(
+                    *sol_record.key,
+                    get_sol_record_v2_key(custody.key).0,
+                    VaultError::InvalidHealthCheckAccounts
+                );
+
+                let data = custody.try_borrow_data()?;
+                if *custody.owner != NAME_SERVICE_PROGRAM_ID
+                    || data.len() < 96
+                    || data[32..64] != vault_key.to_bytes()
+                {
+                    issues |= HEALTH_NOT_CUSTODIED;
+                }
+                issues |= sol_record_health(sol_record, &vault_key)?;
+            }
+        }
+
+        if seen.len() as u64 != ctx.accounts.vault.domains_count {
+            issues |= HEALTH_COUNT_MISMATCH;
+        }
+
+        msg!("Vault {} health issues: {:#x}", vault_key, issues);
+        Ok(issues)
+    }
+
+    /// Last-resort exit returning every supplied vaulted domain to the owner
+    /// Needs no admin-controlled state: works while paused or with the outflow breaker tripped,
+    /// charges no fee and ignores staking (unclaimed rewards are forfeited); entries are closed
+    /// to their rent payers
+    /// The owner's own safeguards still apply: the co-signer, if set, must sign and the
+    /// withdrawal confirmation phrase, if set, must match
+    /// remaining_accounts, per domain:
+    /// - wrapped: [domain_entry, domain_mint, vault_token_account, owner_token_account, rent_payer]
+    /// - unwrapped: [domain_entry, name_account, rent_payer]
+    pub fn emergency_withdraw_all<'info>(
+        ctx: Context<'_, '_, 'info, 'info, EmergencyWithdrawAll<'info>>,
+        confirmation_phrase: Option<String>,
+    ) -> Result<()> {
+        check_co_signer(&ctx.accounts.vault, ctx.accounts.co_signer.as_ref())?;
+        check_withdrawal_phrase(&ctx.accounts.withdrawal_phrase, confirmation_phrase.as_ref())?;
+        let owner_key = ctx.accounts.owner.key();
+        let vault_key = ctx.accounts.vault.key();
+        let bump = ctx.accounts.vault.bump;
+        let signer_seeds: &[&[u8]] = &[VAULT_SEED, owner_key.as_ref(), &[bump]];
+        let withdrawn = withdraw_entries(
+            ctx.remaining_accounts,
+            &ctx.accounts.vault.to_account_info(),
+            &owner_key,
+            signer_seeds,
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.token_2022_program.to_account_info(),
+            &ctx.accounts.name_service_program.to_account_info(),
+            ctx.accounts.stats.as_deref_mut(),
+            &ctx.accounts.vault_summary.to_account_info(),
+            confirmation_phrase.as_ref(),
+        )?
+        .len() as u64;
+        // Counted, but a tripped breaker never locks owners out of the emergency exit
+        record_outflow(
+            &ctx.accounts.outflow_breaker,
+            &ctx.accounts.security_log,
+            withdrawn,
+            false,
+        )?;
+
+        let vault = &mut ctx.accounts.vault;
+        vault.domains_count = vault.domains_count.saturating_sub(withdrawn);
+
+        msg!(
+            "Emergency withdrawal of {} domains from vault {}. Remaining domains: {}",
+            withdrawn,
+            vault_key,
+            vault.domains_count
+        );
+        Ok(())
+    }
+
+    /// Create a named bundle of the vault's domains, or rename an existing one and add domains
+    /// remaining_accounts: domain entries to add to the bundle
+    pub fn set_bundle<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SetBundle<'info>>,
+        bundle_id: u8,
+        name: String,
+    ) -> Result<()> {
+        require!(
+            !name.is_empty() && name.len() <= MAX_BUNDLE_NAME_LEN,
+            VaultError::InvalidBundle
+        );
+
+        let vault_key = ctx.accounts.vault.key();
+        let bundle = &mut ctx.accounts.bundle;
+        bundle.vault = vault_key;
+        bundle.id = bundle_id;
+        bundle.name = name;
+        bundle.bump = ctx.bumps.bundle;
+
+        for entry_info in ctx.remaining_accounts {
+            let domain_entry = Account::<DomainEntry>::try_from(entry_info)?;
+            require_keys_eq!(domain_entry.vault, vault_key, VaultError::DomainNotInVault);
+            require!(!bundle.entries.contains(entry_info.key), VaultError::InvalidBundle);
+            bundle.entries.push(entry_info.key());
+        }
+        require!(bundle.entries.len() <= MAX_BUNDLE_DOMAINS, VaultError::InvalidBundle);
+
+        msg!(
+            "Bundle {} \"{}\" holds {} domains",
+            bundle_id,
+            bundle.name,
+            bundle.entries.len()
+        );
+        Ok(())
+    }
+
+    /// Remove a domain entry from a bundle; the entry may already be closed
+    pub fn remove_from_bundle(ctx: Context<UpdateBundle>, domain_entry: Pubkey) -> Result<()> {
+        let bundle = &mut ctx.accounts.bundle;
+        let position = bundle
+            .entries
+            .iter()
+            .position(|entry| *entry == domain_entry)
+            .ok_or(VaultError::InvalidBundle)?;
+        bundle.entries.remove(position);
+
+        msg!("Entry {} removed from bundle {}", domain_entry, bundle.id);
+        Ok(())
+    }
+
+    /// Close a bundle without touching its domains
+    pub fn close_bundle(ctx: Context<CloseBundle>) -> Result<()> {
+        msg!("Bundle {} closed", ctx.accounts.bundle.id);
+        Ok(())
+    }
+
+    /// Withdraw a page of a bundle's domains to the owner; pages are sized by the caller to
+    /// fit account limits, and the bundle is closed once its last domain is withdrawn
+    /// remaining_accounts: as in emergency_withdraw_all, for bundled domains only
+    pub fn withdraw_bundle<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawBundle<'info>>,
+        confirmation_phrase: Option<String>,
+    ) -> Result<()> {
+        check_co_signer(&ctx.accounts.vault, ctx.accounts.co_signer.as_ref())?;
+        check_withdrawal_phrase(&ctx.accounts.withdrawal_phrase, confirmation_phrase.as_ref())?;
+        let owner_key = ctx.accounts.owner.key();
+        let bump = ctx.accounts.vault.bump;
+        let signer_seeds: &[&[u8]] = &[VAULT_SEED, owner_key.as_ref(), &[bump]];
+
+        let withdrawn = withdraw_entries(
+            ctx.remaining_accounts,
+            &ctx.accounts.vault.to_account_info(),
+            &owner_key,
+            signer_seeds,
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.token_2022_program.to_account_info(),
+            &ctx.accounts.name_service_program.to_account_info(),
+            ctx.accounts.stats.as_deref_mut(),
+            &ctx.accounts.vault_summary.to_account_info(),
+            confirmation_phrase.as_ref(),
+        )?;
+        record_outflow(
+            &ctx.accounts.outflow_breaker,
+            &ctx.accounts.security_log,
+            withdrawn.len() as u64,
+            true,
+        )?;
+
+        let bundle = &mut ctx.accounts.bundle;
+        for entry in &withdrawn {
+            let position = bundle
+                .entries
+                .iter()
+                .position(|bundled| bundled == entry)
+                .ok_or(VaultError::InvalidBundle)?;
+            bundle.entries.remove(position);
+        }
+        let remaining = bundle.entries.len();
+        let bundle_id = bundle.id;
+
+        let vault = &mut ctx.accounts.vault;
+        vault.domains_count = vault.domains_count.saturating_sub(withdrawn.len() as u64);
+        if remaining == 0 {
+            ctx.accounts.bundle.close(ctx.accounts.owner.to_account_info())?;
+        }
+
+        msg!(
+            "Withdrew {} domains of bundle {}; {} remaining",
+            withdrawn.len(),
+            bundle_id,
+            remaining
+        );
+        Ok(())
+    }
+
+    /// Create the vault's summary account, seeded from its domain entries
+    /// remaining_accounts must be every domain entry of the vault, once each
+    pub fn init_vault_summary<'info>(
+        ctx: Context<'_, '_, 'info, 'info, InitVaultSummary<'info>>,
+    ) -> Result<()> {
+        let vault = &ctx.accounts.vault;
+        require!(
+            ctx.remaining_accounts.len() as u64 == vault.domains_count,
+            VaultError::InvalidSummaryEntries
+        );
+
+        let mut seen = Vec::with_capacity(ctx.remaining_accounts.len());
+        let (mut wrapped, mut unwrapped) = (0u64, 0u64);
+        for entry_info in ctx.remaining_accounts {
+            require!(!seen.contains(entry_info.key), VaultError::InvalidSummaryEntries);
+            seen.push(*entry_info.key);
+            let domain_entry = Account::<DomainEntry>::try_from(entry_info)?;
+            require_keys_eq!(domain_entry.vault, vault.key(), VaultError::DomainNotInVault);
+            match domain_entry.kind {
+                DomainKind::WrappedSplToken | DomainKind::WrappedToken2022 => wrapped += 1,
+                DomainKind::Unwrapped => unwrapped += 1,
+                _ => {}
+            }
+        }
+
+        let vault_summary = &mut ctx.accounts.vault_summary;
+        vault_summary.vault = vault.key();
+        vault_summary.total_domains = vault.domains_count;
+        vault_summary.wrapped_count = wrapped;
+        vault_summary.unwrapped_count = unwrapped;
+        vault_summary.last_activity_at = Clock::get()?.unix_timestamp;
+        vault_summary.bump = ctx.bumps.vault_summary;
+
+        msg!(
+            "Vault summary created: {} domains ({} wrapped, {} unwrapped)",
+            vault_summary.total_domains,
+            wrapped,
+            unwrapped
+        );
+        Ok(())
+    }
+
+    /// Store an owner-encrypted backup blob for the vault, replacing any previous one
+    /// The program never interprets the contents; encryption is entirely client-side
+    pub fn set_backup_blob(ctx: Context<SetBackupBlob>, data: Vec<u8>) -> Result<()> {
+        require!(
+            !data.is_empty() && data.len() <= MAX_BACKUP_BLOB_LEN,
+            VaultError::InvalidBackupBlob
+        );
+
+        let backup_blob = &mut ctx.accounts.backup_blob;
+        backup_blob.vault = ctx.accounts.vault.key();
+        backup_blob.data = data;
+        backup_blob.updated_at = Clock::get()?.unix_timestamp;
+        backup_blob.bump = ctx.bumps.backup_blob;
+
+        msg!("Backup blob set ({} bytes)", backup_blob.data.len());
+        Ok(())
+    }
+
+    /// Delete the vault's backup blob, refunding its rent to the owner
+    pub fn close_backup_blob(_ctx: Context<CloseBackupBlob>) -> Result<()> {
+        msg!("Backup blob closed");
+        Ok(())
+    }
+
+    /// Set where the indexer/keeper should deliver the vault's alerts, replacing any previous routing
+    /// Webhook identifiers are hashes or other opaque handles resolved off-chain
+    pub fn set_notification_endpoints(
+        ctx: Context<SetNotificationEndpoints>,
+        webhook_ids: Vec<[u8; 32]>,
+        push_program: Option<Pubkey>,
+        alert_mask: u8,
+    ) -> Result<()> {
+        require!(
+            webhook_ids.len() <= MAX_NOTIFICATION_WEBHOOKS
+                && (!webhook_ids.is_empty() || push_program.is_some())
+                && alert_mask != 0
+                && alert_mask & !ALERT_ALL == 0,
+            VaultError::InvalidNotificationEndpoints
+        );
+
+        let notification_endpoints = &mut ctx.accounts.notification_endpoints;
+        notification_endpoints.vault = ctx.accounts.vault.key();
+        notification_endpoints.webhook_ids = webhook_ids;
+        notification_endpoints.push_program = push_program;
+        notification_endpoints.alert_mask = alert_mask;
+        notification_endpoints.updated_at = Clock::get()?.unix_timestamp;
+        notification_endpoints.bump = ctx.bumps.notification_endpoints;
+
+        msg!(
+            "Notification endpoints set: {} webhooks, alerts {:#04x}",
+            notification_endpoints.webhook_ids.len(),
+            alert_mask
+        );
+        Ok(())
+    }
+
+    /// Delete a vault's notification endpoints, stopping alert delivery
+    pub fn close_notification_endpoints(_ctx: Context<CloseNotificationEndpoints>) -> Result<()> {
+        msg!("Notification endpoints closed");
+        Ok(())
+    }
+
+    /// Delegate the voting weight of the vault's domains to `delegate`, replacing any previous one
+    /// Governance programs read the delegation PDA with the vault's live `domains_count` as weight
+    pub fn set_voting_delegate(ctx: Context<SetVotingDelegate>, delegate: Pubkey) -> Result<()> {
+        require_keys_neq!(delegate, Pubkey::default(), VaultError::InvalidVotingDelegate);
+
+        let voting_delegation = &mut ctx.accounts.voting_delegation;
+        let previous = voting_delegation.delegate;
+        voting_delegation.vault = ctx.accounts.vault.key();
+        voting_delegation.owner = ctx.accounts.owner.key();
+        voting_delegation.delegate = delegate;
+        voting_delegation.delegated_at = Clock::get()?.unix_timestamp;
+        voting_delegation.bump = ctx.bumps.voting_delegation;
+
+        emit!(VotingDelegateChanged {
+            vault: voting_delegation.vault,
+            previous_delegate: previous,
+            delegate,
+        });
+        msg!("Voting weight of vault {} delegated to {}", voting_delegation.vault, delegate);
+        Ok(())
+    }
+
+    /// Take back delegated voting weight, closing the delegation
+    pub fn revoke_voting_delegate(ctx: Context<RevokeVotingDelegate>) -> Result<()> {
+        emit!(VotingDelegateChanged {
+            vault: ctx.accounts.vault.key(),
+            previous_delegate: ctx.accounts.voting_delegation.delegate,
+            delegate: Pubkey::default(),
+        });
+        msg!("Voting delegation of vault {} revoked", ctx.accounts.vault.key());
+        Ok(())
+    }
+
+    /// Queue a change of the vault's withdrawal co-signer (Pubkey::default() removes it)
+    /// Setting, replacing and removing all wait `CO_SIGNER_DELAY_SECONDS`
+    pub fn queue_co_signer_change(
+        ctx: Context<QueueCoSignerChange>,
+        new_co_signer: Pubkey,
+    ) -> Result<()> {
+        require_keys_neq!(
+            new_co_signer,
+            ctx.accounts.vault.co_signer,
+            VaultError::CoSignerUnchanged
+        );
+        let eta = Clock::get()?
+            .unix_timestamp
+            .checked_add(CO_SIGNER_DELAY_SECONDS)
+            .unwrap();
+
+        let co_signer_change = &mut ctx.accounts.co_signer_change;
+        co_signer_change.vault = ctx.accounts.vault.key();
+        co_signer_change.new_co_signer = new_co_signer;
+        co_signer_change.eta = eta;
+        co_signer_change.bump = ctx.bumps.co_signer_change;
+        log_security_event(
+            &ctx.accounts.security_log,
+            SecurityEventKind::CoSignerChangeQueued,
+            SecuritySeverity::Warning,
+            co_signer_change.vault,
+        )?;
+
+        msg!("Co-signer change to {} queued, applicable at {}", new_co_signer, eta);
+        Ok(())
+    }
+
+    /// Apply a queued co-signer change after its delay
+    /// Permissionless, so a change can't be held back once it is due
+    pub fn apply_co_signer_change(ctx: Context<ApplyCoSignerChange>) -> Result<()> {
+        let co_signer_change = &ctx.accounts.co_signer_change;
+        require!(
+            Clock::get()?.unix_timestamp >= co_signer_change.eta,
+            VaultError::TimelockNotElapsed
+        );
+
+        let vault = &mut ctx.accounts.vault;
+        let previous_co_signer = vault.co_signer;
+        vault.co_signer = co_signer_change.new_co_signer;
+
+        emit!(AuthorityChanged {
+            subject: vault.key(),
+            role: AuthorityRole::VaultCoSigner,
+            method: AuthorityChangeMethod::TimelockedChange,
+            old_authority: previous_co_signer,
+            new_authority: vault.co_signer,
+            initiated_at: co_signer_change.eta.saturating_sub(CO_SIGNER_DELAY_SECONDS),
+            effective_at: Clock::get()?.unix_timestamp,
+        });
+        msg!("Vault {} co-signer set to {}", vault.key(), vault.co_signer);
+        pay_keeper_bounty(
+            &ctx.accounts.keeper_bounties,
+            &ctx.accounts.keeper,
+            &ctx.accounts.co_signer_change.to_account_info(),
+            CrankKind::ApplyCoSignerChange,
+            true,
+        )?;
+        Ok(())
+    }
+
+    /// Cancel a queued co-signer change; the owner or the current co-signer may cancel
+    pub fn cancel_co_signer_change(ctx: Context<CancelCoSignerChange>) -> Result<()> {
+        msg!(
+            "Co-signer change to {} cancelled",
+            ctx.accounts.co_signer_change.new_co_signer
+        );
+        Ok(())
+    }
+
+    /// Set or replace the vault's withdrawal confirmation phrase (stored as its sha256 hash)
+    /// Withdrawals must then carry the phrase; replacing it requires the current phrase.
+    /// The phrase is public once used, so rotate it after each withdrawal
+    pub fn set_withdrawal_phrase(
+        ctx: Context<SetWithdrawalPhrase>,
+        phrase_hash: [u8; 32],
+        current_phrase: Option<String>,
+    ) -> Result<()> {
+        require!(phrase_hash != [0; 32], VaultError::ConfirmationPhraseMismatch);
+        let withdrawal_phrase = &mut ctx.accounts.withdrawal_phrase;
+        if withdrawal_phrase.phrase_hash != [0; 32] {
+            let current_hash = current_phrase.map(|p| hashv(&[p.as_bytes()]).to_bytes());
+            require!(
+                current_hash == Some(withdrawal_phrase.phrase_hash),
+                VaultError::ConfirmationPhraseMismatch
+            );
+        }
+
+        withdrawal_phrase.vault = ctx.accounts.vault.key();
+        withdrawal_phrase.phrase_hash = phrase_hash;
+        withdrawal_phrase.bump = ctx.bumps.withdrawal_phrase;
+
+        msg!("Withdrawal confirmation phrase set");
+        Ok(())
+    }
+
+    /// Remove the vault's withdrawal confirmation phrase; requires the current phrase
+    pub fn remove_withdrawal_phrase(
+        ctx: Context<RemoveWithdrawalPhrase>,
+        current_phrase: String,
+    ) -> Result<()> {
+        check_withdrawal_phrase(
+            &ctx.accounts.withdrawal_phrase.to_account_info(),
+            Some(&current_phrase),
+        )?;
+
+        msg!("Withdrawal confirmation phrase removed");
+        Ok(())
+    }
+
+    /// Schedule a recurring crank on the vault, replacing the interval of an existing schedule
+    /// The first run is due immediately
+    pub fn schedule_operation(
+        ctx: Context<ScheduleOperation>,
+        kind: ScheduledOperationKind,
+        interval_seconds: i64,
+    ) -> Result<()> {
+        require!(
+            interval_seconds >= MIN_SCHEDULE_INTERVAL_SECONDS,
+            VaultError::InvalidScheduleInterval
+        );
+
+        let scheduled_operation = &mut ctx.accounts.scheduled_operation;
+        if scheduled_operation.vault == Pubkey::default() {
+            scheduled_operation.next_run_at = Clock::get()?.unix_timestamp;
+        }
+        scheduled_operation.vault = ctx.accounts.vault.key();
+        scheduled_operation.kind = kind;
+        scheduled_operation.interval_seconds = interval_seconds;
+        scheduled_operation.bump = ctx.bumps.scheduled_operation;
+
+        msg!("{:?} scheduled every {}s", kind, interval_seconds);
+        Ok(())
+    }
+
+    /// Cancel a scheduled operation
+    pub fn cancel_scheduled_operation(ctx: Context<CancelScheduledOperation>) -> Result<()> {
+        msg!("{:?} schedule cancelled", ctx.accounts.scheduled_operation.kind);
+        Ok(())
+    }
+
+    /// Add lamports to a scheduled operation, escrowed to pay keeper bounties for its runs
+    /// Whatever is left is refunded to the owner when the schedule is cancelled
+    pub fn fund_scheduled_operation(
+        ctx: Context<FundScheduledOperation>,
+        lamports: u64,
+    ) -> Result<()> {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.funder.to_account_info(),
+                    to: ctx.accounts.scheduled_operation.to_account_info(),
+                },
+            ),
+            lamports,
+        )?;
+
+        msg!(
+            "Funded {:?} schedule with {} lamports",
+            ctx.accounts.scheduled_operation.kind,
+            lamports
+        );
+        Ok(())
+    }
+
+    /// Mark a due scheduled operation as run
+    /// Permissionless for automation threads and keepers; only succeeds if a later instruction
+    /// of the same transaction is the scheduled crank for the same vault
+    pub fn run_scheduled_operation(ctx: Context<RunScheduledOperation>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let scheduled_operation = &mut ctx.accounts.scheduled_operation;
+        require!(
+            now >= scheduled_operation.next_run_at,
+            VaultError::ScheduledOperationNotDue
+        );
+
+        let (discriminator, vault_index) = match scheduled_operation.kind {
+            ScheduledOperationKind::Sweep => (crate::instruction::AutoSweep::DISCRIMINATOR, 0),
+            ScheduledOperationKind::PointsAccrual => {
+                (crate::instruction::AccruePoints::DISCRIMINATOR, 1)
+            }
+        };
+        let instructions = ctx.accounts.instructions_sysvar.to_account_info();
+        let current_index = load_current_index_checked(&instructions)? as usize;
+        let mut index = current_index + 1;
+        let mut found = false;
+        while let Ok(ix) = load_instruction_at_checked(index, &instructions) {
+            if ix.program_id == crate::ID
+                && ix.data.starts_with(discriminator)
+                && ix.accounts.get(vault_index).map(|meta| meta.pubkey)
+                    == Some(scheduled_operation.vault)
+            {
+                found = true;
+                break;
+            }
+            index += 1;
+        }
+        require!(found, VaultError::ScheduledCrankMissing);
+
+        // Anchor the next run to now, so a late thread doesn't trigger a burst of catch-up runs
+        scheduled_operation.next_run_at = now
+            .checked_add(scheduled_operation.interval_seconds)
+            .unwrap();
+        scheduled_operation.runs = scheduled_operation.runs.saturating_add(1);
+
+        msg!(
+            "{:?} run #{} for vault {}; next due at {}",
+            scheduled_operation.kind,
+            scheduled_operation.runs,
+            scheduled_operation.vault,
+            scheduled_operation.next_run_at
+        );
+        pay_keeper_bounty(
+            &ctx.accounts.keeper_bounties,
+            &ctx.accounts.keeper,
+            &ctx.accounts.scheduled_operation.to_account_info(),
+            CrankKind::ScheduledRun,
+            false,
+        )?;
+        Ok(())
+    }
+
+    /// Commit the merkle root of domains custodied at `slot`, built off-chain by the indexer (admin only)
+    /// remaining_accounts are distinct [domain_entry, vault] pairs, each proven against the root by
+    /// the matching entry of `sample_proofs`. This only catches mistakes: the admin picks the
+    /// samples, they are checked against current custody rather than `slot`, and `leaf_count` is
+    /// taken as given, so the root is as trustworthy as the admin and indexer that built it
+    pub fn commit_custody_snapshot<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CommitCustodySnapshot<'info>>,
+        slot: u64,
+        root: [u8; 32],
+        leaf_count: u64,
+        sample_proofs: Vec<Vec<[u8; 32]>>,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        require!(slot <= clock.slot, VaultError::InvalidCustodySnapshot);
+        require!(
+            !sample_proofs.is_empty()
+                && sample_proofs.len() <= MAX_SNAPSHOT_SAMPLES
+                && ctx.remaining_accounts.len() == sample_proofs.len() * 2
+                && leaf_count >= sample_proofs.len() as u64,
+            VaultError::InvalidCustodySnapshot
+        );
+
+        for (i, (pair, proof)) in ctx
+            .remaining_accounts
+            .chunks(2)
+            .zip(sample_proofs.iter())
+            .enumerate()
+        {
+            require!(
+                proof.len() <= MAX_SNAPSHOT_PROOF_LEN,
+                VaultError::InvalidCustodySnapshot
+            );
+            require!(
+                ctx.remaining_accounts[..i * 2]
+                    .chunks(2)
+                    .all(|earlier| earlier[0].key != pair[0].key),
+                VaultError::InvalidCustodySnapshot
+            );
+            let domain_entry = Account::<DomainEntry>::try_from(&pair[0])?;
+            let vault = Account::<UserVault>::try_from(&pair[1])?;
+            require_keys_eq!(domain_entry.vault, vault.key(), VaultError::DomainNotInVault);
+            require!(
+                verify_custody_proof(
+                    &root,
+                    &custody_snapshot_leaf(&vault.owner, &domain_entry.domain),
+                    proof
+                ),
+                VaultError::CustodyProofMismatch
+            );
+        }
+
+        let custody_snapshot = &mut ctx.accounts.custody_snapshot;
+        custody_snapshot.slot = slot;
+        custody_snapshot.root = root;
+        custody_snapshot.leaf_count = leaf_count;
+        custody_snapshot.samples_checked = sample_proofs.len() as u8;
+        custody_snapshot.committed_at = clock.unix_timestamp;
+        custody_snapshot.bump = ctx.bumps.custody_snapshot;
+
+        emit!(CustodySnapshotCommitted {
+            slot,
+            root,
+            leaf_count,
+        });
+        msg!(
+            "Custody snapshot at slot {} committed: {} domains claimed, {} entries spot-checked",
+            slot,
+            leaf_count,
+            sample_proofs.len()
+        );
+        Ok(())
+    }
+
+    /// Issue a short-lived subdomain `label` under a vaulted unwrapped parent, owned by the vault
+    /// Handing out a fresh burner per context avoids reusing one public name everywhere
+    pub fn issue_burner_subdomain(
+        ctx: Context<IssueBurnerSubdomain>,
+        label: String,
+        lifetime_seconds: i64,
+    ) -> Result<()> {
+        require!(!ctx.accounts.config.paused, VaultError::ProtocolPaused);
+        require!(
+            !ctx.accounts.parent_entry.records_locked,
+            VaultError::RecordsLocked
+        );
+        require!(
+            is_burner_label(&label),
+            VaultError::InvalidBurnerSubdomain
+        );
+        require!(
+            (1..=MAX_BURNER_LIFETIME_SECONDS).contains(&lifetime_seconds),
+            VaultError::InvalidBurnerSubdomain
+        );
+        require!(
+            ctx.accounts.parent_entry.kind == DomainKind::Unwrapped,
+            VaultError::UnsupportedDomainKind
+        );
+        check_cpi_program(&ctx.accounts.config, ctx.accounts.name_service_program.key)?;
+
+        // SNS subdomain names are hashed with a leading NUL
+        let hashed_name = hash_domain_name(&format!("\0{}", label));
+        let parent_key = ctx.accounts.parent_name_account.key();
+        let (subdomain_key, _) = Pubkey::find_program_address(
+            &[&hashed_name, Pubkey::default().as_ref(), parent_key.as_ref()],
+            &NAME_SERVICE_PROGRAM_ID,
+        );
+        require_keys_eq!(
+            ctx.accounts.subdomain_account.key(),
+            subdomain_key,
+            VaultError::InvalidBurnerSubdomain
+        );
+
+        let vault_key = ctx.accounts.vault.key();
+        let owner_key = ctx.accounts.owner.key();
+        let signer_seeds: &[&[u8]] = &[VAULT_SEED, owner_key.as_ref(), &[ctx.accounts.vault.bump]];
+
+        // Create instruction = 0: hashed_name (borsh Vec<u8>), lamports, space
+        let lamports = Rent::get()?.minimum_balance(NAME_REGISTRY_HEADER_LEN);
+        let mut create_data = vec![0u8];
+        create_data.extend_from_slice(&(hashed_name.len() as u32).to_le_bytes());
+        create_data.extend_from_slice(&hashed_name);
+        create_data.extend_from_slice(&lamports.to_le_bytes());
+        create_data.extend_from_slice(&0u32.to_le_bytes());
+
+        let create_ix = anchor_lang::solana_program::instruction::Instruction {
+            program_id: NAME_SERVICE_PROGRAM_ID,
+            accounts: vec![
+                anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                    ctx.accounts.system_program.key(),
+                    false,
+                ),
+                anchor_lang::solana_program::instruction::AccountMeta::new(owner_key, true),
+                anchor_lang::solana_program::instruction::AccountMeta::new(subdomain_key, false),
+                // New owner: the vault, so the subdomain can be deleted on expiry
+                anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                    vault_key, false,
+                ),
+                // No class
+                anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                    Pubkey::default(),
+                    false,
+                ),
+                anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                    parent_key, false,
+                ),
+                // Parent owner (vault PDA - must sign)
+                anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                    vault_key, true,
+                ),
+            ],
+            data: create_data,
+        };
+        invoke_sns(
+            &create_ix,
+            &[
+                ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.owner.to_account_info(),
+                ctx.accounts.subdomain_account.to_account_info(),
+                ctx.accounts.vault.to_account_info(),
+                ctx.accounts.parent_name_account.to_account_info(),
+                ctx.accounts.name_service_program.to_account_info(),
+            ],
+            &[signer_seeds],
+            SnsCpiStep::Create,
+            subdomain_key,
+        )?;
+
+        let expires_at = Clock::get()?
+            .unix_timestamp
+            .checked_add(lifetime_seconds)
+            .unwrap();
+        let burner = &mut ctx.accounts.burner_subdomain;
+        burner.vault = vault_key;
+        burner.parent = parent_key;
+        burner.subdomain = subdomain_key;
+        burner.expires_at = expires_at;
+        burner.bump = ctx.bumps.burner_subdomain;
+
+        msg!("Burner subdomain {} issued until {}", subdomain_key, expires_at);
+        Ok(())
+    }
+
+    /// Delete a burner subdomain, refunding its rent to the vault owner
+    /// Permissionless once expired, so a crank can revoke burners; the owner may revoke any time
+    pub fn revoke_burner_subdomain(ctx: Context<RevokeBurnerSubdomain>) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.vault.owner
+                || Clock::get()?.unix_timestamp >= ctx.accounts.burner_subdomain.expires_at,
+            VaultError::BurnerNotExpired
+        );
+        check_cpi_program(&ctx.accounts.config, ctx.accounts.name_service_program.key)?;
+
+        let owner_key = ctx.accounts.vault.owner;
+        let signer_seeds: &[&[u8]] = &[VAULT_SEED, owner_key.as_ref(), &[ctx.accounts.vault.bump]];
+        let subdomain_key = ctx.accounts.subdomain_account.key();
+
+        // Delete instruction = 3
+        let delete_ix = anchor_lang::solana_program::instruction::Instruction {
+            program_id: NAME_SERVICE_PROGRAM_ID,
+            accounts: vec![
+                anchor_lang::solana_program::instruction::AccountMeta::new(subdomain_key, false),
+                // Name owner (vault PDA - must sign)
+                anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                    ctx.accounts.vault.key(),
+                    true,
+                ),
+                anchor_lang::solana_program::instruction::AccountMeta::new(owner_key, false),
+            ],
+            data: vec![3u8],
+        };
+        invoke_sns(
+            &delete_ix,
+            &[
+                ctx.accounts.subdomain_account.to_account_info(),
+                ctx.accounts.vault.to_account_info(),
+                ctx.accounts.owner.to_account_info(),
+                ctx.accounts.name_service_program.to_account_info(),
+            ],
+            &[signer_seeds],
+            SnsCpiStep::Delete,
+            subdomain_key,
+        )?;
+
+        msg!("Burner subdomain {} revoked", subdomain_key);
+        Ok(())
+    }
+
+    /// Initialize the global protocol config
+    /// Only the program upgrade authority can create it and becomes the first admin
+    pub fn initialize_config(ctx: Context<InitializeConfig>, timelock_seconds: i64) -> Result<()> {
+        require!(
+            (0..=MAX_TIMELOCK_SECONDS).contains(&timelock_seconds),
+            VaultError::InvalidTimelock
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.authority.key();
+        config.pending_admin = None;
+        config.admin_nominated_at = 0;
+        config.governance = None;
+        config.pending_governance = None;
+        config.timelock_seconds = timelock_seconds;
+        config.paused = false;
+        config.mint_allowlist_mode = false;
+        config.fee_schedule = [0; FEE_OPERATION_COUNT];
+        config.points_weights = PointsWeights::default();
+        config.staking = StakingParams::default();
+        config.claim_programs = Vec::new();
+        config.cpi_programs = vec![
+            NAME_SERVICE_PROGRAM_ID,
+            SNS_RECORDS_PROGRAM_ID,
+            NAME_TOKENIZER_PROGRAM_ID,
+            anchor_spl::token::ID,
+            anchor_spl::token_2022::ID,
+        ];
+        config.next_param_change_id = 0;
+        config.bump = ctx.bumps.config;
+
+        let now = Clock::get()?.unix_timestamp;
+        emit!(AuthorityChanged {
+            subject: config.key(),
+            role: AuthorityRole::ProtocolAdmin,
+            method: AuthorityChangeMethod::Initialization,
+            old_authority: Pubkey::default(),
+            new_authority: config.admin,
+            initiated_at: now,
+            effective_at: now,
+        });
+
+        msg!(
+            "Protocol config initialized. Admin: {}, timelock: {}s",
+            config.admin,
+            config.timelock_seconds
+        );
+        Ok(())
+    }
+
+    /// Nominate a new admin (step 1 of 2)
+    /// The nominee must call `accept_admin` once the config timelock has elapsed
+    pub fn nominate_admin(ctx: Context<UpdateConfig>, new_admin: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.pending_admin = Some(new_admin);
+        config.pending_governance = None;
+        config.admin_nominated_at = Clock::get()?.unix_timestamp;
+
+        msg!(
+            "Admin {} nominated, can accept in {}s",
+            new_admin,
+            config.timelock_seconds
+        );
+        Ok(())
+    }
+
+    /// Cancel a pending admin nomination
+    pub fn cancel_admin_nomination(ctx: Context<UpdateConfig>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(config.pending_admin.is_some(), VaultError::NoPendingAdmin);
+
+        config.pending_admin = None;
+        config.pending_governance = None;
+        config.admin_nominated_at = 0;
+
+        msg!("Pending admin nomination cancelled");
+        Ok(())
+    }
+
+    /// Accept the admin role (step 2 of 2)
+    /// Must be signed by the nominated key, so a typo'd nomination can never brick the config
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(
+            config.pending_admin == Some(ctx.accounts.pending_admin.key()),
+            VaultError::NoPendingAdmin
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let unlocks_at = config
+            .admin_nominated_at
+            .checked_add(config.timelock_seconds)
+            .unwrap();
+        require!(now >= unlocks_at, VaultError::TimelockNotElapsed);
+
+        let previous_admin = config.admin;
+        emit!(AuthorityChanged {
+            subject: config.key(),
+            role: AuthorityRole::ProtocolAdmin,
+            method: AuthorityChangeMethod::Transfer,
+            old_authority: previous_admin,
+            new_authority: ctx.accounts.pending_admin.key(),
+            initiated_at: config.admin_nominated_at,
+            effective_at: now,
+        });
+        config.admin = ctx.accounts.pending_admin.key();
+        config.governance = config.pending_governance.take();
+        config.pending_admin = None;
+        config.admin_nominated_at = 0;
+
+        msg!("Admin changed from {} to {}", previous_admin, config.admin);
+        Ok(())
+    }
+
+    /// Nominate an SPL Governance native treasury as the new admin
+    /// Once accepted through a governance proposal, all config changes require a passed vote
+    pub fn nominate_governance_admin(ctx: Context<NominateGovernanceAdmin>) -> Result<()> {
+        // Realm and governance layouts both start with account_type (u8) followed by the realm key
+        let realm_data = ctx.accounts.realm.try_borrow_data()?;
+        require!(
+            !realm_data.is_empty() && GOVERNANCE_REALM_ACCOUNT_TYPES.contains(&realm_data[0]),
+            VaultError::InvalidGovernanceAccount
+        );
+
+        let governance_data = ctx.accounts.governance.try_borrow_data()?;
+        require!(
+            governance_data.len() >= 33 && GOVERNANCE_ACCOUNT_TYPES.contains(&governance_data[0]),
+            VaultError::InvalidGovernanceAccount
+        );
+        require!(
+            governance_data[1..33] == ctx.accounts.realm.key().to_bytes(),
+            VaultError::InvalidGovernanceAccount
+        );
+
+        let native_treasury = ctx.accounts.native_treasury.key();
+        let governance = ctx.accounts.governance.key();
+        let config = &mut ctx.accounts.config;
+        config.pending_admin = Some(native_treasury);
+        config.pending_governance = Some(governance);
+        config.admin_nominated_at = Clock::get()?.unix_timestamp;
+
+        msg!(
+            "Governance {} (treasury {}) nominated as admin, can accept in {}s",
+            governance,
+            native_treasury,
+            config.timelock_seconds
+        );
+        Ok(())
+    }
+
+    /// Update the config timelock
+    /// Raising it takes effect immediately; while a timelock is active, lowering it must be queued
+    /// as `ParamChange::SetTimelock`, so the decrease itself waits out the current delay
+    pub fn set_timelock(ctx: Context<UpdateConfig>, timelock_seconds: i64) -> Result<()> {
+        require!(
+            (0..=MAX_TIMELOCK_SECONDS).contains(&timelock_seconds),
+            VaultError::InvalidTimelock
+        );
+
+        let config = &mut ctx.accounts.config;
+        require!(
+            timelock_seconds >= config.timelock_seconds || config.timelock_seconds == 0,
+            VaultError::ParamChangeTimelocked
+        );
+        config.timelock_seconds = timelock_seconds;
+
+        msg!("Config timelock set to {}s", timelock_seconds);
+        Ok(())
+    }
+
+    /// Pause deposits immediately, or unpause while no timelock is active
+    /// Withdrawals are never paused so users can always exit
+    pub fn set_paused(ctx: Context<UpdateConfig>, paused: bool) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(
+            paused || config.timelock_seconds == 0,
+            VaultError::ParamChangeTimelocked
+        );
+        config.paused = paused;
+
+        msg!("Protocol paused: {}", paused);
+        Ok(())
+    }
+
+    /// Set the fee charged for an operation
+    /// Lowering a fee is immediate; raising it must go through the timelock while one is set
+    pub fn set_fee(ctx: Context<UpdateConfig>, operation: FeeOperation, lamports: u64) -> Result<()> {
+        require!(lamports <= MAX_FEE_LAMPORTS, VaultError::FeeTooHigh);
+        require!(
+            operation != FeeOperation::Withdraw || lamports == 0,
+            VaultError::WithdrawalsAreFeeFree
+        );
+
+        let config = &mut ctx.accounts.config;
+        require!(
+            lamports <= config.fee_schedule[operation as usize] || config.timelock_seconds == 0,
+            VaultError::ParamChangeTimelocked
+        );
+        config.fee_schedule[operation as usize] = lamports;
+
+        msg!("{:?} fee set to {} lamports", operation, lamports);
+        Ok(())
+    }
+
+    /// Set the points credited per custodied domain-day
+    /// Lowering the rate must go through the timelock while one is set
+    pub fn set_points_weights(ctx: Context<UpdateConfig>, weights: PointsWeights) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(
+            weights.custody_per_domain_day >= config.points_weights.custody_per_domain_day
+                || config.timelock_seconds == 0,
+            VaultError::ParamChangeTimelocked
+        );
+        config.points_weights = weights;
+
+        msg!(
+            "Points weights set: custody per domain-day {}",
+            weights.custody_per_domain_day
+        );
+        Ok(())
+    }
+
+    /// Post the floor prices of the domain valuation table (admin only)
+    /// `length_floors[i]` applies to names of i + 1 characters, the last tier to longer names;
+    /// a name's floor is the higher of its length and character-category floors
+    /// While a timelock is set, the matching queued `SetValuationFloors` change must be passed
+    pub fn set_valuation_floors(
+        ctx: Context<SetValuationFloors>,
+        length_floors: [u64; VALUATION_LENGTH_TIERS],
+        category_floors: [u64; NAME_CATEGORIES],
+    ) -> Result<()> {
+        consume_queued_change(
+            &ctx.accounts.config,
+            &ctx.accounts.queued_change,
+            &ctx.accounts.payer,
+            ParamChange::SetValuationFloors {
+                length_floors,
+                category_floors,
+            },
+        )?;
+
+        let valuation_table = &mut ctx.accounts.valuation_table;
+        valuation_table.length_floors = length_floors;
+        valuation_table.category_floors = category_floors;
+        valuation_table.updated_at = Clock::get()?.unix_timestamp;
+        valuation_table.bump = ctx.bumps.valuation_table;
+
+        msg!("Valuation floors updated");
+        Ok(())
+    }
+
+    /// Quote the floor price of a vaulted domain from the valuation table, in lamports
+    /// `name` is the domain without ".sol" and must derive the entry's domain
+    pub fn quote_floor_price(ctx: Context<QuoteFloorPrice>, name: String) -> Result<u64> {
+        let name_account = get_domain_key(&name);
+        let domain_entry = &ctx.accounts.domain_entry;
+        let expected = match domain_entry.kind {
+            DomainKind::Unwrapped => name_account,
+            DomainKind::WrappedSplToken | DomainKind::WrappedToken2022 => {
+                get_tokenized_mint_key(&name_account).0
+            }
+            _ => return err!(VaultError::UnsupportedDomainKind),
+        };
+        require_keys_eq!(expected, domain_entry.domain, VaultError::DomainNotInVault);
+
+        let floor = floor_price(&ctx.accounts.valuation_table, &name);
+        msg!("Floor price of {}.sol: {} lamports", name, floor);
+        Ok(floor)
+    }
+
+    /// Credit custody points accrued since the last update
+    /// Permissionless so keepers can keep ledgers current for vaults that see no activity
+    pub fn accrue_points(ctx: Context<AccruePoints>) -> Result<()> {
+        let bump = ctx.accounts.user_points.bump;
+        credit_points(
+            &mut ctx.accounts.user_points,
+            Some(&ctx.accounts.config),
+            &ctx.accounts.vault,
+            bump,
+        )
+    }
+
+    /// Create the protocol fee treasury
+    pub fn initialize_treasury(ctx: Context<InitializeTreasury>) -> Result<()> {
+        let treasury = &mut ctx.accounts.treasury;
+        treasury.total_collected = 0;
+        treasury.bump = ctx.bumps.treasury;
+
+        msg!("Treasury initialized: {}", treasury.key());
+        Ok(())
+    }
+
+    /// Create the global security log (admin only)
+    pub fn initialize_security_log(ctx: Context<InitializeSecurityLog>) -> Result<()> {
+        ctx.accounts.security_log.bump = ctx.bumps.security_log;
+
+        msg!("Security log initialized with {} slots", SECURITY_LOG_CAPACITY);
+        Ok(())
+    }
+
+    /// Set the lamports paid to keepers per run of a crank (admin only), 0 disabling its bounty
+    /// Bounties come out of the crank's own escrow, never the treasury
+    pub fn set_keeper_bounty(
+        ctx: Context<SetKeeperBounty>,
+        crank: CrankKind,
+        lamports: u64,
+    ) -> Result<()> {
+        let keeper_bounties = &mut ctx.accounts.keeper_bounties;
+        keeper_bounties.lamports[crank as usize] = lamports;
+        keeper_bounties.bump = ctx.bumps.keeper_bounties;
+
+        msg!("{:?} keeper bounty set to {} lamports", crank, lamports);
+        Ok(())
+    }
+
+    /// Configure the outflow circuit breaker (admin only), creating it on first use
+    /// Withdrawals halt once a window's outflow exceeds `trip_multiple` times the trailing
+    /// average of past windows, and at least `min_trip_outflow` domains; emergency_withdraw_all
+    /// is still counted but never halted
+    pub fn configure_outflow_breaker(
+        ctx: Context<ConfigureOutflowBreaker>,
+        window_seconds: i64,
+        trip_multiple: u32,
+        min_trip_outflow: u64,
+    ) -> Result<()> {
+        require!(
+            window_seconds > 0 && trip_multiple > 0,
+            VaultError::InvalidBreakerParams
+        );
+
+        let outflow_breaker = &mut ctx.accounts.outflow_breaker;
+        if outflow_breaker.window_start == 0 {
+            outflow_breaker.window_start = Clock::get()?.unix_timestamp;
+        }
+        outflow_breaker.window_seconds = window_seconds;
+        outflow_breaker.trip_multiple = trip_multiple;
+        outflow_breaker.min_trip_outflow = min_trip_outflow;
+        outflow_breaker.bump = ctx.bumps.outflow_breaker;
+
+        msg!(
+            "Outflow breaker: {}s windows, trips above {}x trailing average (min {})",
+            window_seconds,
+            trip_multiple,
+            min_trip_outflow
+        );
+        Ok(())
+    }
+
+    /// Reset a tripped outflow circuit breaker, resuming withdrawals (admin only)
+    pub fn reset_outflow_breaker(ctx: Context<ResetOutflowBreaker>) -> Result<()> {
+        let outflow_breaker = &mut ctx.accounts.outflow_breaker;
+        outflow_breaker.tripped = false;
+        outflow_breaker.window_start = Clock::get()?.unix_timestamp;
+        outflow_breaker.window_outflow = 0;
+        log_security_event(
+            &ctx.accounts.security_log,
+            SecurityEventKind::OutflowBreakerReset,
+            SecuritySeverity::Info,
+            outflow_breaker.key(),
+        )?;
+
+        msg!("Outflow breaker reset");
+        Ok(())
+    }
+
+    /// Create the global protocol stats account
+    pub fn initialize_stats(ctx: Context<InitializeStats>) -> Result<()> {
+        let stats = &mut ctx.accounts.stats;
+        stats.bump = ctx.bumps.stats;
+
+        msg!("Protocol stats initialized: {}", stats.key());
+        Ok(())
+    }
+
+    /// Write this build's version and feature bits to the program info PDA
+    /// Permissionless: the values are compiled in, so anyone can publish them after an upgrade
+    pub fn sync_program_info(ctx: Context<SyncProgramInfo>) -> Result<()> {
+        let program_info = &mut ctx.accounts.program_info;
+        write_program_info(program_info, Clock::get()?.slot);
+        program_info.bump = ctx.bumps.program_info;
+
+        let [major, minor, patch] = PROGRAM_VERSION;
+        msg!(
+            "Program info synced: v{}.{}.{}, features {:#x}",
+            major,
+            minor,
+            patch,
+            ENABLED_FEATURES
+        );
+        Ok(())
+    }
+
+    /// Withdraw collected fees from the treasury, keeping it rent exempt
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+        let treasury_info = ctx.accounts.treasury.to_account_info();
+        let rent_exempt_minimum = Rent::get()?.minimum_balance(treasury_info.data_len());
+        let available = treasury_info
+            .lamports()
+            .saturating_sub(rent_exempt_minimum);
+        require!(amount <= available, VaultError::InsufficientTreasuryBalance);
+
+        ctx.accounts.treasury.sub_lamports(amount)?;
+        ctx.accounts.destination.add_lamports(amount)?;
+
+        msg!(
+            "Withdrew {} lamports from treasury to {}",
+            amount,
+            ctx.accounts.destination.key()
+        );
+        Ok(())
+    }
+
+    /// Set the staking reward rate and unstake cooldown
+    /// Cutting the rate or raising the cooldown must go through the timelock while one is set,
+    /// since the new rate also applies to rewards accrued but not yet settled
+    pub fn set_staking_params(ctx: Context<UpdateConfig>, staking: StakingParams) -> Result<()> {
+        require!(
+            (0..=MAX_UNSTAKE_COOLDOWN_SECONDS).contains(&staking.unstake_cooldown_seconds),
+            VaultError::InvalidCooldown
+        );
+
+        let config = &mut ctx.accounts.config;
+        require!(
+            (staking.reward_lamports_per_domain_day >= config.staking.reward_lamports_per_domain_day
+                && staking.unstake_cooldown_seconds <= config.staking.unstake_cooldown_seconds)
+                || config.timelock_seconds == 0,
+            VaultError::ParamChangeTimelocked
+        );
+        config.staking = staking;
+
+        msg!(
+            "Staking params set: {} lamports per domain-day, {}s cooldown",
+            staking.reward_lamports_per_domain_day,
+            staking.unstake_cooldown_seconds
+        );
+        Ok(())
+    }
+
+    /// Add or remove a program on the airdrop claim allowlist
+    /// Removal is immediate; adding must go through the timelock while one is set
+    pub fn set_claim_program(ctx: Context<UpdateConfig>, program: Pubkey, allowed: bool) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(
+            !allowed || config.timelock_seconds == 0,
+            VaultError::ParamChangeTimelocked
+        );
+        set_claim_program_allowed(config, program, allowed)?;
+
+        msg!("Claim program {} allowed: {}", program, allowed);
+        Ok(())
+    }
+
+    /// Add or remove a program on the CPI allowlist
+    /// Removal is immediate; adding must go through the timelock while one is set
+    pub fn set_cpi_program(ctx: Context<UpdateConfig>, program: Pubkey, allowed: bool) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(
+            !allowed || config.timelock_seconds == 0,
+            VaultError::ParamChangeTimelocked
+        );
+        set_cpi_program_allowed(config, program, allowed)?;
+
+        msg!("CPI program {} allowed: {}", program, allowed);
+        Ok(())
+    }
+
+    /// Allow or deny a mint for `deposit_domain`
+    /// While a timelock is set, the matching queued `SetMintPolicy` change must be passed
+    pub fn set_mint_policy(ctx: Context<SetMintPolicy>, status: MintStatus) -> Result<()> {
+        consume_queued_change(
+            &ctx.accounts.config,
+            &ctx.accounts.queued_change,
+            &ctx.accounts.payer,
+            ParamChange::SetMintPolicy {
+                mint: ctx.accounts.mint.key(),
+                status,
+            },
+        )?;
+
+        let mint_policy = &mut ctx.accounts.mint_policy;
+        mint_policy.mint = ctx.accounts.mint.key();
+        mint_policy.status = status;
+        mint_policy.bump = ctx.bumps.mint_policy;
+
+        msg!("Mint {} policy set to {:?}", mint_policy.mint, status);
+        Ok(())
+    }
+
+    /// Remove a mint policy, returning the mint to the default behaviour
+    pub fn remove_mint_policy(ctx: Context<RemoveMintPolicy>) -> Result<()> {
+        msg!("Mint {} policy removed", ctx.accounts.mint_policy.mint);
+        Ok(())
+    }
+
+    /// Queue a parameter change, executable once the config timelock has elapsed
+    /// The queued event gives users time to exit before the change takes effect
+    pub fn queue_param_change(ctx: Context<QueueParamChange>, change: ParamChange) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let now = Clock::get()?.unix_timestamp;
+        let eta = now
+            .checked_add(config.timelock_seconds)
+            .ok_or(VaultError::ParamChangeOverflow)?;
+
+        let queued_change = &mut ctx.accounts.queued_change;
+        queued_change.id = config.next_param_change_id;
+        queued_change.change = change.clone();
+        queued_change.queued_at = now;
+        queued_change.eta = eta;
+        queued_change.payer = ctx.accounts.admin.key();
+        queued_change.bump = ctx.bumps.queued_change;
+
+        config.next_param_change_id = config
+            .next_param_change_id
+            .checked_add(1)
+            .ok_or(VaultError::ParamChangeOverflow)?;
+
+        emit!(ParamChangeQueued {
+            id: queued_change.id,
+            change,
+            eta,
+        });
+
+        msg!("Param change {} queued, executable at {}", queued_change.id, eta);
+        Ok(())
+    }
+
+    /// Execute a queued parameter change after its ETA
+    /// Permissionless, so a change can't be held back once it is due
+    /// Changes to accounts other than the config are applied by their setter instead
+    pub fn execute_param_change(ctx: Context<ExecuteParamChange>) -> Result<()> {
+        let queued_change = &ctx.accounts.queued_change;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= queued_change.eta, VaultError::TimelockNotElapsed);
+
+        let config = &mut ctx.accounts.config;
+        apply_param_change(config, &queued_change.change)?;
+
+        emit!(ParamChangeExecuted {
+            id: queued_change.id,
+            change: queued_change.change.clone(),
+        });
+
+        msg!("Param change {} executed", queued_change.id);
+        Ok(())
+    }
+
+    /// Cancel a queued parameter change
+    pub fn cancel_param_change(ctx: Context<CancelParamChange>) -> Result<()> {
+        let queued_change = &ctx.accounts.queued_change;
+
+        emit!(ParamChangeCancelled {
+            id: queued_change.id,
+            change: queued_change.change.clone(),
+        });
+
+        msg!("Param change {} cancelled", queued_change.id);
+        Ok(())
+    }
+}
+
+/// Apply a parameter change to the config, validating it the same way the direct setters do
+fn apply_param_change(config: &mut ProtocolConfig, change: &ParamChange) -> Result<()> {
+    match change {
+        ParamChange::SetPaused { paused } => {
+            config.paused = *paused;
+        }
+        ParamChange::SetMintAllowlistMode { enabled } => {
+            config.mint_allowlist_mode = *enabled;
+        }
+        ParamChange::SetFee { operation, lamports } => {
+            require!(*lamports <= MAX_FEE_LAMPORTS, VaultError::FeeTooHigh);
+            require!(
+                *operation != FeeOperation::Withdraw || *lamports == 0,
+                VaultError::WithdrawalsAreFeeFree
+            );
+            config.fee_schedule[*operation as usize] = *lamports;
+        }
+        ParamChange::SetStakingParams { staking } => {
+            require!(
+                (0..=MAX_UNSTAKE_COOLDOWN_SECONDS).contains(&staking.unstake_cooldown_seconds),
+                VaultError::InvalidCooldown
+            );
+            config.staking = *staking;
+        }
+        ParamChange::SetClaimProgram { program, allowed } => {
+            set_claim_program_allowed(config, *program, *allowed)?;
+        }
+        ParamChange::SetTimelock { timelock_seconds } => {
+            require!(
+                (0..=MAX_TIMELOCK_SECONDS).contains(timelock_seconds),
+                VaultError::InvalidTimelock
+            );
+            config.timelock_seconds = *timelock_seconds;
+        }
+        ParamChange::SetCpiProgram { program, allowed } => {
+            set_cpi_program_allowed(config, *program, *allowed)?;
+        }
+        ParamChange::SetPointsWeights { weights } => {
+            config.points_weights = *weights;
+        }
+        ParamChange::SetMintPolicy { .. } | ParamChange::SetValuationFloors { .. } => {
+            return err!(VaultError::ParamChangeAppliedBySetter);
+        }
+    }
+    Ok(())
+}
+
+/// Consume the queued change a setter needs while a timelock is set, closing it to its payer
+/// Used by setters whose target account isn't the config, so `execute_param_change` can't apply it
+fn consume_queued_change<'info>(
+    config: &ProtocolConfig,
+    queued_change: &Option<Account<'info, QueuedParamChange>>,
+    payer: &Option<UncheckedAccount<'info>>,
+    change: ParamChange,
+) -> Result<()> {
+    if config.timelock_seconds == 0 {
+        return Ok(());
+    }
+    let (Some(queued_change), Some(payer)) = (queued_change, payer) else {
+        return err!(VaultError::ParamChangeTimelocked);
+    };
+    require!(queued_change.change == change, VaultError::ParamChangeMismatch);
+    require!(
+        Clock::get()?.unix_timestamp >= queued_change.eta,
+        VaultError::TimelockNotElapsed
+    );
+    require_keys_eq!(queued_change.payer, payer.key(), VaultError::UnauthorizedAccess);
+
+    emit!(ParamChangeExecuted {
+        id: queued_change.id,
+        change,
+    });
+    msg!("Param change {} executed", queued_change.id);
+
+    queued_change.close(payer.to_account_info())
+}
+
+/// Charge the configured fee for an operation, moving lamports from the payer to the treasury
+fn charge_fee<'info>(
+    config: &ProtocolConfig,
+    operation: FeeOperation,
+    payer: &Signer<'info>,
+    treasury: &mut Account<'info, Treasury>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let fee = config.fee_schedule[operation as usize];
+    if fee == 0 {
+        return Ok(());
+    }
+
+    anchor_lang::system_program::transfer(
+        CpiContext::new(
+            system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: payer.to_account_info(),
+                to: treasury.to_account_info(),
+            },
+        ),
+        fee,
+    )?;
+    treasury.total_collected = treasury.total_collected.checked_add(fee).unwrap();
+
+    msg!("Charged {:?} fee of {} lamports", operation, fee);
+    Ok(())
+}
+
+/// Credit custody points for the time since the last accrual
+/// Points only accrue per custodied domain-second, so deposit/withdraw loops earn nothing
+/// Must run before the vault's domain count changes so custody is credited at the old count
+/// Without a config (exits before it is initialized) the elapsed time is settled at no points
+fn credit_points(
+    user_points: &mut Account<UserPoints>,
+    config: Option<&ProtocolConfig>,
+    vault: &UserVault,
+    bump: u8,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    // Freshly created ledger: start accruing from now
+    if user_points.owner == Pubkey::default() {
+        user_points.owner = vault.owner;
+        user_points.points = 0;
+        user_points.last_accrual_at = now;
+        user_points.bump = bump;
+    }
+    require_keys_eq!(user_points.owner, vault.owner, VaultError::UnauthorizedAccess);
+
+    let elapsed = now.saturating_sub(user_points.last_accrual_at).max(0) as u128;
+    let custody_per_domain_day =
+        config.map_or(0, |config| config.points_weights.custody_per_domain_day);
+    // Saturating: an oversized admin-set weight caps points instead of failing custody moves
+    let custody_points = elapsed
+        .saturating_mul(vault.domains_count as u128)
+        .saturating_mul(custody_per_domain_day as u128)
+        / SECONDS_PER_DAY as u128;
+    let custody_points = u64::try_from(custody_points).unwrap_or(u64::MAX);
+
+    user_points.points = user_points.points.saturating_add(custody_points);
+    user_points.last_accrual_at = now;
+
+    msg!(
+        "Credited {} custody points. Total: {}",
+        custody_points,
+        user_points.points
+    );
+    Ok(())
+}
+
+/// Add or remove a program from the airdrop claim allowlist
+fn set_claim_program_allowed(config: &mut ProtocolConfig, program: Pubkey, allowed: bool) -> Result<()> {
+    // SNS and token programs are never claim targets, so the generic vault-signed CPI can't
+    // bypass record locks or approve, freeze or re-authorize vault token accounts
+    require!(
+        !allowed || !is_protected_program(&program),
+        VaultError::ClaimProgramNotAllowed
+    );
+    let listed = config.claim_programs.contains(&program);
+    if allowed && !listed {
+        require!(
+            config.claim_programs.len() < MAX_CLAIM_PROGRAMS,
+            VaultError::TooManyClaimPrograms
+        );
+        config.claim_programs.push(program);
+    } else if !allowed {
+        config.claim_programs.retain(|p| *p != program);
+    }
+    Ok(())
+}
+
+fn set_cpi_program_allowed(config: &mut ProtocolConfig, program: Pubkey, allowed: bool) -> Result<()> {
+    let listed = config.cpi_programs.contains(&program);
+    if allowed && !listed {
+        require!(
+            config.cpi_programs.len() < MAX_CPI_PROGRAMS,
+            VaultError::TooManyCpiPrograms
+        );
+        config.cpi_programs.push(program);
+    } else if !allowed {
+        config.cpi_programs.retain(|p| *p != program);
+    }
+    Ok(())
+}
+
+/// Require `program` to be on the config's CPI allowlist before the program invokes it
+fn check_cpi_program(config: &ProtocolConfig, program: &Pubkey) -> Result<()> {
+    require!(
+        config.cpi_programs.contains(program),
+        VaultError::CpiProgramNotAllowed
+    );
+    Ok(())
+}
+
+/// Amount of vault-owned assets held by an account: the token amount for vault-owned
+/// token accounts, 1 for name registries owned by the vault, 0 otherwise
+fn vault_asset_amount(vault: &Pubkey, account: &AccountInfo) -> Result<u64> {
+    let data = account.try_borrow_data()?;
+    let owner_field_matches = data.len() >= 64 && data[32..64] == vault.to_bytes();
+
+    // Token account layout: mint (32) + owner (32) + amount (8) + ...
+    if (*account.owner == anchor_spl::token::ID || *account.owner == anchor_spl::token_2022::ID)
+        && data.len() >= 72
+        && owner_field_matches
+    {
+        return Ok(u64::from_le_bytes(data[64..72].try_into().unwrap()));
+    }
+
+    // Name registry layout: parent_name (32) + owner (32) + class (32) + data
+    if *account.owner == NAME_SERVICE_PROGRAM_ID && owner_field_matches {
+        return Ok(1);
+    }
+
+    Ok(0)
+}
+
+/// Whether a token account could hold a domain NFT: a single token of a 0-decimal mint
+/// Domains deposited before the entry backfill have no entry, so the entry check alone misses them
+fn may_hold_domain(token_account: &TokenAccount, mint: &Mint) -> bool {
+    token_account.amount == 1 && mint.decimals == 0
+}
+
+/// Programs the vault never signs for through a claim: SNS, whose records can be locked, and
+/// the token programs, which could delegate or re-authorize vault token accounts
+fn is_protected_program(program: &Pubkey) -> bool {
+    [
+        NAME_SERVICE_PROGRAM_ID,
+        SNS_RECORDS_PROGRAM_ID,
+        anchor_spl::token::ID,
+        anchor_spl::token_2022::ID,
+    ]
+    .contains(program)
+}
+
+/// Delegate, state, delegated amount and close authority of a token account, empty otherwise
+/// An approve or authority change leaves the amount untouched but lets the domain be pulled later
+fn token_account_authorities(account: &AccountInfo) -> Result<Vec<u8>> {
+    if *account.owner != anchor_spl::token::ID && *account.owner != anchor_spl::token_2022::ID {
+        return Ok(Vec::new());
+    }
+    let data = account.try_borrow_data()?;
+    if data.len() < 165 {
+        return Ok(Vec::new());
+    }
+    // delegate (72..108) + state (108), skipping is_native, then delegated_amount and
+    // close_authority (121..165)
+    Ok([&data[72..109], &data[121..165]].concat())
+}
+
+/// Record the vault-owned assets among `accounts` as (index, amount, authorities) triples
+fn snapshot_vault_assets(
+    vault: &Pubkey,
+    accounts: &[AccountInfo],
+) -> Result<Vec<(usize, u64, Vec<u8>)>> {
+    let mut snapshot = Vec::new();
+    for (index, account) in accounts.iter().enumerate() {
+        let amount = vault_asset_amount(vault, account)?;
+        if amount > 0 {
+            snapshot.push((index, amount, token_account_authorities(account)?));
+        }
+    }
+    Ok(snapshot)
+}
+
+/// Initialize a freshly created domain entry
+fn init_domain_entry(
+    domain_entry: &mut Account<DomainEntry>,
+    vault: Pubkey,
+    domain: Pubkey,
+    kind: DomainKind,
+    bump: u8,
+    rent_payer: Pubkey,
+) -> Result<()> {
+    domain_entry.vault = vault;
+    domain_entry.domain = domain;
+    domain_entry.kind = kind;
+    domain_entry.deposited_at = Clock::get()?.unix_timestamp;
+    domain_entry.staked = false;
+    domain_entry.reward_checkpoint = 0;
+    domain_entry.unstake_requested_at = 0;
+    domain_entry.unstake_available_at = 0;
+    domain_entry.unclaimed_rewards = 0;
+    domain_entry.records_hash = [0; 32];
+    domain_entry.records_snapshot_at = 0;
+    domain_entry.records_locked = false;
+    domain_entry.tag = [0; 32];
+    domain_entry.rent_payer = rent_payer;
+    domain_entry.bump = bump;
+    domain_entry.memo_hash = [0; 32];
+    domain_entry._reserved = [0; ENTRY_RESERVED_BYTES];
+    Ok(())
+}
+
+/// Move staking rewards accrued since the last checkpoint into `unclaimed_rewards`
+/// Accrual stops once unstaking has been requested
+fn settle_rewards(domain_entry: &mut DomainEntry, config: &ProtocolConfig, now: i64) {
+    if !domain_entry.staked {
+        return;
+    }
+
+    let end = if domain_entry.unstake_requested_at > 0 {
+        domain_entry.unstake_requested_at.min(now)
+    } else {
+        now
+    };
+    let elapsed = end.saturating_sub(domain_entry.reward_checkpoint).max(0) as u128;
+    let reward = elapsed
+        .checked_mul(config.staking.reward_lamports_per_domain_day as u128)
+        .map(|v| v / SECONDS_PER_DAY as u128)
+        .unwrap();
+
+    domain_entry.unclaimed_rewards = domain_entry
+        .unclaimed_rewards
+        .saturating_add(u64::try_from(reward).unwrap_or(u64::MAX));
+    domain_entry.reward_checkpoint = end.max(domain_entry.reward_checkpoint);
+}
+
+/// Reject deposits of denylisted mints, or of mints not explicitly allowed in allowlist mode
+/// The policy account is the derived MintPolicy PDA, which may not exist
+fn check_mint_policy(config: &ProtocolConfig, mint_policy: &AccountInfo) -> Result<()> {
+    let status = if mint_policy.data_is_empty() {
+        None
+    } else {
+        let data = mint_policy.try_borrow_data()?;
+        Some(MintPolicy::try_deserialize(&mut &data[..])?.status)
+    };
+
+    require!(status != Some(MintStatus::Denied), VaultError::MintDenied);
+    require!(
+        !config.mint_allowlist_mode || status == Some(MintStatus::Allowed),
+        VaultError::MintNotAllowed
+    );
+    Ok(())
+}
+
+/// Pre-computed sha256 hash of "SPL Name Service" + "\x02SOL", i.e. `hash_record_name("SOL")`
+/// This is used for SOL Record V2 PDA derivation
+/// sha256("SPL Name Service\x02SOL") = 30ecde95b64ef547d89fde3987039f70b53937a8ffbcc10a285b826fdfa076bd
+#[constant]
+pub const SOL_RECORD_V2_HASHED_NAME: [u8; 32] = [
+    0x30, 0xec, 0xde, 0x95, 0xb6, 0x4e, 0xf5, 0x47,
+    0xd8, 0x9f, 0xde, 0x39, 0x87, 0x03, 0x9f, 0x70,
+    0xb5, 0x39, 0x37, 0xa8, 0xff, 0xbc, 0xc1, 0x0a,
+    0x28, 0x5b, 0x82, 0x6f, 0xdf, 0xa0, 0x76, 0xbd
+];
+
+/// Check that `name_account` is the name registry of the domain tracked by `domain_entry`
+/// Wrapped entries are keyed by the tokenizer mint, which is derived from the name account
+fn check_entry_name_account(domain_entry: &DomainEntry, name_account: &AccountInfo) -> Result<()> {
+    let expected = match domain_entry.kind {
+        DomainKind::Unwrapped => name_account.key(),
+        DomainKind::WrappedSplToken | DomainKind::WrappedToken2022 => {
+            get_tokenized_mint_key(name_account.key).0
+        }
+        _ => return err!(VaultError::UnsupportedDomainKind),
+    };
+    require_keys_eq!(expected, domain_entry.domain, VaultError::DomainNotInVault);
+    Ok(())
+}
+
+/// Hash a domain's Record V2 accounts for tamper-evidence snapshots
+/// Records must be passed in strictly ascending key order so a snapshot has one canonical form
+fn hash_record_accounts(name_account: &Pubkey, records: &[AccountInfo]) -> Result<[u8; 32]> {
+    let (central_state, _) = get_central_state_key();
+    let mut hash = [0u8; 32];
+    let mut previous: Option<Pubkey> = None;
+
+    for record in records {
+        require!(
+            previous.is_none_or(|p| p < *record.key),
+            VaultError::InvalidRecordAccount
+        );
+        previous = Some(*record.key);
+
+        check_record_account(record, name_account, &central_state)?;
+        let data = record.try_borrow_data()?;
+        hash = hashv(&[&hash, record.key.as_ref(), &data]).to_bytes();
+    }
+
+    Ok(hash)
+}
+
+/// Check that `record` is a Record V2 account of the domain `name_account`
+fn check_record_account(record: &AccountInfo, name_account: &Pubkey, central_state: &Pubkey) -> Result<()> {
+    require_keys_eq!(
+        *record.owner,
+        NAME_SERVICE_PROGRAM_ID,
+        VaultError::InvalidRecordAccount
+    );
+    let data = record.try_borrow_data()?;
+    // Name registry header: parent(32) + owner(32) + class(32)
+    require!(data.len() >= 96, VaultError::InvalidRecordAccount);
+    require!(
+        data[0..32] == name_account.to_bytes() && data[64..96] == central_state.to_bytes(),
+        VaultError::InvalidRecordAccount
+    );
+    Ok(())
+}
+
+/// Health issues of a vaulted domain's SOL record V2 (none if the record doesn't exist)
+/// Record V2 layout after the registry header: staleness validation (u16), ROA validation (u16),
+/// content length (u32), staleness id, roa id, content; Solana validations carry 32-byte ids
+fn sol_record_health(sol_record: &AccountInfo, vault: &Pubkey) -> Result<u32> {
+    // Validation kinds: None (0), Solana (1), Ethereum (2), UnverifiedSolana (3)
+    const SOLANA_VALIDATION: u16 = 1;
+    let id_len = |validation: u16| match validation {
+        1 | 3 => 32,
+        2 => 20,
+        _ => 0,
+    };
+    if sol_record.data_is_empty() {
+        return Ok(0);
+    }
+
+    let data = sol_record.try_borrow_data()?;
+    if *sol_record.owner != NAME_SERVICE_PROGRAM_ID || data.len() < 104 {
+        return Ok(HEALTH_RECORD_UNVERIFIED | HEALTH_RECORD_STALE);
+    }
+    let staleness = u16::from_le_bytes([data[96], data[97]]);
+    let roa = u16::from_le_bytes([data[98], data[99]]);
+
+    let mut issues = 0;
+    let staleness_id = 104;
+    if staleness != SOLANA_VALIDATION
+        || data.get(staleness_id..staleness_id + 32) != Some(vault.as_ref())
+    {
+        issues |= HEALTH_RECORD_STALE;
+    }
+
+    // A SOL record is verified when its ROA is validated by the vault, which is also its content
+    let roa_id = staleness_id + id_len(staleness);
+    let content = roa_id + id_len(roa);
+    let roa_ok = roa == SOLANA_VALIDATION
+        && data.get(roa_id..roa_id + 32) == Some(vault.as_ref())
+        && data.get(content..content + 32) == Some(vault.as_ref());
+    if !roa_ok {
+        issues |= HEALTH_RECORD_UNVERIFIED;
+    }
+    Ok(issues)
+}
+
+/// Build an SNS Records V2 instruction using the program's common account layout:
+/// system, name service, fee payer, record, domain, domain owner, central state, [verifier]
+#[allow(clippy::too_many_arguments)]
+fn sns_records_instruction(
+    data: Vec<u8>,
+    fee_payer: Pubkey,
+    record: Pubkey,
+    name_account: Pubkey,
+    domain_owner: Pubkey,
+    domain_owner_signs: bool,
+    central_state: Pubkey,
+    verifier: Option<Pubkey>,
+) -> anchor_lang::solana_program::instruction::Instruction {
+    use anchor_lang::solana_program::instruction::AccountMeta;
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(anchor_lang::solana_program::system_program::ID, false),
+        AccountMeta::new_readonly(NAME_SERVICE_PROGRAM_ID, false),
+        AccountMeta::new(fee_payer, true),
+        AccountMeta::new(record, false),
+        AccountMeta::new(name_account, false),
+        AccountMeta::new(domain_owner, domain_owner_signs),
+        AccountMeta::new_readonly(central_state, false),
+    ];
+    if let Some(verifier) = verifier {
+        accounts.push(AccountMeta::new(verifier, true));
+    }
+
+    anchor_lang::solana_program::instruction::Instruction {
+        program_id: SNS_RECORDS_PROGRAM_ID,
+        accounts,
+        data,
+    }
+}
+
+/// Re-run staleness validation of a domain's SOL record with the vault as current owner
+/// Must follow any instruction that moves a domain's registry ownership to a vault, since
+/// the owner change marks the record stale and breaks resolution
+#[allow(clippy::too_many_arguments)]
+fn revalidate_sol_record_staleness<'info>(
+    fee_payer: &AccountInfo<'info>,
+    sol_record: &AccountInfo<'info>,
+    name_account: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+    central_state: &AccountInfo<'info>,
+    name_service_program: &AccountInfo<'info>,
+    sns_records_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    vault_signer_seeds: &[&[u8]],
+) -> Result<()> {
+    // validateSolanaSignature (tag 3) with staleness = true, the vault signing as verifier
+    let validate_ix = sns_records_instruction(
+        vec![3u8, 1u8],
+        fee_payer.key(),
+        sol_record.key(),
+        name_account.key(),
+        vault.key(),
+        false,
+        central_state.key(),
+        Some(vault.key()),
+    );
+    invoke_sns(
+        &validate_ix,
+        &[
+            system_program.clone(),
+            name_service_program.clone(),
+            fee_payer.clone(),
+            sol_record.clone(),
+            name_account.clone(),
+            vault.clone(),
+            central_state.clone(),
+            vault.clone(),
+            sns_records_program.clone(),
+        ],
+        &[vault_signer_seeds],
+        SnsCpiStep::Validate,
+        sol_record.key(),
+    )?;
+    Ok(())
+}
+
+/// Validate typed record content and encode it the way SNS clients read it back
+/// Well-known record names only accept their own content type (e.g. SOL a pubkey, ETH an address)
+fn encode_record_content(name: &str, content: &RecordContent) -> Result<Vec<u8>> {
+    let type_matches = match name {
+        "SOL" | "SHDW" => matches!(content, RecordContent::Pubkey(_)),
+        "ETH" | "BSC" | "BASE" => matches!(content, RecordContent::EthAddress(_)),
+        "IPFS" => matches!(content, RecordContent::IpfsCid(_)),
+        "ARWV" => matches!(content, RecordContent::ArweaveTxId(_)),
+        "A" => matches!(content, RecordContent::Ipv4Address(_)),
+        "AAAA" => matches!(content, RecordContent::Ipv6Address(_)),
+        "CNAME" => matches!(content, RecordContent::Hostname(_)),
+        "TXT" => matches!(content, RecordContent::Utf8(_)),
+        _ => true,
+    };
+    require!(type_matches, VaultError::InvalidRecordContent);
+    require!(content_within_limit(content), VaultError::RecordContentTooLarge);
+
+    let encoded = match content {
+        RecordContent::Pubkey(key) => {
+            require!(*key != Pubkey::default(), VaultError::InvalidRecordContent);
+            key.to_bytes().to_vec()
+        }
+        RecordContent::EthAddress(address) => {
+            require!(*address != [0; 20], VaultError::InvalidRecordContent);
+            address.to_vec()
+        }
+        RecordContent::Utf8(text) => {
+            require!(
+                !text.is_empty() && !text.chars().any(char::is_control),
+                VaultError::InvalidRecordContent
+            );
+            text.as_bytes().to_vec()
+        }
+        RecordContent::IpfsCid(cid) => {
+            require!(is_ipfs_cid(cid), VaultError::InvalidRecordContent);
+            cid.as_bytes().to_vec()
+        }
+        RecordContent::ArweaveTxId(id) => {
+            require!(
+                id.len() == ARWEAVE_TX_ID_LEN
+                    && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'),
+                VaultError::InvalidRecordContent
+            );
+            id.as_bytes().to_vec()
+        }
+        RecordContent::Ipv4Address(address) => address.to_vec(),
+        RecordContent::Ipv6Address(address) => address.to_vec(),
+        RecordContent::Hostname(hostname) => {
+            require!(is_hostname(hostname), VaultError::InvalidRecordContent);
+            hostname.as_bytes().to_vec()
+        }
+    };
+    Ok(encoded)
+}
+
+/// Whether record content fits the maximum size of its type
+/// Pubkeys and ETH addresses have a fixed size; text and CIDs are bounded explicitly
+fn content_within_limit(content: &RecordContent) -> bool {
+    match content {
+        RecordContent::Pubkey(_)
+        | RecordContent::EthAddress(_)
+        | RecordContent::Ipv4Address(_)
+        | RecordContent::Ipv6Address(_) => true,
+        RecordContent::Utf8(text) => text.len() <= MAX_RECORD_TEXT_LEN,
+        RecordContent::IpfsCid(cid) => cid.len() <= MAX_RECORD_CID_LEN,
+        RecordContent::ArweaveTxId(id) => id.len() <= ARWEAVE_TX_ID_LEN,
+        RecordContent::Hostname(hostname) => hostname.len() <= MAX_HOSTNAME_LEN,
+    }
+}
+
+/// SNS record name of a social platform
+fn social_record_name(platform: SocialPlatform) -> &'static str {
+    match platform {
+        SocialPlatform::Twitter => "twitter",
+        SocialPlatform::Discord => "discord",
+        SocialPlatform::Github => "github",
+    }
+}
+
+/// Whether `handle` is a well-formed username on `platform`
+fn is_social_handle(platform: SocialPlatform, handle: &str) -> bool {
+    match platform {
+        SocialPlatform::Twitter => {
+            (1..=15).contains(&handle.len())
+                && handle.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        SocialPlatform::Discord => {
+            (2..=32).contains(&handle.len())
+                && handle
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '.')
+        }
+        SocialPlatform::Github => {
+            (1..=39).contains(&handle.len())
+                && !handle.starts_with('-')
+                && !handle.ends_with('-')
+                && handle.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        }
+    }
+}
+
+/// Parse a DNS-style record into its SNS record name and typed content
+fn dns_record_content(record: DnsRecord) -> Result<(&'static str, RecordContent)> {
+    let parsed = match record {
+        DnsRecord::A(address) => (
+            "A",
+            RecordContent::Ipv4Address(
+                address
+                    .parse::<std::net::Ipv4Addr>()
+                    .map_err(|_| VaultError::InvalidRecordContent)?
+                    .octets(),
+            ),
+        ),
+        DnsRecord::Aaaa(address) => (
+            "AAAA",
+            RecordContent::Ipv6Address(
+                address
+                    .parse::<std::net::Ipv6Addr>()
+                    .map_err(|_| VaultError::InvalidRecordContent)?
+                    .octets(),
+            ),
+        ),
+        DnsRecord::Cname(hostname) => ("CNAME", RecordContent::Hostname(hostname)),
+        DnsRecord::Txt(text) => ("TXT", RecordContent::Utf8(text)),
+    };
+    Ok(parsed)
+}
+
+/// Whether `hostname` follows RFC 1123: dot-separated labels of 1-63 letters, digits or
+/// hyphens, not starting or ending with a hyphen; a single trailing dot is allowed
+fn is_hostname(hostname: &str) -> bool {
+    let hostname = hostname.strip_suffix('.').unwrap_or(hostname);
+    !hostname.is_empty()
+        && hostname.split('.').all(|label| {
+            (1..=63).contains(&label.len())
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+/// Whether `cid` is a CIDv0 ("Qm" + base58, 46 chars) or a base32 CIDv1 ("b" + [a-z2-7])
+fn is_ipfs_cid(cid: &str) -> bool {
+    const BASE58: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+    if let Some(rest) = cid.strip_prefix("Qm") {
+        return cid.len() == 46 && rest.chars().all(|c| BASE58.contains(c));
+    }
+    match cid.strip_prefix('b') {
+        Some(rest) => {
+            rest.len() >= 58
+                && rest.chars().all(|c| c.is_ascii_lowercase() || ('2'..='7').contains(&c))
+        }
+        None => false,
+    }
+}
+
+/// Create or overwrite a Record V2 of a vault-owned domain
+/// Uses allocateAndPostRecord (tag 1) for a new record and editRecord (tag 2) otherwise;
+/// the SNS Records program checks that `record` is derived from `name`
+#[allow(clippy::too_many_arguments)]
+fn write_record_v2<'info>(
+    fee_payer: &AccountInfo<'info>,
+    record: &AccountInfo<'info>,
+    name_account: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+    central_state: &AccountInfo<'info>,
+    name_service_program: &AccountInfo<'info>,
+    sns_records_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    name: &str,
+    content: &[u8],
+    vault_signer_seeds: &[&[u8]],
+) -> Result<()> {
+    let tag = if record.data_is_empty() { 1u8 } else { 2u8 };
+    // Record names are passed with the 0x02 V2 prefix
+    let mut data = vec![tag];
+    data.extend_from_slice(&(name.len() as u32 + 1).to_le_bytes());
+    data.push(RECORD_V2_CLASS);
+    data.extend_from_slice(name.as_bytes());
+    data.extend_from_slice(&(content.len() as u32).to_le_bytes());
+    data.extend_from_slice(content);
+
+    let record_ix = sns_records_instruction(
+        data,
+        fee_payer.key(),
+        record.key(),
+        name_account.key(),
+        vault.key(),
+        true,
+        central_state.key(),
+        None,
+    );
+    invoke_sns(
+        &record_ix,
+        &[
+            system_program.clone(),
+            name_service_program.clone(),
+            fee_payer.clone(),
+            record.clone(),
+            name_account.clone(),
+            vault.clone(),
+            central_state.clone(),
+            sns_records_program.clone(),
+        ],
+        &[vault_signer_seeds],
+        if tag == 1 { SnsCpiStep::Allocate } else { SnsCpiStep::EditRecord },
+        record.key(),
+    )?;
+    Ok(())
+}
+
+/// Require the current instruction to be top-level (not a CPI) in a transaction whose
+/// instructions all target this program or the compute budget program
+fn check_isolated_transaction(instructions_sysvar: &AccountInfo) -> Result<()> {
+    require!(
+        anchor_lang::solana_program::instruction::get_stack_height() == 1,
+        VaultError::TransactionNotIsolated
+    );
+
+    let mut index = 0;
+    while let Ok(ix) = load_instruction_at_checked(index, instructions_sysvar) {
+        require!(
+            ix.program_id == crate::ID || ix.program_id == COMPUTE_BUDGET_PROGRAM_ID,
+            VaultError::TransactionNotIsolated
+        );
+        index += 1;
+    }
+    Ok(())
+}
+
+/// Invoke an SNS program, surfacing which step failed and on which account
+/// On an error handed back by the runtime, the context is emitted as `SnsCpiFailed` and
+/// set as return data before the error is propagated
+fn invoke_sns(
+    ix: &anchor_lang::solana_program::instruction::Instruction,
+    account_infos: &[AccountInfo],
+    signer_seeds: &[&[&[u8]]],
+    step: SnsCpiStep,
+    account: Pubkey,
+) -> Result<()> {
+    invoke_signed(ix, account_infos, signer_seeds).map_err(|err| {
+        let failure = SnsCpiFailed {
+            step,
+            program: ix.program_id,
+            account,
+        };
+        msg!("SNS {:?} failed on {}: {}", step, account, err);
+        set_return_data(&failure.try_to_vec().unwrap_or_default());
+        emit!(failure);
+        err.into()
+    })
+}
+
+/// Count a handler call in the protocol stats, moving `domains_delta` domains in (+1) or out (-1)
+/// Saturates rather than failing: stats must never block a user operation
+fn record_stats(stats: &mut ProtocolStats, counter: StatsCounter, domains_delta: i8) {
+    let count = &mut stats.instruction_counts[counter as usize];
+    *count = count.saturating_add(1);
+    match domains_delta {
+        1 => stats.total_domains = stats.total_domains.saturating_add(1),
+        -1 => stats.total_domains = stats.total_domains.saturating_sub(1),
+        _ => {}
+    }
+    if domains_delta != 0 {
+        stats.total_volume = stats.total_volume.saturating_add(1);
+    }
+}
+
+/// Accounts shared by `deposit_domain` and `deposit_domain_thin`
+struct DepositDomainAccounts<'a, 'info> {
+    owner: &'a Signer<'info>,
+    vault: &'a mut Account<'info, UserVault>,
+    config: &'a Account<'info, ProtocolConfig>,
+    treasury: &'a mut Account<'info, Treasury>,
+    stats: &'a mut Account<'info, ProtocolStats>,
+    vault_summary: &'a UncheckedAccount<'info>,
+    user_points: &'a mut Account<'info, UserPoints>,
+    domain_mint: &'a InterfaceAccount<'info, Mint>,
+    name_account: &'a UncheckedAccount<'info>,
+    mint_policy: &'a UncheckedAccount<'info>,
+    user_token_account: &'a InterfaceAccount<'info, TokenAccount>,
+    vault_token_account: &'a InterfaceAccount<'info, TokenAccount>,
+    domain_entry: &'a mut Account<'info, DomainEntry>,
+    token_program: &'a Interface<'info, TokenInterface>,
+    system_program: &'a Program<'info, System>,
+}
+
+/// Move a wrapped domain NFT into the vault; the regular and thin deposits differ only in
+/// which accounts they may create
+fn deposit_wrapped_domain(
+    accounts: DepositDomainAccounts,
+    points_bump: u8,
+    entry_bump: u8,
+    memo_hash: Option<[u8; 32]>,
+) -> Result<()> {
+    require!(!accounts.config.paused, VaultError::ProtocolPaused);
+    check_cpi_program(accounts.config, accounts.token_program.key)?;
+    check_mint_policy(accounts.config, accounts.mint_policy)?;
+    check_domain_mint(accounts.domain_mint)?;
+    check_wrapped_domain(&accounts.domain_mint.key(), accounts.name_account)?;
+    check_vault_destination_account(
+        accounts.vault_token_account,
+        &accounts.domain_mint.key(),
+        &accounts.vault.key(),
+    )?;
+    charge_fee(
+        accounts.config,
+        FeeOperation::Deposit,
+        accounts.owner,
+        accounts.treasury,
+        accounts.system_program,
+    )?;
+    credit_points(
+        accounts.user_points,
+        Some(accounts.config),
+        accounts.vault,
+        points_bump,
+    )?;
+
+    // Transfer the SNS domain (NFT) from user to vault's token account
+    let cpi_accounts = TransferChecked {
+        from: accounts.user_token_account.to_account_info(),
+        to: accounts.vault_token_account.to_account_info(),
+        authority: accounts.owner.to_account_info(),
+        mint: accounts.domain_mint.to_account_info(),
+    };
+    let cpi_program = accounts.token_program.to_account_info();
+    let cpi_context = CpiContext::new(cpi_program, cpi_accounts);
+
+    // SNS domains are NFTs with 0 decimals and amount of 1
+    transfer_checked(cpi_context, 1, 0)?;
+
+    let owner_key = accounts.owner.key();
+    let bump = accounts.vault.bump;
+    clear_vault_token_authorities(
+        accounts.vault_token_account,
+        &accounts.vault.to_account_info(),
+        &accounts.token_program.to_account_info(),
+        &[VAULT_SEED, owner_key.as_ref(), &[bump]],
+    )?;
+
+    init_domain_entry(
+        accounts.domain_entry,
+        accounts.vault.key(),
+        accounts.domain_mint.key(),
+        wrapped_domain_kind(accounts.token_program.key),
+        entry_bump,
+        accounts.owner.key(),
+    )?;
+
+    let vault = accounts.vault;
+    vault.domains_count = vault.domains_count.checked_add(1).unwrap();
+    update_vault_summary(accounts.vault_summary, 1, 0)?;
+    record_stats(accounts.stats, StatsCounter::DepositDomain, 1);
+    accounts.domain_entry.memo_hash = memo_hash.unwrap_or_default();
+    emit!(DomainDeposited {
+        vault: vault.key(),
+        domain: accounts.domain_entry.domain,
+        kind: accounts.domain_entry.kind,
+        memo_hash,
+    });
+
+    msg!(
+        "Domain {} deposited to vault. Total domains: {}",
+        accounts.domain_mint.key(),
+        vault.domains_count
+    );
+    Ok(())
+}
+
+/// Accounts shared by `withdraw_domain` and `withdraw_domain_thin`
+struct WithdrawDomainAccounts<'a, 'info> {
+    owner: &'a Signer<'info>,
+    vault: &'a mut Account<'info, UserVault>,
+    config: Option<&'a Account<'info, ProtocolConfig>>,
+    stats: Option<&'a mut Account<'info, ProtocolStats>>,
+    vault_summary: &'a UncheckedAccount<'info>,
+    outflow_breaker: &'a UncheckedAccount<'info>,
+    security_log: &'a UncheckedAccount<'info>,
+    user_points: &'a mut Account<'info, UserPoints>,
+    domain_mint: &'a InterfaceAccount<'info, Mint>,
+    vault_token_account: &'a InterfaceAccount<'info, TokenAccount>,
+    user_token_account: &'a InterfaceAccount<'info, TokenAccount>,
+    domain_entry: &'a Account<'info, DomainEntry>,
+    token_program: &'a Interface<'info, TokenInterface>,
+    withdrawal_phrase: &'a UncheckedAccount<'info>,
+    co_signer: Option<&'a Signer<'info>>,
+}
+
+/// Move a wrapped domain NFT out of the vault back to its owner; the regular and thin
+/// withdrawals differ only in which accounts they may create
+fn withdraw_wrapped_domain(
+    accounts: WithdrawDomainAccounts,
+    points_bump: u8,
+    memo_hash: Option<[u8; 32]>,
+    confirmation_phrase: Option<String>,
+) -> Result<()> {
+    check_co_signer(accounts.vault, accounts.co_signer)?;
+    check_withdrawal_phrase(accounts.withdrawal_phrase, confirmation_phrase.as_ref())?;
+    record_outflow(accounts.outflow_breaker, accounts.security_log, 1, true)?;
+    if let Some(config) = accounts.config {
+        check_cpi_program(config, accounts.token_program.key)?;
+    }
+    let entry_token_program = domain_token_program(accounts.domain_entry.kind)
+        .ok_or(VaultError::UnsupportedDomainKind)?;
+    require_keys_eq!(
+        accounts.token_program.key(),
+        entry_token_program,
+        VaultError::WrongTokenProgram
+    );
+    check_token_account_state(
+        accounts.user_token_account,
+        &accounts.domain_mint.key(),
+        &accounts.owner.key(),
+    )?;
+    credit_points(
+        accounts.user_points,
+        accounts.config.map(|config| &**config),
+        accounts.vault,
+        points_bump,
+    )?;
+
+    // Verify the vault has domains
+    require!(accounts.vault.domains_count > 0, VaultError::NoDomains);
+
+    // Build PDA signer seeds
+    let owner_key = accounts.owner.key();
+    let bump = accounts.vault.bump;
+    let signer_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, owner_key.as_ref(), &[bump]]];
+
+    // Transfer the SNS domain back to user
+    let cpi_accounts = TransferChecked {
+        from: accounts.vault_token_account.to_account_info(),
+        to: accounts.user_token_account.to_account_info(),
+        authority: accounts.vault.to_account_info(),
+        mint: accounts.domain_mint.to_account_info(),
+    };
+    let cpi_program = accounts.token_program.to_account_info();
+    let cpi_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+    // SNS domains are NFTs with 0 decimals and amount of 1
+    transfer_checked(cpi_context, 1, 0)?;
+
+    // Update domains count
+    let vault = accounts.vault;
+    vault.domains_count = vault.domains_count.checked_sub(1).unwrap();
+    update_vault_summary(accounts.vault_summary, -1, 0)?;
+    if let Some(stats) = accounts.stats {
+        record_stats(stats, StatsCounter::WithdrawDomain, -1);
+    }
+    emit!(DomainWithdrawn {
+        vault: vault.key(),
+        domain: accounts.domain_entry.domain,
+        kind: accounts.domain_entry.kind,
+        memo_hash,
+        confirmation_phrase: confirmation_phrase.clone(),
+    });
+
+    msg!(
+        "Domain {} withdrawn from vault. Remaining domains: {}",
+        accounts.domain_mint.key(),
+        vault.domains_count
+    );
+    Ok(())
+}
+
+/// Kind of a wrapped domain held under `token_program`
+fn wrapped_domain_kind(token_program: &Pubkey) -> DomainKind {
+    if *token_program == anchor_spl::token_2022::ID {
+        DomainKind::WrappedToken2022
+    } else {
+        DomainKind::WrappedSplToken
+    }
+}
+
+/// Token program a wrapped domain was deposited under, as recorded by its entry kind
+/// None for kinds that aren't held as tokens
+pub fn domain_token_program(kind: DomainKind) -> Option<Pubkey> {
+    match kind {
+        DomainKind::WrappedSplToken => Some(anchor_spl::token::ID),
+        DomainKind::WrappedToken2022 => Some(anchor_spl::token_2022::ID),
+        _ => None,
+    }
+}
+
+/// Grow an account of this program in place to `new_len`, zero-filling the appended fields
+/// and topping up rent from `payer`; returns the previous length (larger accounts are left alone)
+/// Layout changes must only append fields for this to be a valid migration
+fn migrate_account_layout<'info>(
+    account: &AccountInfo<'info>,
+    new_len: usize,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<usize> {
+    let old_len = account.data_len();
+    if old_len >= new_len {
+        return Ok(old_len);
+    }
+
+    let rent_due = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(account.lamports());
+    if rent_due > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                anchor_lang::system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+    account.resize(new_len)?;
+    Ok(old_len)
+}
+
+/// Require the vault's co-signer, if it has one, to have signed
+fn check_co_signer(vault: &UserVault, co_signer: Option<&Signer>) -> Result<()> {
+    if vault.co_signer != Pubkey::default() {
+        require!(
+            co_signer.is_some_and(|s| s.key() == vault.co_signer),
+            VaultError::CoSignerRequired
+        );
+    }
+    Ok(())
+}
+
+/// Require `phrase` to match the vault's withdrawal confirmation phrase, if one is set
+/// The phrase account is the derived WithdrawalPhrase PDA, which may not exist
+fn check_withdrawal_phrase(withdrawal_phrase: &AccountInfo, phrase: Option<&String>) -> Result<()> {
+    if withdrawal_phrase.data_is_empty() {
+        return Ok(());
+    }
+    let data = withdrawal_phrase.try_borrow_data()?;
+    let expected = WithdrawalPhrase::try_deserialize(&mut &data[..])?.phrase_hash;
+
+    require!(
+        phrase.is_some_and(|p| {
+            p.len() <= MAX_CONFIRMATION_PHRASE_LEN && hashv(&[p.as_bytes()]).to_bytes() == expected
+        }),
+        VaultError::ConfirmationPhraseMismatch
+    );
+    Ok(())
+}
+
+/// Re-check an `init_if_needed` token account beyond Anchor's creation constraints, since an
+/// account that already existed skips creation: it must hold `mint` for `owner`, be unfrozen,
+/// have no delegate, and have no close authority other than `owner`
+pub fn check_token_account_state(
+    token_account: &TokenAccount,
+    mint: &Pubkey,
+    owner: &Pubkey,
+) -> Result<()> {
+    check_vault_destination_account(token_account, mint, owner)?;
+    require!(token_account.delegate.is_none(), VaultError::DelegatedTokenAccount);
+    require!(
+        token_account
+            .close_authority
+            .map_or(true, |authority| authority == *owner),
+        VaultError::ForeignCloseAuthority
+    );
+    Ok(())
+}
+
+/// Re-check an `init_if_needed` vault token account a domain is deposited into: it must hold
+/// `mint` for `vault` and be unfrozen. A delegate or vault-held close authority is allowed,
+/// since the deposit clears both (see clear_vault_token_authorities)
+pub fn check_vault_destination_account(
+    token_account: &TokenAccount,
+    mint: &Pubkey,
+    vault: &Pubkey,
+) -> Result<()> {
+    require!(
+        token_account.mint == *mint && token_account.owner == *vault,
+        VaultError::TokenAccountMismatch
+    );
+    require!(!token_account.is_frozen(), VaultError::FrozenTokenAccount);
+    Ok(())
+}
+
+/// Revoke any delegate and clear a vault-held close authority on a vault token account,
+/// so custodied tokens can't be moved or the account closed by a previously granted approval.
+/// A close authority held by anyone other than the vault can't be cleared and is rejected
+fn clear_vault_token_authorities<'info>(
+    token_account: &InterfaceAccount<'info, TokenAccount>,
+    vault: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    signer_seeds: &[&[u8]],
+) -> Result<()> {
+    let close_authority: Option<Pubkey> = token_account.close_authority.into();
+    if let Some(close_authority) = close_authority {
+        require_keys_eq!(close_authority, vault.key(), VaultError::ForeignCloseAuthority);
+        set_authority(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                SetAuthority {
+                    current_authority: vault.clone(),
+                    account_or_mint: token_account.to_account_info(),
+                },
+                &[signer_seeds],
+            ),
+            AuthorityType::CloseAccount,
+            None,
+        )?;
+    }
+
+    if token_account.delegate.is_some() {
+        revoke(CpiContext::new_with_signer(
+            token_program.clone(),
+            Revoke {
+                source: token_account.to_account_info(),
+                authority: vault.clone(),
+            },
+            &[signer_seeds],
+        ))?;
+        msg!("Revoked delegate on vault token account {}", token_account.key());
+    }
+    Ok(())
+}
+
+/// Withdraw every domain described by `accounts` to the vault owner, closing its entry
+/// Per domain: wrapped [domain_entry, domain_mint, vault_token_account, owner_token_account,
+/// rent_payer], unwrapped [domain_entry, name_account, rent_payer]. Returns the closed entries
+#[allow(clippy::too_many_arguments)]
+fn withdraw_entries<'info>(
+    accounts: &'info [AccountInfo<'info>],
+    vault: &AccountInfo<'info>,
+    owner_key: &Pubkey,
+    signer_seeds: &[&[u8]],
+    token_program: &AccountInfo<'info>,
+    token_2022_program: &AccountInfo<'info>,
+    name_service_program: &AccountInfo<'info>,
+    mut stats: Option<&mut ProtocolStats>,
+    vault_summary: &AccountInfo<'info>,
+    confirmation_phrase: Option<&String>,
+) -> Result<Vec<Pubkey>> {
+    let vault_key = vault.key();
+    let mut withdrawn = Vec::new();
+    let mut accounts = accounts.iter();
+    while let Some(entry_info) = accounts.next() {
+        let domain_entry = Account::<DomainEntry>::try_from(entry_info)?;
+        require_keys_eq!(domain_entry.vault, vault_key, VaultError::DomainNotInVault);
+        let mut next = || accounts.next().ok_or(VaultError::InvalidEmergencyAccounts);
+
+        let counter = match domain_entry.kind {
+            DomainKind::WrappedSplToken | DomainKind::WrappedToken2022 => {
+                let domain_mint = next()?;
+                let vault_token_account = next()?;
+                let owner_token_account = next()?;
+                require_keys_eq!(
+                    *domain_mint.key,
+                    domain_entry.domain,
+                    VaultError::InvalidEmergencyAccounts
+                );
+                let source = InterfaceAccount::<TokenAccount>::try_from(vault_token_account)?;
+                require!(!source.is_frozen(), VaultError::VaultTokenAccountFrozen);
+                let destination = InterfaceAccount::<TokenAccount>::try_from(owner_token_account)?;
+                require_keys_eq!(destination.owner, *owner_key, VaultError::InvalidEmergencyAccounts);
+
+                let token_program = if domain_entry.kind == DomainKind::WrappedToken2022 {
+                    token_2022_program.clone()
+                } else {
+                    token_program.clone()
+                };
+                let cpi_accounts = TransferChecked {
+                    from: vault_token_account.clone(),
+                    to: owner_token_account.clone(),
+                    authority: vault.clone(),
+                    mint: domain_mint.clone(),
+                };
+                transfer_checked(
+                    CpiContext::new_with_signer(token_program, cpi_accounts, &[signer_seeds]),
+                    1,
+                    0,
+                )?;
+                StatsCounter::WithdrawDomain
+            }
+            DomainKind::Unwrapped => {
+                let name_account = next()?;
+                require_keys_eq!(
+                    *name_account.key,
+                    domain_entry.domain,
+                    VaultError::InvalidEmergencyAccounts
+                );
+
+                // Name service Transfer (2) to the owner, signed by the vault
+                let mut transfer_data = vec![2u8];
+                transfer_data.extend_from_slice(owner_key.as_ref());
+                let transfer_ix = anchor_lang::solana_program::instruction::Instruction {
+                    program_id: NAME_SERVICE_PROGRAM_ID,
+                    accounts: vec![
+                        anchor_lang::solana_program::instruction::AccountMeta::new(
+                            *name_account.key,
+                            false,
+                        ),
+                        anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                            vault_key, true,
+                        ),
+                    ],
+                    data: transfer_data,
+                };
+                invoke_sns(
+                    &transfer_ix,
+                    &[
+                        name_account.clone(),
+                        vault.clone(),
+                        name_service_program.clone(),
+                    ],
+                    &[signer_seeds],
+                    SnsCpiStep::Transfer,
+                    *name_account.key,
+                )?;
+                StatsCounter::WithdrawUnwrappedDomain
+            }
+            _ => return err!(VaultError::UnsupportedDomainKind),
+        };
+
+        let rent_payer = next()?;
+        require_keys_eq!(
+            *rent_payer.key,
+            domain_entry.rent_payer,
+            VaultError::InvalidEmergencyAccounts
+        );
+        emit!(DomainWithdrawn {
+            vault: vault_key,
+            domain: domain_entry.domain,
+            kind: domain_entry.kind,
+            memo_hash: None,
+            confirmation_phrase: confirmation_phrase.cloned(),
+        });
+        domain_entry.close(rent_payer.clone())?;
+        if let Some(stats) = stats.as_deref_mut() {
+            record_stats(stats, counter, -1);
+        }
+        if domain_entry.kind == DomainKind::Unwrapped {
+            update_vault_summary(vault_summary, 0, -1)?;
+        } else {
+            update_vault_summary(vault_summary, -1, 0)?;
+        }
+        withdrawn.push(entry_info.key());
+    }
+    Ok(withdrawn)
+}
+
+/// Character category of a domain name, used for valuation floors
+fn name_category(name: &str) -> NameCategory {
+    if !name.is_ascii() {
+        NameCategory::NonAscii
+    } else if name.bytes().all(|b| b.is_ascii_digit()) {
+        NameCategory::Numeric
+    } else if name.bytes().all(|b| b.is_ascii_alphabetic()) {
+        NameCategory::Letters
+    } else {
+        NameCategory::Mixed
+    }
+}
+
+/// Floor price of a domain name: the higher of its length tier and category floors
+pub fn floor_price(valuation_table: &ValuationTable, name: &str) -> u64 {
+    let tier = name.chars().count().clamp(1, VALUATION_LENGTH_TIERS) - 1;
+    valuation_table.length_floors[tier]
+        .max(valuation_table.category_floors[name_category(name) as usize])
+}
+
+/// Helper function to derive the name account of a .sol domain (`name` without ".sol")
+pub fn get_domain_key(name: &str) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            &hash_domain_name(name),
+            Pubkey::default().as_ref(),
+            ROOT_DOMAIN_ACCOUNT.as_ref(),
+        ],
+        &NAME_SERVICE_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Count `domains` leaving custody against the outflow circuit breaker, if one is configured
+/// With `halt`, fails once the breaker has tripped; the withdrawal that trips it still goes
+/// through, since failing it would also roll back the trip
+fn record_outflow(
+    outflow_breaker: &AccountInfo,
+    security_log: &AccountInfo,
+    domains: u64,
+    halt: bool,
+) -> Result<()> {
+    if outflow_breaker.data_is_empty() || domains == 0 {
+        return Ok(());
+    }
+    let mut data = outflow_breaker.try_borrow_mut_data()?;
+    let mut breaker = OutflowBreaker::try_deserialize(&mut &data[..])?;
+    require!(!(halt && breaker.tripped), VaultError::OutflowHalted);
+
+    let now = Clock::get()?.unix_timestamp;
+    if now >= breaker.window_start.saturating_add(breaker.window_seconds) {
+        // Trailing average as an exponential moving average over past windows (weight 1/8)
+        breaker.trailing_average = (breaker.trailing_average.saturating_mul(7)
+            + breaker.window_outflow)
+            / 8;
+        breaker.window_start = now;
+        breaker.window_outflow = 0;
+    }
+    breaker.window_outflow = breaker.window_outflow.saturating_add(domains);
+
+    let threshold = breaker
+        .trailing_average
+        .saturating_mul(breaker.trip_multiple as u64)
+        .max(breaker.min_trip_outflow);
+    if !breaker.tripped && breaker.window_outflow > threshold {
+        breaker.tripped = true;
+        emit!(OutflowBreakerTripped {
+            window_outflow: breaker.window_outflow,
+            threshold,
+        });
+        msg!("Outflow breaker tripped: {} domains out this window", breaker.window_outflow);
+        log_security_event(
+            security_log,
+            SecurityEventKind::OutflowBreakerTripped,
+            SecuritySeverity::Critical,
+            outflow_breaker.key(),
+        )?;
+    }
+
+    breaker.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
+/// Append an entry to the security log, if one is initialized
+/// Only occurrences that commit are recorded; a rejected instruction rolls back its entry
+fn log_security_event(
+    security_log: &AccountInfo,
+    kind: SecurityEventKind,
+    severity: SecuritySeverity,
+    subject: Pubkey,
+) -> Result<()> {
+    if security_log.data_is_empty() {
+        return Ok(());
+    }
+    let mut data = security_log.try_borrow_mut_data()?;
+    let mut log = SecurityLog::try_deserialize(&mut &data[..])?;
+
+    let entry = SecurityLogEntry {
+        kind,
+        severity,
+        subject,
+        timestamp: Clock::get()?.unix_timestamp,
+    };
+    log.entries[(log.total % SECURITY_LOG_CAPACITY as u64) as usize] = entry;
+    log.total = log.total.saturating_add(1);
+
+    log.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
+/// Pay the keeper of a crank its configured bounty out of the crank's escrow account
+/// An escrow being closed is drained entirely; otherwise it keeps its rent exempt minimum.
+/// A short escrow pays what it can, and a missing schedule or keeper pays nothing
+fn pay_keeper_bounty<'info>(
+    keeper_bounties: &Option<Account<'info, KeeperBounties>>,
+    keeper: &Option<Signer<'info>>,
+    escrow: &AccountInfo<'info>,
+    crank: CrankKind,
+    closing: bool,
+) -> Result<()> {
+    let (Some(keeper_bounties), Some(keeper)) = (keeper_bounties, keeper) else {
+        return Ok(());
+    };
+    let reserved = if closing {
+        0
+    } else {
+        Rent::get()?.minimum_balance(escrow.data_len())
+    };
+    let bounty = keeper_bounties.lamports[crank as usize]
+        .min(escrow.lamports().saturating_sub(reserved));
+    if bounty == 0 {
+        return Ok(());
+    }
+
+    escrow.sub_lamports(bounty)?;
+    keeper.add_lamports(bounty)?;
+    msg!("Paid {:?} bounty of {} lamports to {}", crank, bounty, keeper.key());
+    Ok(())
+}
+
+/// Apply a custody change to the vault's summary, if one is initialized
+fn update_vault_summary(vault_summary: &AccountInfo, wrapped: i64, unwrapped: i64) -> Result<()> {
+    if vault_summary.data_is_empty() {
+        return Ok(());
+    }
+    let mut data = vault_summary.try_borrow_mut_data()?;
+    let mut summary = VaultSummary::try_deserialize(&mut &data[..])?;
+
+    summary.wrapped_count = summary.wrapped_count.saturating_add_signed(wrapped);
+    summary.unwrapped_count = summary.unwrapped_count.saturating_add_signed(unwrapped);
+    summary.total_domains = summary
+        .total_domains
+        .saturating_add_signed(wrapped + unwrapped);
+    summary.last_activity_at = Clock::get()?.unix_timestamp;
+
+    summary.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
+/// Release the next sweep bucket, if the swept mint has sweep buckets set
+/// Returns the largest denomination covered by `balance` and the index of the destination it
+/// goes to, picked by weight, and pushes the next release back by a random delay. The entropy
+/// is slot-derived, so whoever cranks can grind it by choosing when to send; restricting the
+/// crank to the owner's keeper hides amounts and timing from outsiders, not from the keeper
+/// or the slot leader
+fn release_sweep_bucket(
+    sweep_buckets: &AccountInfo,
+    keeper: Option<&Signer>,
+    balance: u64,
+    destinations: &[SweepDestination],
+) -> Result<Option<(u64, usize)>> {
+    if sweep_buckets.data_is_empty() {
+        return Ok(None);
+    }
+    let mut data = sweep_buckets.try_borrow_mut_data()?;
+    let mut buckets = SweepBuckets::try_deserialize(&mut &data[..])?;
+    require!(
+        keeper.is_some_and(|k| k.key() == buckets.keeper),
+        VaultError::UnauthorizedAccess
+    );
+
+    let clock = Clock::get()?;
+    require!(
+        clock.unix_timestamp >= buckets.next_release_at,
+        VaultError::SweepBucketNotReleased
+    );
+    let denomination = buckets
+        .denominations
+        .iter()
+        .rev()
+        .find(|d| **d <= balance)
+        .copied()
+        .ok_or(VaultError::SweepThresholdNotMet)?;
+
+    let entropy = hashv(&[
+        buckets.vault.as_ref(),
+        buckets.mint.as_ref(),
+        &clock.slot.to_le_bytes(),
+        &buckets.releases.to_le_bytes(),
+    ])
+    .to_bytes();
+    let roll = u64::from_le_bytes(entropy[..8].try_into().unwrap());
+    let delay_roll = u64::from_le_bytes(entropy[8..16].try_into().unwrap());
+
+    let target = (roll % SWEEP_TOTAL_WEIGHT_BPS as u64) as u32;
+    let mut cumulative = 0u32;
+    let chosen = destinations
+        .iter()
+        .position(|d| {
+            cumulative += d.weight_bps as u32;
+            target < cumulative
+        })
+        .unwrap_or(destinations.len() - 1);
+
+    let delay = delay_roll % (buckets.max_delay_seconds as u64 + 1);
+    buckets.next_release_at = clock.unix_timestamp.checked_add(delay as i64).unwrap();
+    buckets.releases = buckets.releases.saturating_add(1);
+    buckets.try_serialize(&mut &mut data[..])?;
+
+    Ok(Some((denomination, chosen)))
+}
+
+/// Leaf of a custody snapshot: sha256(0x00 || owner || domain)
+/// The 0x00 prefix keeps leaves from being passed off as inner nodes
+pub fn custody_snapshot_leaf(owner: &Pubkey, domain: &Pubkey) -> [u8; 32] {
+    hashv(&[&[0x00], owner.as_ref(), domain.as_ref()]).to_bytes()
+}
+
+/// Verify a custody snapshot merkle proof
+/// Inner nodes are sha256(0x01 || min(a, b) || max(a, b)), so proofs need no left/right flags
+pub fn verify_custody_proof(root: &[u8; 32], leaf: &[u8; 32], proof: &[[u8; 32]]) -> bool {
+    let computed = proof.iter().fold(*leaf, |node, sibling| {
+        let (a, b) = if node <= *sibling {
+            (node, *sibling)
+        } else {
+            (*sibling, node)
+        };
+        hashv(&[&[0x01], &a, &b]).to_bytes()
+    });
+    computed == *root
+}
+
+/// Whether `label` is a valid burner subdomain label: 1-32 lowercase letters, digits or '-',
+/// not starting or ending with '-'
+fn is_burner_label(label: &str) -> bool {
+    !label.is_empty()
+        && label.len() <= MAX_BURNER_LABEL_LEN
+        && label
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+        && !label.starts_with('-')
+        && !label.ends_with('-')
+}
+
+/// Parse one numeric part of the crate version at compile time
+const fn parse_version_part(part: &str) -> u16 {
+    let bytes = part.as_bytes();
+    let mut value = 0u16;
+    let mut i = 0;
+    while i < bytes.len() {
+        value = value * 10 + (bytes[i] - b'0') as u16;
+        i += 1;
+    }
+    value
+}
+
+/// Helper function to derive the program info PDA
+pub fn get_program_info_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PROGRAM_INFO_SEED], &crate::ID)
+}
+
+/// Fill `program_info` with this build's compiled-in version and feature bits
+pub fn write_program_info(program_info: &mut ProgramInfo, slot: u64) {
+    let [major, minor, patch] = PROGRAM_VERSION;
+    program_info.major = major;
+    program_info.minor = minor;
+    program_info.patch = patch;
+    program_info.features = ENABLED_FEATURES;
+    program_info.updated_slot = slot;
+}
+
+/// Helper function to derive the Name Tokenizer mint of a wrapped domain
+pub fn get_tokenized_mint_key(domain_name_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[TOKENIZED_NAME_SEED, domain_name_account.as_ref()],
+        &NAME_TOKENIZER_PROGRAM_ID,
+    )
+}
+
+/// Helper function to derive the Name Tokenizer central state, the mint authority of its mints
+pub fn get_tokenizer_central_state() -> Pubkey {
+    Pubkey::find_program_address(
+        &[NAME_TOKENIZER_PROGRAM_ID.as_ref()],
+        &NAME_TOKENIZER_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Check a deposited mint looks like a domain NFT: not native SOL, 0 decimals, a supply of
+/// exactly 1, and no mint authority that could inflate it other than the Name Tokenizer's
+fn check_domain_mint(mint: &InterfaceAccount<Mint>) -> Result<()> {
+    require_keys_neq!(
+        mint.key(),
+        anchor_spl::token::spl_token::native_mint::ID,
+        VaultError::NativeMintDeposit
+    );
+    require_keys_neq!(
+        mint.key(),
+        anchor_spl::token_2022::spl_token_2022::native_mint::ID,
+        VaultError::NativeMintDeposit
+    );
+    require!(mint.decimals == 0, VaultError::DomainMintHasDecimals);
+    require!(mint.supply == 1, VaultError::DomainSupplyNotOne);
+    require!(
+        mint.mint_authority
+            .map_or(true, |authority| authority == get_tokenizer_central_state()),
+        VaultError::InflatableDomainMint
+    );
+    Ok(())
+}
+
+/// Check a name registry is a .sol domain: its parent must be the .sol TLD
+/// Keeps arbitrary name accounts from getting an entry and earning staking rewards
+pub fn check_sol_domain_registry(data: &[u8]) -> Result<()> {
+    // Name registry layout: parent_name (32) + owner (32) + class (32) + data
+    require!(
+        data.len() >= 96 && data[..32] == ROOT_DOMAIN_ACCOUNT.to_bytes(),
+        VaultError::NotSolDomain
+    );
+    Ok(())
+}
+
+/// Check a wrapped domain mint is the Name Tokenizer mint of a .sol domain's name registry
+/// Only the Name Tokenizer can create that mint, so self-minted 0-decimal tokens are rejected
+pub fn check_wrapped_domain_mint(domain_mint: &Pubkey, name_account: &Pubkey) -> Result<()> {
+    require_keys_eq!(
+        *domain_mint,
+        get_tokenized_mint_key(name_account).0,
+        VaultError::NotSolDomain
+    );
+    Ok(())
+}
+
+/// Check a wrapped domain deposit: the mint is tokenized from `name_account`, a .sol domain
+fn check_wrapped_domain(domain_mint: &Pubkey, name_account: &AccountInfo) -> Result<()> {
+    check_wrapped_domain_mint(domain_mint, name_account.key)?;
+    check_sol_domain_registry(&name_account.try_borrow_data()?)
+}
+
+/// Check a flash record session may write `record`: it must be one the owner approved
+/// The end-of-session hash only covers the records passed to it, so writes are bound up front
+pub fn check_session_record(session_policy: &RecordSessionPolicy, record: &Pubkey) -> Result<()> {
+    require!(
+        session_policy.allowed_records.contains(record),
+        VaultError::RecordNotInSession
+    );
+    Ok(())
+}
+
+/// Prefix SNS hashes every name with before deriving its account
+pub const SNS_HASH_PREFIX: &str = "SPL Name Service";
+
+/// Prefix of Record V2 names (`0x01` is Record V1, `0x00` a subdomain)
+pub const RECORD_V2_NAME_PREFIX: u8 = 0x02;
+
+/// Helper function to hash a domain name the way SNS does: sha256("SPL Name Service" + name)
+/// `name` excludes the TLD, e.g. "bonfida" for bonfida.sol
+pub fn hash_domain_name(name: &str) -> [u8; 32] {
+    hashv(&[SNS_HASH_PREFIX.as_bytes(), name.as_bytes()]).to_bytes()
+}
+
+/// Helper function to hash a Record V2 name, e.g. "SOL" or "url": sha256("SPL Name Service" + "\x02" + record)
+pub fn hash_record_name(record: &str) -> [u8; 32] {
+    hashv(&[
+        SNS_HASH_PREFIX.as_bytes(),
+        &[RECORD_V2_NAME_PREFIX],
+        record.as_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// Helper function to derive the Record V2 PDA of any record on a domain
+pub fn get_record_v2_key(domain_name_account: &Pubkey, record: &str) -> (Pubkey, u8) {
+    let (central_state, _) = get_central_state_key();
+    Pubkey::find_program_address(
+        &[
+            &hash_record_name(record),
+            central_state.as_ref(),
+            domain_name_account.as_ref(),
+        ],
+        &NAME_SERVICE_PROGRAM_ID,
+    )
+}
+
+/// Helper function to derive the SOL record V2 PDA for a domain
+/// Uses SNS SDK derivation: findProgramAddressSync([hashedName, centralState, domainKey], NAME_PROGRAM_ID)
+pub fn get_sol_record_v2_key(domain_name_account: &Pubkey) -> (Pubkey, u8) {
+    let (central_state, _) = get_central_state_key();
+    
+    // Record V2 derivation uses NAME_PROGRAM_ID (not SNS_RECORDS_PROGRAM_ID!)
+    Pubkey::find_program_address(
+        &[
+            &SOL_RECORD_V2_HASHED_NAME,
+            central_state.as_ref(),
+            domain_name_account.as_ref(),
+        ],
+        &NAME_SERVICE_PROGRAM_ID,
+    )
+}
+
+/// Helper function to get the SNS Records V2 central state PDA
+/// Central state is derived using the program ID itself as seed
+pub fn get_central_state_key() -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[SNS_RECORDS_PROGRAM_ID.as_ref()],
+        &SNS_RECORDS_PROGRAM_ID,
+    )
+}
+
+/// Helper function to derive a user's vault PDA
+pub fn get_vault_address(owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VAULT_SEED, owner.as_ref()], &crate::ID)
+}
+
+/// Helper function to derive the vault's associated token account for a mint
+/// `token_program` is the mint's owner (SPL Token or Token-2022)
+pub fn get_vault_token_account(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    let (vault, _) = get_vault_address(owner);
+    anchor_spl::associated_token::get_associated_token_address_with_program_id(
+        &vault,
+        mint,
+        token_program,
+    )
+}
+
+/// Helper function to derive the entry PDA tracking a domain in a vault
+/// `domain` is the mint of a wrapped domain or the name account of an unwrapped one
+pub fn get_domain_entry_address(vault: &Pubkey, domain: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ENTRY_SEED, vault.as_ref(), domain.as_ref()], &crate::ID)
+}
+
+/// Helper function to derive the voting delegation PDA of a vault, for governance programs
+pub fn get_voting_delegation_address(vault: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VOTING_DELEGATION_SEED, vault.as_ref()], &crate::ID)
+}
+
+/// User's vault account that stores metadata
+#[account]
+#[derive(InitSpace)]
+pub struct UserVault {
+    /// The owner of this vault (user's public key)
+    pub owner: Pubkey,
+    /// PDA bump seed for signing
+    pub bump: u8,
+    /// Number of domains currently in the vault
+    pub domains_count: u64,
+    /// Set while the vault signs an external CPI; every instruction rejects a vault in flight
+    pub in_flight: bool,
+    /// Key that must co-sign withdrawals (default pubkey if none)
+    pub co_signer: Pubkey,
+    /// Zeroed space for future fields, carved from the front so they need no realloc
+    pub _reserved: [u8; VAULT_RESERVED_BYTES],
+}
+
+/// Global protocol configuration, owned by the protocol admin
+#[account]
+#[derive(InitSpace)]
+pub struct ProtocolConfig {
+    /// Key allowed to change protocol parameters
+    pub admin: Pubkey,
+    /// Admin nominated through `nominate_admin`, waiting to accept
+    pub pending_admin: Option<Pubkey>,
+    /// SPL Governance account controlling the admin, if the admin is a governance native treasury
+    pub governance: Option<Pubkey>,
+    /// Governance account behind the pending admin, if nominated through `nominate_governance_admin`
+    pub pending_governance: Option<Pubkey>,
+    /// Unix timestamp of the pending nomination
+    pub admin_nominated_at: i64,
+    /// Delay in seconds before admin transfers and lowered parameters take effect (0 = disabled)
+    pub timelock_seconds: i64,
+    /// Whether new deposits are paused
+    pub paused: bool,
+    /// When set, `deposit_domain` only accepts mints with an Allowed policy
+    pub mint_allowlist_mode: bool,
+    /// Fee in lamports charged per operation, indexed by FeeOperation
+    pub fee_schedule: [u64; FEE_OPERATION_COUNT],
+    /// Points credited by the points ledger
+    pub points_weights: PointsWeights,
+    /// Staking reward rate and unstake cooldown
+    pub staking: StakingParams,
+    /// Programs the vault may sign for through `claim_with_vault_signature`
+    #[max_len(MAX_CLAIM_PROGRAMS)]
+    pub claim_programs: Vec<Pubkey>,
+    /// External programs the vault program may CPI into (claim programs are allowlisted separately)
+    #[max_len(MAX_CPI_PROGRAMS)]
+    pub cpi_programs: Vec<Pubkey>,
+    /// Id assigned to the next queued parameter change
+    pub next_param_change_id: u64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Create the vault PDA for a user
+#[derive(Accounts)]
+pub struct InitializeVault<'info> {
+    /// The user initializing their vault
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The vault PDA - derived from the owner's public key
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + UserVault::INIT_SPACE,
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The global protocol stats
+    #[account(mut, seeds = [STATS_SEED], bump = stats.bump)]
+    pub stats: Account<'info, ProtocolStats>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Deposit a wrapped (tokenized) SNS domain into the vault
+#[derive(Accounts)]
+pub struct DepositDomain<'info> {
+    /// The owner depositing a domain
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The global protocol config (deposits are rejected while paused)
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The protocol fee treasury
+    #[account(mut, seeds = [TREASURY_SEED], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    /// The global protocol stats
+    #[account(mut, seeds = [STATS_SEED], bump = stats.bump)]
+    pub stats: Account<'info, ProtocolStats>,
+
+    /// The vault's summary PDA, which may not exist
+    /// CHECK: Derived address; deserialized only when initialized
+    #[account(mut, seeds = [VAULT_SUMMARY_SEED, vault.key().as_ref()], bump)]
+    pub vault_summary: UncheckedAccount<'info>,
+
+    /// The owner's points ledger
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + UserPoints::INIT_SPACE,
+        seeds = [POINTS_SEED, owner.key().as_ref()],
+        bump
+    )]
+    pub user_points: Account<'info, UserPoints>,
+
+    /// The SNS domain mint (NFT)
+    pub domain_mint: InterfaceAccount<'info, Mint>,
+
+    /// The tokenized domain's name registry, which the mint must be derived from
+    /// CHECK: Owned by the Name Service program; checked by check_wrapped_domain
+    #[account(owner = NAME_SERVICE_PROGRAM_ID)]
+    pub name_account: UncheckedAccount<'info>,
+
+    /// The deposit policy for this mint (may be uninitialized)
+    /// CHECK: Address is derived from the mint; contents are read by check_mint_policy
+    #[account(seeds = [MINT_POLICY_SEED, domain_mint.key().as_ref()], bump)]
+    pub mint_policy: UncheckedAccount<'info>,
+
+    /// User's token account holding the SNS domain
+    /// Frozen or delegated accounts are rejected up front with a precise error
+    #[account(
+        mut,
+        associated_token::mint = domain_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+        constraint = !user_token_account.is_frozen() @ VaultError::FrozenTokenAccount,
+        constraint = user_token_account.delegate.is_none() @ VaultError::DelegatedTokenAccount,
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Vault's token account to receive the SNS domain
+    /// We use init_if_needed because the vault may not have a token account for this specific domain yet
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = domain_mint,
+        associated_token::authority = vault,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The entry tracking this domain in the vault
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + DomainEntry::INIT_SPACE,
+        seeds = [ENTRY_SEED, vault.key().as_ref(), domain_mint.key().as_ref()],
+        bump
+    )]
+    pub domain_entry: Account<'info, DomainEntry>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Deposit a domain into existing vault accounts
+#[derive(Accounts)]
+pub struct DepositDomainThin<'info> {
+    /// The owner depositing a domain
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The global protocol config (deposits are rejected while paused)
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The protocol fee treasury
+    #[account(mut, seeds = [TREASURY_SEED], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    /// The global protocol stats
+    #[account(mut, seeds = [STATS_SEED], bump = stats.bump)]
+    pub stats: Account<'info, ProtocolStats>,
+
+    /// The vault's summary PDA, which may not exist
+    /// CHECK: Derived address; deserialized only when initialized
+    #[account(mut, seeds = [VAULT_SUMMARY_SEED, vault.key().as_ref()], bump)]
+    pub vault_summary: UncheckedAccount<'info>,
+
+    /// The owner's points ledger, which must already exist
+    #[account(
+        mut,
+        seeds = [POINTS_SEED, owner.key().as_ref()],
+        bump = user_points.bump
+    )]
+    pub user_points: Account<'info, UserPoints>,
+
+    /// The SNS domain mint (NFT)
+    pub domain_mint: InterfaceAccount<'info, Mint>,
+
+    /// The tokenized domain's name registry, which the mint must be derived from
+    /// CHECK: Owned by the Name Service program; checked by check_wrapped_domain
+    #[account(owner = NAME_SERVICE_PROGRAM_ID)]
+    pub name_account: UncheckedAccount<'info>,
+
+    /// The deposit policy for this mint (may be uninitialized)
+    /// CHECK: Address is derived from the mint; contents are read by check_mint_policy
+    #[account(seeds = [MINT_POLICY_SEED, domain_mint.key().as_ref()], bump)]
+    pub mint_policy: UncheckedAccount<'info>,
+
+    /// User's token account holding the SNS domain
+    /// Frozen or delegated accounts are rejected up front with a precise error
+    #[account(
+        mut,
+        associated_token::mint = domain_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+        constraint = !user_token_account.is_frozen() @ VaultError::FrozenTokenAccount,
+        constraint = user_token_account.delegate.is_none() @ VaultError::DelegatedTokenAccount,
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Vault's token account to receive the SNS domain, which must already exist
+    #[account(
+        mut,
+        associated_token::mint = domain_mint,
+        associated_token::authority = vault,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The entry tracking this domain in the vault
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + DomainEntry::INIT_SPACE,
+        seeds = [ENTRY_SEED, vault.key().as_ref(), domain_mint.key().as_ref()],
+        bump
+    )]
+    pub domain_entry: Account<'info, DomainEntry>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Withdraw a wrapped (tokenized) SNS domain back to the owner
+#[derive(Accounts)]
+pub struct WithdrawDomain<'info> {
+    /// The owner withdrawing a domain
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault (also acts as authority for vault token accounts)
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The global protocol config, if initialized; exits never depend on admin setup
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Option<Account<'info, ProtocolConfig>>,
+
+    /// The global protocol stats, if initialized; exits never depend on admin setup
+    #[account(mut, seeds = [STATS_SEED], bump = stats.bump)]
+    pub stats: Option<Account<'info, ProtocolStats>>,
+
+    /// The vault's summary PDA, which may not exist
+    /// CHECK: Derived address; deserialized only when initialized
+    #[account(mut, seeds = [VAULT_SUMMARY_SEED, vault.key().as_ref()], bump)]
+    pub vault_summary: UncheckedAccount<'info>,
+
+    /// The outflow circuit breaker PDA, which may not exist
+    /// CHECK: Derived address; deserialized only when initialized
+    #[account(mut, seeds = [OUTFLOW_BREAKER_SEED], bump)]
+    pub outflow_breaker: UncheckedAccount<'info>,
+
+    /// The security log PDA, which may not exist
+    /// CHECK: Derived address; deserialized only when initialized
+    #[account(mut, seeds = [SECURITY_LOG_SEED], bump)]
+    pub security_log: UncheckedAccount<'info>,
+
+    /// The owner's points ledger
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + UserPoints::INIT_SPACE,
+        seeds = [POINTS_SEED, owner.key().as_ref()],
+        bump
+    )]
+    pub user_points: Account<'info, UserPoints>,
+
+    /// The SNS domain mint (NFT)
+    pub domain_mint: InterfaceAccount<'info, Mint>,
+
+    /// Vault's token account holding the SNS domain
+    /// A frozen account fails here rather than deep inside the transfer CPI
+    #[account(
+        mut,
+        associated_token::mint = domain_mint,
+        associated_token::authority = vault,
+        associated_token::token_program = token_program,
+        constraint = !vault_token_account.is_frozen() @ VaultError::VaultTokenAccountFrozen,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// User's token account to receive the SNS domain
+    /// We use init_if_needed in case user closed their token account
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = domain_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The entry tracking this domain, closed on withdrawal
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED, vault.key().as_ref(), domain_mint.key().as_ref()],
+        bump = domain_entry.bump,
+        constraint = !domain_entry.staked @ VaultError::DomainStaked,
+        close = rent_payer
+    )]
+    pub domain_entry: Account<'info, DomainEntry>,
+
+    /// Whoever paid the entry's rent, refunded when it closes
+    /// CHECK: Address is the entry's recorded rent payer
+    #[account(mut, address = domain_entry.rent_payer)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    /// The vault's withdrawal phrase PDA, which may not exist
+    /// CHECK: Derived address; deserialized only when initialized
+    #[account(seeds = [WITHDRAWAL_PHRASE_SEED, vault.key().as_ref()], bump)]
+    pub withdrawal_phrase: UncheckedAccount<'info>,
+
+    /// The vault's co-signer, required when the vault has one
+    pub co_signer: Option<Signer<'info>>,
+}
+
+/// Withdraw a domain into an existing owner token account
+#[derive(Accounts)]
+pub struct WithdrawDomainThin<'info> {
+    /// The owner withdrawing a domain
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault (also acts as authority for vault token accounts)
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The global protocol config, if initialized; exits never depend on admin setup
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Option<Account<'info, ProtocolConfig>>,
+
+    /// The global protocol stats, if initialized; exits never depend on admin setup
+    #[account(mut, seeds = [STATS_SEED], bump = stats.bump)]
+    pub stats: Option<Account<'info, ProtocolStats>>,
+
+    /// The vault's summary PDA, which may not exist
+    /// CHECK: Derived address; deserialized only when initialized
+    #[account(mut, seeds = [VAULT_SUMMARY_SEED, vault.key().as_ref()], bump)]
+    pub vault_summary: UncheckedAccount<'info>,
+
+    /// The outflow circuit breaker PDA, which may not exist
+    /// CHECK: Derived address; deserialized only when initialized
+    #[account(mut, seeds = [OUTFLOW_BREAKER_SEED], bump)]
+    pub outflow_breaker: UncheckedAccount<'info>,
+
+    /// The security log PDA, which may not exist
+    /// CHECK: Derived address; deserialized only when initialized
+    #[account(mut, seeds = [SECURITY_LOG_SEED], bump)]
+    pub security_log: UncheckedAccount<'info>,
+
+    /// The owner's points ledger, which must already exist
+    #[account(
+        mut,
+        seeds = [POINTS_SEED, owner.key().as_ref()],
+        bump = user_points.bump
+    )]
+    pub user_points: Account<'info, UserPoints>,
+
+    /// The SNS domain mint (NFT)
+    pub domain_mint: InterfaceAccount<'info, Mint>,
+
+    /// Vault's token account holding the SNS domain
+    /// A frozen account fails here rather than deep inside the transfer CPI
+    #[account(
+        mut,
+        associated_token::mint = domain_mint,
+        associated_token::authority = vault,
+        associated_token::token_program = token_program,
+        constraint = !vault_token_account.is_frozen() @ VaultError::VaultTokenAccountFrozen,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// User's token account to receive the SNS domain, which must already exist
+    #[account(
+        mut,
+        associated_token::mint = domain_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The entry tracking this domain, closed on withdrawal
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED, vault.key().as_ref(), domain_mint.key().as_ref()],
+        bump = domain_entry.bump,
+        constraint = !domain_entry.staked @ VaultError::DomainStaked,
+        close = rent_payer
+    )]
+    pub domain_entry: Account<'info, DomainEntry>,
+
+    /// Whoever paid the entry's rent, refunded when it closes
+    /// CHECK: Address is the entry's recorded rent payer
+    #[account(mut, address = domain_entry.rent_payer)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+
+    /// The vault's withdrawal phrase PDA, which may not exist
+    /// CHECK: Derived address; deserialized only when initialized
+    #[account(seeds = [WITHDRAWAL_PHRASE_SEED, vault.key().as_ref()], bump)]
+    pub withdrawal_phrase: UncheckedAccount<'info>,
+
+    /// The vault's co-signer, required when the vault has one
+    pub co_signer: Option<Signer<'info>>,
+}
+
+/// Create a vault-owned ATA for an arbitrary mint
+#[derive(Accounts)]
+pub struct InitVaultTokenAccount<'info> {
+    /// The owner of the vault (payer for the ATA creation)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The token mint for which to create an ATA
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// The vault's token account (ATA) to be initialized
+    #[account(
+        init,
+        payer = owner,
+        associated_token::mint = token_mint,
+        associated_token::authority = vault,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Pre-create the accounts of an upcoming deposit
+#[derive(Accounts)]
+pub struct PrepareDeposit<'info> {
+    /// Anyone paying for the accounts
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The vault the domain will be deposited into
+    #[account(
+        seeds = [VAULT_SEED, vault.owner.as_ref()],
+        bump = vault.bump,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The owner's points ledger, left unclaimed for the deposit to start
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + UserPoints::INIT_SPACE,
+        seeds = [POINTS_SEED, vault.owner.as_ref()],
+        bump
+    )]
+    pub user_points: Account<'info, UserPoints>,
+
+    /// The SNS domain mint (NFT) to be deposited
+    pub domain_mint: InterfaceAccount<'info, Mint>,
+
+    /// Vault's token account that will receive the domain
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = domain_mint,
+        associated_token::authority = vault,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Deposit an unwrapped SNS domain by transferring registry ownership to the vault
+#[derive(Accounts)]
+pub struct DepositUnwrappedDomain<'info> {
+    /// The owner depositing a domain
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The global protocol config (deposits are rejected while paused)
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The protocol fee treasury
+    #[account(mut, seeds = [TREASURY_SEED], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    /// The global protocol stats
+    #[account(mut, seeds = [STATS_SEED], bump = stats.bump)]
+    pub stats: Account<'info, ProtocolStats>,
+
+    /// The vault's summary PDA, which may not exist
+    /// CHECK: Derived address; deserialized only when initialized
+    #[account(mut, seeds = [VAULT_SUMMARY_SEED, vault.key().as_ref()], bump)]
+    pub vault_summary: UncheckedAccount<'info>,
+
+    /// The owner's points ledger
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + UserPoints::INIT_SPACE,
+        seeds = [POINTS_SEED, owner.key().as_ref()],
+        bump
+    )]
+    pub user_points: Account<'info, UserPoints>,
+
+    /// The SNS name account (domain registry)
+    /// CHECK: Parent checked by check_sol_domain_registry; validated by the Name Service program
+    #[account(mut, owner = NAME_SERVICE_PROGRAM_ID)]
+    pub name_account: UncheckedAccount<'info>,
+
+    /// The entry tracking this domain in the vault
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + DomainEntry::INIT_SPACE,
+        seeds = [ENTRY_SEED, vault.key().as_ref(), name_account.key().as_ref()],
+        bump
+    )]
+    pub domain_entry: Account<'info, DomainEntry>,
+
+    /// The SNS Name Service program
+    /// CHECK: This is the official SNS Name Service program
+    #[account(address = NAME_SERVICE_PROGRAM_ID)]
+    pub name_service_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Withdraw an unwrapped SNS domain by transferring registry ownership back to the owner
+#[derive(Accounts)]
+pub struct WithdrawUnwrappedDomain<'info> {
+    /// The owner withdrawing a domain
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault (current owner of the domain)
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The global protocol config, if initialized; exits never depend on admin setup
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Option<Account<'info, ProtocolConfig>>,
+
+    /// The global protocol stats, if initialized; exits never depend on admin setup
+    #[account(mut, seeds = [STATS_SEED], bump = stats.bump)]
+    pub stats: Option<Account<'info, ProtocolStats>>,
+
+    /// The vault's summary PDA, which may not exist
+    /// CHECK: Derived address; deserialized only when initialized
+    #[account(mut, seeds = [VAULT_SUMMARY_SEED, vault.key().as_ref()], bump)]
+    pub vault_summary: UncheckedAccount<'info>,
+
+    /// The outflow circuit breaker PDA, which may not exist
+    /// CHECK: Derived address; deserialized only when initialized
+    #[account(mut, seeds = [OUTFLOW_BREAKER_SEED], bump)]
+    pub outflow_breaker: UncheckedAccount<'info>,
+
+    /// The security log PDA, which may not exist
+    /// CHECK: Derived address; deserialized only when initialized
+    #[account(mut, seeds = [SECURITY_LOG_SEED], bump)]
+    pub security_log: UncheckedAccount<'info>,
+
+    /// The owner's points ledger
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + UserPoints::INIT_SPACE,
+        seeds = [POINTS_SEED, owner.key().as_ref()],
+        bump
+    )]
+    pub user_points: Account<'info, UserPoints>,
+
+    /// The SNS name account (domain registry)
+    /// CHECK: This account is validated by the Name Service program
+    #[account(mut)]
+    pub name_account: UncheckedAccount<'info>,
+
+    /// The entry tracking this domain, closed on withdrawal
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED, vault.key().as_ref(), name_account.key().as_ref()],
+        bump = domain_entry.bump,
+        constraint = !domain_entry.staked @ VaultError::DomainStaked,
+        close = rent_payer
+    )]
+    pub domain_entry: Account<'info, DomainEntry>,
+
+    /// Whoever paid the entry's rent, refunded when it closes
+    /// CHECK: Address is the entry's recorded rent payer
+    #[account(mut, address = domain_entry.rent_payer)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    /// The SNS Name Service program
+    /// CHECK: This is the official SNS Name Service program
+    #[account(address = NAME_SERVICE_PROGRAM_ID)]
+    pub name_service_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// The vault's withdrawal phrase PDA, which may not exist
+    /// CHECK: Derived address; deserialized only when initialized
+    #[account(seeds = [WITHDRAWAL_PHRASE_SEED, vault.key().as_ref()], bump)]
+    pub withdrawal_phrase: UncheckedAccount<'info>,
+
+    /// The vault's co-signer, required when the vault has one
+    pub co_signer: Option<Signer<'info>>,
+}
+
+/// Deposit an unwrapped domain and update SOL record to point to vault PDA
+#[derive(Accounts)]
+pub struct DepositDomainWithRecord<'info> {
+    /// The owner depositing a domain
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The global protocol config (deposits are rejected while paused)
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The protocol fee treasury
+    #[account(mut, seeds = [TREASURY_SEED], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    /// The global protocol stats
+    #[account(mut, seeds = [STATS_SEED], bump = stats.bump)]
+    pub stats: Account<'info, ProtocolStats>,
+
+    /// The vault's summary PDA, which may not exist
+    /// CHECK: Derived address; deserialized only when initialized
+    #[account(mut, seeds = [VAULT_SUMMARY_SEED, vault.key().as_ref()], bump)]
+    pub vault_summary: UncheckedAccount<'info>,
+
+    /// The owner's points ledger
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + UserPoints::INIT_SPACE,
+        seeds = [POINTS_SEED, owner.key().as_ref()],
+        bump
+    )]
+    pub user_points: Account<'info, UserPoints>,
+
+    /// The SNS name account (domain registry)
+    /// CHECK: Parent checked by check_sol_domain_registry; validated by the Name Service program
+    #[account(mut, owner = NAME_SERVICE_PROGRAM_ID)]
+    pub name_account: UncheckedAccount<'info>,
+
+    /// The entry tracking this domain in the vault
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + DomainEntry::INIT_SPACE,
+        seeds = [ENTRY_SEED, vault.key().as_ref(), name_account.key().as_ref()],
+        bump
+    )]
+    pub domain_entry: Account<'info, DomainEntry>,
+
+    /// The SOL record V2 account (will be created/updated)
+    /// Derived as [sha256("SPL Name Service" + "\x02SOL"), central_state, name_account] under the Name Service program
+    /// CHECK: Address is checked against the SNS Records V2 derivation; contents are managed by the SNS Records V2 program
+    #[account(
+        mut,
+        seeds = [
+            SOL_RECORD_V2_HASHED_NAME.as_ref(),
+            central_state.key().as_ref(),
+            name_account.key().as_ref(),
+        ],
+        bump,
+        seeds::program = name_service_program.key()
+    )]
+    pub sol_record_v2: UncheckedAccount<'info>,
+
+    /// The central state account for SNS Records V2
+    /// Derived as [SNS_RECORDS_PROGRAM_ID] under the SNS Records V2 program
+    /// CHECK: Address is checked against the SNS Records V2 central state derivation
+    #[account(
+        seeds = [sns_records_program.key().as_ref()],
+        bump,
+        seeds::program = sns_records_program.key()
+    )]
+    pub central_state: UncheckedAccount<'info>,
+
+    /// The SNS Name Service program
+    /// CHECK: This is the official SNS Name Service program
+    #[account(address = NAME_SERVICE_PROGRAM_ID)]
+    pub name_service_program: UncheckedAccount<'info>,
+
+    /// The SNS Records V2 program
+    /// CHECK: This is the official SNS Records V2 program
+    #[account(address = SNS_RECORDS_PROGRAM_ID)]
+    pub sns_records_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// A timelocked change to protocol parameters
+/// Variants are Borsh-encoded by index in queued changes: only ever append new ones
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub enum ParamChange {
+    SetPaused { paused: bool },
+    SetTimelock { timelock_seconds: i64 },
+    SetMintAllowlistMode { enabled: bool },
+    SetFee { operation: FeeOperation, lamports: u64 },
+    SetStakingParams { staking: StakingParams },
+    SetClaimProgram { program: Pubkey, allowed: bool },
+    SetCpiProgram { program: Pubkey, allowed: bool },
+    SetPointsWeights { weights: PointsWeights },
+    /// Applied by `set_mint_policy`, which creates the policy account if needed
+    SetMintPolicy { mint: Pubkey, status: MintStatus },
+    /// Applied by `set_valuation_floors`, which creates the table account if needed
+    SetValuationFloors {
+        length_floors: [u64; VALUATION_LENGTH_TIERS],
+        category_floors: [u64; NAME_CATEGORIES],
+    },
+}
+
+/// Operations that can be charged a protocol fee
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum FeeOperation {
+    Deposit,
+    /// Kept for encoding stability; withdrawals are fee-free so a fee can never block an exit
+    Withdraw,
+    RecordUpdate,
+}
+
+/// Points credited for custody over time
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct PointsWeights {
+    /// Points per domain held in custody for a full day
+    pub custody_per_domain_day: u64,
+}
+
+/// On-chain points ledger of a vault owner, used for incentive programs
+#[account]
+#[derive(InitSpace)]
+pub struct UserPoints {
+    /// The vault owner these points belong to
+    pub owner: Pubkey,
+    /// Total points credited
+    pub points: u64,
+    /// Unix timestamp up to which custody points have been credited
+    pub last_accrual_at: i64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Staking parameters
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct StakingParams {
+    /// Reward paid per staked domain per full day, in lamports
+    pub reward_lamports_per_domain_day: u64,
+    /// Delay between `request_unstake` and `unstake_domain`
+    pub unstake_cooldown_seconds: i64,
+}
+
+/// Per-domain state of a domain held in a vault
+#[account]
+#[derive(InitSpace)]
+pub struct DomainEntry {
+    /// The vault holding the domain
+    pub vault: Pubkey,
+    /// Domain mint (wrapped) or name registry account (unwrapped)
+    pub domain: Pubkey,
+    /// How the domain is held, which decides the withdrawal path
+    pub kind: DomainKind,
+    /// Unix timestamp of the deposit
+    pub deposited_at: i64,
+    /// Whether the domain is staked; staked domains can't be withdrawn
+    pub staked: bool,
+    /// Unix timestamp up to which staking rewards have been settled
+    pub reward_checkpoint: i64,
+    /// Unix timestamp of the unstake request (0 if none)
+    pub unstake_requested_at: i64,
+    /// Unix timestamp after which unstaking can complete (0 if none)
+    pub unstake_available_at: i64,
+    /// Settled rewards not yet claimed, in lamports
+    pub unclaimed_rewards: u64,
+    /// Hash of the keys and data of the Record V2 accounts passed to the last snapshot
+    pub records_hash: [u8; 32],
+    /// Unix timestamp of the last record snapshot (0 if none)
+    pub records_snapshot_at: i64,
+    /// Whether the domain's records are frozen by the owner
+    pub records_locked: bool,
+    /// Owner-defined tag/category for grouping domains (e.g. a UTF-8 label, zero-padded)
+    pub tag: [u8; 32],
+    /// Key that paid the entry's rent and is refunded when it closes
+    pub rent_payer: Pubkey,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Owner-supplied hash binding an off-chain reference to the deposit (zero if none)
+    pub memo_hash: [u8; 32],
+    /// Zeroed space for future fields, carved from the front so they need no realloc
+    pub _reserved: [u8; ENTRY_RESERVED_BYTES],
+}
+
+/// How a vaulted domain is held
+/// The first two variants keep the byte layout of the former `wrapped: bool` flag
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum DomainKind {
+    /// Name registry owned by the vault; withdrawn with `withdraw_unwrapped_domain`
+    Unwrapped,
+    /// Tokenizer NFT under the SPL Token program; withdrawn with `withdraw_domain`
+    WrappedSplToken,
+    /// Tokenizer NFT under Token-2022; withdrawn with `withdraw_domain`
+    WrappedToken2022,
+    /// Compressed domain NFT (no custody path yet)
+    Compressed,
+    /// Metaplex Core asset (no custody path yet)
+    CoreAsset,
+    /// Domain of an alternative TLD program (no custody path yet)
+    AltTld,
+}
+
+/// Marks a vault token account as quarantined dust
+#[account]
+#[derive(InitSpace)]
+pub struct QuarantinedTokenAccount {
+    /// The vault owning the token account
+    pub vault: Pubkey,
+    /// The quarantined token account
+    pub token_account: Pubkey,
+    /// Mint of the quarantined token account
+    pub mint: Pubkey,
+    /// Unix timestamp of the quarantine
+    pub quarantined_at: i64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// One weighted recipient of a sweep
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct SweepDestination {
+    /// Wallet whose token account receives this share
+    pub wallet: Pubkey,
+    /// Share of each sweep in basis points
+    pub weight_bps: u16,
+}
+
+/// A mint a sweep policy forwards, with its own threshold
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct SweepMint {
+    /// The swept mint
+    pub mint: Pubkey,
+    /// Minimum token balance, in this mint's base units, before a sweep is allowed
+    pub threshold: u64,
+}
+
+/// Owner-configured policy forwarding incoming vault payments
+#[account]
+#[derive(InitSpace)]
+pub struct SweepPolicy {
+    /// The vault this policy applies to
+    pub vault: Pubkey,
+    /// Weighted wallets whose token accounts receive swept balances
+    #[max_len(MAX_SWEEP_DESTINATIONS)]
+    pub destinations: Vec<SweepDestination>,
+    /// Mints that may be swept, each with its threshold
+    #[max_len(MAX_SWEEP_MINTS)]
+    pub allowed_mints: Vec<SweepMint>,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Fixed denominations the sweep crank forwards incoming payments of one mint in
+#[account]
+#[derive(InitSpace)]
+pub struct SweepBuckets {
+    /// The vault these buckets apply to
+    pub vault: Pubkey,
+    /// Mint the denominations are in
+    pub mint: Pubkey,
+    /// The only account allowed to crank bucket releases
+    pub keeper: Pubkey,
+    /// Allowed sweep amounts in base units, strictly increasing
+    #[max_len(MAX_SWEEP_DENOMINATIONS)]
+    pub denominations: Vec<u64>,
+    /// Upper bound of the random delay between two releases
+    pub max_delay_seconds: u32,
+    /// Unix timestamp before which no bucket is released
+    pub next_release_at: i64,
+    /// Number of buckets released so far
+    pub releases: u64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Published price for paying a vaulted domain
+#[account]
+#[derive(InitSpace)]
+pub struct PaymentEndpoint {
+    /// The vault receiving payments
+    pub vault: Pubkey,
+    /// The vaulted domain (mint or name account) being paid
+    pub domain: Pubkey,
+    /// Mint payments are made in
+    pub mint: Pubkey,
+    /// Price per payment in base units
+    pub amount: u64,
+    /// Subscription period covered by each payment (0 for one-off payments)
+    pub period_seconds: i64,
+    /// Free-form description of the expected memo format
+    #[max_len(MAX_MEMO_SCHEMA_LEN)]
+    pub memo_schema: String,
+    /// Id of the next receipt
+    pub next_receipt_id: u64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Record of one payment to a domain endpoint
+#[account]
+#[derive(InitSpace)]
+pub struct PaymentReceipt {
+    /// The endpoint that was paid
+    pub endpoint: Pubkey,
+    /// Sequential receipt id within the endpoint
+    pub id: u64,
+    /// Who paid
+    pub payer: Pubkey,
+    /// Mint of the payment
+    pub mint: Pubkey,
+    /// Amount paid in base units
+    pub amount: u64,
+    /// Unix timestamp of the payment
+    pub paid_at: i64,
+    /// End of the subscription period paid for (0 for one-off payments)
+    pub valid_until: i64,
+    /// SHA-256 of the payer-supplied memo, for off-chain reconciliation
+    pub memo_hash: [u8; 32],
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// One record name→content pair of a record template
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub struct TemplateRecord {
+    /// Record name without the V2 prefix (e.g. "url", "IPFS", "twitter")
+    #[max_len(MAX_RECORD_NAME_LEN)]
+    pub name: String,
+    /// Raw record content
+    #[max_len(MAX_TEMPLATE_CONTENT_LEN)]
+    pub content: Vec<u8>,
+}
+
+/// Typed content of a record written through the vault
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum RecordContent {
+    /// A Solana public key, stored as its 32 bytes
+    Pubkey(Pubkey),
+    /// Free-form UTF-8 text (url, twitter, email, ...)
+    Utf8(String),
+    /// An IPFS CID, stored as its string form
+    IpfsCid(String),
+    /// An Ethereum-style 20-byte address
+    EthAddress([u8; 20]),
+    /// An IPv4 address, stored as its 4 octets (A record)
+    Ipv4Address([u8; 4]),
+    /// An IPv6 address, stored as its 16 octets (AAAA record)
+    Ipv6Address([u8; 16]),
+    /// A hostname, stored as its string form (CNAME record)
+    Hostname(String),
+    /// An Arweave transaction id, stored as its string form (ARWV record)
+    ArweaveTxId(String),
+}
+
+/// Decentralized site content pointer written by `set_content_record`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ContentRecord {
+    /// IPFS CID (IPFS record)
+    Ipfs(String),
+    /// Arweave transaction id (ARWV record)
+    Arweave(String),
+}
+
+/// DNS-style record in its human-readable form, parsed by `update_dns_record`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum DnsRecord {
+    /// IPv4 address, e.g. "192.0.2.1"
+    A(String),
+    /// IPv6 address, e.g. "2001:db8::1"
+    Aaaa(String),
+    /// Canonical hostname, e.g. "example.com"
+    Cname(String),
+    /// Free-form text
+    Txt(String),
+}
+
+/// Social platforms whose records can be staged for verification
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum SocialPlatform {
+    Twitter,
+    Discord,
+    Github,
+}
+
+/// External verification status of a social record
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum VerificationStatus {
+    Pending,
+    Verified,
+    Rejected,
+}
+
+/// Verification status of a social record posted for a vaulted domain
+#[account]
+#[derive(InitSpace)]
+pub struct SocialVerification {
+    /// Entry of the vaulted domain
+    pub domain_entry: Pubkey,
+    /// The Record V2 account holding the handle
+    pub record: Pubkey,
+    /// Platform of the handle
+    pub platform: SocialPlatform,
+    /// Current verification status
+    pub status: VerificationStatus,
+    /// When the record was last posted
+    pub posted_at: i64,
+    /// When the verification was finalized (0 while pending)
+    pub reviewed_at: i64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Owner-defined set of records applied to many vaulted domains at once
+#[account]
+#[derive(InitSpace)]
+pub struct RecordTemplate {
+    /// The vault owning the template
+    pub vault: Pubkey,
+    /// Template id within the vault
+    pub id: u8,
+    /// Records written by the template
+    #[max_len(MAX_TEMPLATE_RECORDS)]
+    pub records: Vec<TemplateRecord>,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Record-only rights of a third-party operator over one vaulted domain
+#[account]
+#[derive(InitSpace)]
+pub struct RecordOperatorGrant {
+    /// The vault holding the domain
+    pub vault: Pubkey,
+    /// The domain (name account) the grant covers
+    pub domain: Pubkey,
+    /// Key allowed to manage the domain's records
+    pub operator: Pubkey,
+    /// Unix timestamp of the grant
+    pub granted_at: i64,
+    /// Unix timestamp at which the grant lapses (0 for no expiry)
+    pub expires_at: i64,
+    /// Whether the operator may only write records from isolated transactions
+    pub isolated_only: bool,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Owner-approved, single-use flash record session on one vaulted domain
+#[account]
+#[derive(InitSpace)]
+pub struct RecordSessionPolicy {
+    /// The vault holding the domain
+    pub vault: Pubkey,
+    /// The domain (name account) the session covers
+    pub domain: Pubkey,
+    /// Third party allowed to open the session
+    pub delegate: Pubkey,
+    /// Record V2 accounts the session may write
+    #[max_len(MAX_SESSION_RECORDS)]
+    pub allowed_records: Vec<Pubkey>,
+    /// Hash the domain's records must have when the session ends
+    pub expected_records_hash: [u8; 32],
+    /// Unix timestamp after which the session can't be opened
+    pub expires_at: i64,
+    /// Whether the session is open (only ever true within one transaction)
+    pub active: bool,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Owner-encrypted recovery data for client-side secrets of a vault
+#[account]
+#[derive(InitSpace)]
+pub struct BackupBlob {
+    /// The vault the blob belongs to
+    pub vault: Pubkey,
+    /// Ciphertext, opaque to the program
+    #[max_len(MAX_BACKUP_BLOB_LEN)]
+    pub data: Vec<u8>,
+    /// Unix timestamp of the last update
+    pub updated_at: i64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Delegation of a vault's domain voting weight; its existence is the attestation
+#[account]
+#[derive(InitSpace)]
+pub struct VotingDelegation {
+    /// The vault whose domains carry the weight
+    pub vault: Pubkey,
+    /// The vault owner who delegated
+    pub owner: Pubkey,
+    /// Key that votes with the weight
+    pub delegate: Pubkey,
+    /// Unix timestamp of the delegation
+    pub delegated_at: i64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Compact per-vault counters for rendering a vault card from a single account fetch
+/// Maintained by every deposit and withdrawal once created
+#[account]
+#[derive(InitSpace)]
+pub struct VaultSummary {
+    /// The vault summarized
+    pub vault: Pubkey,
+    /// Domains currently in the vault
+    pub total_domains: u64,
+    /// Tokenized domains (SPL Token or Token-2022)
+    pub wrapped_count: u64,
+    /// Name registry domains
+    pub unwrapped_count: u64,
+    /// Unix timestamp of the last deposit or withdrawal
+    pub last_activity_at: i64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Per-vault alert routing read by the indexer/keeper
+#[account]
+#[derive(InitSpace)]
+pub struct NotificationEndpoints {
+    /// The vault the endpoints belong to
+    pub vault: Pubkey,
+    /// Hashed or opaque webhook identifiers, resolved off-chain
+    #[max_len(MAX_NOTIFICATION_WEBHOOKS)]
+    pub webhook_ids: Vec<[u8; 32]>,
+    /// Program to notify through instead of, or besides, webhooks
+    pub push_program: Option<Pubkey>,
+    /// `ALERT_*` bits the owner wants delivered
+    pub alert_mask: u8,
+    /// Unix timestamp of the last update
+    pub updated_at: i64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Pending change of a vault's withdrawal co-signer
+#[account]
+#[derive(InitSpace)]
+pub struct CoSignerChange {
+    /// The vault whose co-signer changes
+    pub vault: Pubkey,
+    /// The co-signer to set (default pubkey removes it)
+    pub new_co_signer: Pubkey,
+    /// Unix timestamp after which the change can be applied
+    pub eta: i64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Hash of a vault's anti-phishing withdrawal confirmation phrase
+#[account]
+#[derive(InitSpace)]
+pub struct WithdrawalPhrase {
+    /// The vault the phrase protects
+    pub vault: Pubkey,
+    /// sha256 of the phrase
+    pub phrase_hash: [u8; 32],
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Named group of a vault's domains, operated on together
+#[account]
+#[derive(InitSpace)]
+pub struct Bundle {
+    /// The vault owning the bundle
+    pub vault: Pubkey,
+    /// Bundle id within the vault
+    pub id: u8,
+    /// Display name
+    #[max_len(MAX_BUNDLE_NAME_LEN)]
+    pub name: String,
+    /// Domain entries in the bundle
+    #[max_len(MAX_BUNDLE_DOMAINS)]
+    pub entries: Vec<Pubkey>,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Number of name character categories
+pub const NAME_CATEGORIES: usize = 4;
+
+/// Character category of a domain name
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum NameCategory {
+    /// Digits only (e.g. "007")
+    Numeric,
+    /// ASCII letters only
+    Letters,
+    /// Other ASCII (letters with digits or hyphens)
+    Mixed,
+    /// Contains non-ASCII characters (e.g. emoji)
+    NonAscii,
+}
+
+/// Admin-posted floor prices used as valuation hints for vaulted domains
+#[account]
+#[derive(InitSpace)]
+pub struct ValuationTable {
+    /// Floor in lamports by name length (1, 2, ..., and longer names in the last tier)
+    pub length_floors: [u64; VALUATION_LENGTH_TIERS],
+    /// Floor in lamports by NameCategory
+    pub category_floors: [u64; NAME_CATEGORIES],
+    /// When the floors were last posted
+    pub updated_at: i64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Severity of a security log entry
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub enum SecuritySeverity {
+    #[default]
+    Info,
+    Warning,
+    Critical,
+}
+
+/// Kinds of occurrence the security log records
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub enum SecurityEventKind {
+    #[default]
+    OutflowBreakerTripped,
+    OutflowBreakerReset,
+    CoSignerChangeQueued,
+    TokenAccountQuarantined,
+}
+
+/// One security log entry
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct SecurityLogEntry {
+    pub kind: SecurityEventKind,
+    pub severity: SecuritySeverity,
+    /// Account the occurrence concerns (vault, token account, breaker)
+    pub subject: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Ring buffer of security-relevant occurrences, for monitoring without log parsing
+#[account]
+#[derive(InitSpace)]
+pub struct SecurityLog {
+    /// Entries ever written; the next entry goes to `total % SECURITY_LOG_CAPACITY`
+    pub total: u64,
+    pub entries: [SecurityLogEntry; SECURITY_LOG_CAPACITY],
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Rolling count of domains leaving custody, halting withdrawals on abnormal outflow
+#[account]
+#[derive(InitSpace)]
+pub struct OutflowBreaker {
+    /// Length of a counting window
+    pub window_seconds: i64,
+    /// Trip when a window's outflow exceeds this multiple of the trailing average
+    pub trip_multiple: u32,
+    /// Never trip at or below this many domains per window
+    pub min_trip_outflow: u64,
+    /// Start of the current window
+    pub window_start: i64,
+    /// Domains withdrawn in the current window
+    pub window_outflow: u64,
+    /// Moving average of domains withdrawn per past window
+    pub trailing_average: u64,
+    /// Whether withdrawals other than the emergency exit are halted until an admin reset
+    pub tripped: bool,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Cranks that can pay keeper bounties
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum CrankKind {
+    /// `run_scheduled_operation`, paid from the schedule's funded lamports
+    ScheduledRun,
+    /// `reap_expired_operator_grant`, paid from the reaped grant's rent
+    ReapOperatorGrant,
+    /// `reap_expired_record_session`, paid from the reaped session's rent
+    ReapRecordSession,
+    /// `apply_co_signer_change`, paid from the applied change's rent
+    ApplyCoSignerChange,
+}
+
+/// Lamports paid to keepers per crank run, indexed by `CrankKind`
+#[account]
+#[derive(InitSpace)]
+pub struct KeeperBounties {
+    pub lamports: [u64; CRANK_KIND_COUNT],
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Permissionless cranks a vault owner can schedule
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub enum ScheduledOperationKind {
+    /// `auto_sweep` of the vault
+    #[default]
+    Sweep,
+    /// `accrue_points` of the vault
+    PointsAccrual,
+}
+
+/// A recurring crank the owner wants run on their vault
+#[account]
+#[derive(InitSpace)]
+pub struct ScheduledOperation {
+    /// The vault the crank runs on
+    pub vault: Pubkey,
+    /// The crank to run
+    pub kind: ScheduledOperationKind,
+    /// Seconds between runs
+    pub interval_seconds: i64,
+    /// Unix timestamp from which the next run is due
+    pub next_run_at: i64,
+    /// Runs so far
+    pub runs: u64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Merkle root of (owner, domain) pairs custodied at a slot, for airdrops and governance snapshots
+/// Admin-attested: the program doesn't prove the root matches custody at the slot
+#[account]
+#[derive(InitSpace)]
+pub struct CustodySnapshot {
+    /// Slot the snapshot was taken at
+    pub slot: u64,
+    /// Root over `custody_snapshot_leaf` leaves
+    pub root: [u8; 32],
+    /// Number of leaves in the tree, as claimed by the admin
+    pub leaf_count: u64,
+    /// Admin-chosen entries proven against the root at commit time (a sanity check only)
+    pub samples_checked: u8,
+    /// Unix timestamp of the commit
+    pub committed_at: i64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// A disposable subdomain issued by a vault, deleted once it expires
+#[account]
+#[derive(InitSpace)]
+pub struct BurnerSubdomain {
+    /// The vault owning the parent and the subdomain
+    pub vault: Pubkey,
+    /// Name account of the vaulted parent domain
+    pub parent: Pubkey,
+    /// Name account of the subdomain
+    pub subdomain: Pubkey,
+    /// Unix timestamp from which anyone may revoke it
+    pub expires_at: i64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// A SOL record update split from its domain's deposit by a minimum slot gap
+#[account]
+#[derive(InitSpace)]
+pub struct StagedRecordUpdate {
+    /// The vault holding the domain
+    pub vault: Pubkey,
+    /// The domain's name registry account
+    pub name_account: Pubkey,
+    /// Slot the update was staged in
+    pub staged_slot: u64,
+    /// Slots that must pass before the update can be completed
+    pub min_slot_gap: u64,
+    /// Lamports set aside to reimburse the completing keeper's record rent
+    pub escrow_lamports: u64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Deployed program version and feature bits, for on-chain feature detection
+#[account]
+#[derive(InitSpace)]
+pub struct ProgramInfo {
+    /// Semantic version major
+    pub major: u16,
+    /// Semantic version minor
+    pub minor: u16,
+    /// Semantic version patch
+    pub patch: u16,
+    /// Bitmask of supported features (FEATURE_* constants)
+    pub features: u64,
+    /// Slot of the last sync
+    pub updated_slot: u64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Staking reward pool; funded lamports sit on this account
+#[account]
+#[derive(InitSpace)]
+pub struct RewardPool {
+    /// Total lamports ever funded
+    pub total_funded: u64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Protocol fee treasury; collected lamports sit on this account
+#[account]
+#[derive(InitSpace)]
+pub struct Treasury {
+    /// Total lamports ever collected as fees
+    pub total_collected: u64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Handlers counted in `ProtocolStats::instruction_counts`, indexed by discriminant
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatsCounter {
+    InitializeVault,
+    DepositDomain,
+    WithdrawDomain,
+    DepositUnwrappedDomain,
+    WithdrawUnwrappedDomain,
+    DepositDomainWithRecord,
+    PayDomain,
+}
+
+/// Number of `StatsCounter` variants
+pub const STATS_COUNTERS: usize = 7;
+
+/// Global protocol counters for dashboards; counts start when the account is created
+#[account]
+#[derive(InitSpace)]
+pub struct ProtocolStats {
+    /// Vaults initialized
+    pub total_vaults: u64,
+    /// Domains currently custodied across all vaults
+    pub total_domains: u64,
+    /// Domains ever moved into or out of a vault
+    pub total_volume: u64,
+    /// Successful calls per `StatsCounter`
+    pub instruction_counts: [u64; STATS_COUNTERS],
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Deposit policy of a mint
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum MintStatus {
+    Allowed,
+    Denied,
+}
+
+/// Admin-managed deposit policy for a single mint
+#[account]
+#[derive(InitSpace)]
+pub struct MintPolicy {
+    /// The mint this policy applies to
+    pub mint: Pubkey,
+    /// Whether the mint is allowed or denied
+    pub status: MintStatus,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// A parameter change waiting for the config timelock
+#[account]
+#[derive(InitSpace)]
+pub struct QueuedParamChange {
+    /// Sequential id, also used as PDA seed
+    pub id: u64,
+    /// The change to apply
+    pub change: ParamChange,
+    /// Unix timestamp at which the change was queued
+    pub queued_at: i64,
+    /// Unix timestamp after which the change can be executed
+    pub eta: i64,
+    /// Admin who queued the change and receives the rent back
+    pub payer: Pubkey,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Create the global protocol config (upgrade authority only)
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    /// The program upgrade authority, becomes the first admin
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// The global config PDA
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ProtocolConfig::INIT_SPACE,
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// This program, used to locate its program data account
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::SolanaProgram>,
+
+    /// The program data account holding the upgrade authority
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+            @ VaultError::UnauthorizedAccess
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Admin-signed update of the global config
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    /// The current config admin
+    pub admin: Signer<'info>,
+
+    /// The global config PDA
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ VaultError::UnauthorizedAccess
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+}
+
+/// Nominate an SPL Governance native treasury as admin
+#[derive(Accounts)]
+pub struct NominateGovernanceAdmin<'info> {
+    /// The current config admin
+    pub admin: Signer<'info>,
+
+    /// The global config PDA
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ VaultError::UnauthorizedAccess
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The realm the governance belongs to
+    /// CHECK: Owner is checked here, account type is checked in the handler
+    #[account(owner = SPL_GOVERNANCE_PROGRAM_ID @ VaultError::InvalidGovernanceAccount)]
+    pub realm: UncheckedAccount<'info>,
+
+    /// The governance account that will execute config instructions
+    /// CHECK: Owner is checked here, account type and realm are checked in the handler
+    #[account(owner = SPL_GOVERNANCE_PROGRAM_ID @ VaultError::InvalidGovernanceAccount)]
+    pub governance: UncheckedAccount<'info>,
+
+    /// The governance native treasury, which signs and pays for executed proposals
+    /// CHECK: Address is checked against the SPL Governance native treasury derivation
+    #[account(
+        seeds = [GOVERNANCE_NATIVE_TREASURY_SEED, governance.key().as_ref()],
+        bump,
+        seeds::program = SPL_GOVERNANCE_PROGRAM_ID
+    )]
+    pub native_treasury: UncheckedAccount<'info>,
+}
+
+/// Accept a pending admin nomination
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    /// The nominated admin
+    pub pending_admin: Signer<'info>,
+
+    /// The global config PDA
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+}
+
+/// Create or update a mint deposit policy
+#[derive(Accounts)]
+pub struct SetMintPolicy<'info> {
+    /// The current config admin
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// The global config PDA
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ VaultError::UnauthorizedAccess
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The mint the policy applies to
+    /// CHECK: Only used as a PDA seed; denylisted "mints" don't need to be valid mints
+    pub mint: UncheckedAccount<'info>,
+
+    /// The mint policy PDA
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + MintPolicy::INIT_SPACE,
+        seeds = [MINT_POLICY_SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub mint_policy: Account<'info, MintPolicy>,
+
+    /// The queued change to apply, required while a timelock is set
+    #[account(
+        mut,
+        seeds = [PARAM_CHANGE_SEED, queued_change.id.to_le_bytes().as_ref()],
+        bump = queued_change.bump
+    )]
+    pub queued_change: Option<Account<'info, QueuedParamChange>>,
+
+    /// The queued change's payer receiving the rent refund
+    /// CHECK: Checked against queued_change.payer in the handler
+    #[account(mut)]
+    pub payer: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Remove a mint deposit policy
+#[derive(Accounts)]
+pub struct RemoveMintPolicy<'info> {
+    /// The current config admin
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// The global config PDA
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ VaultError::UnauthorizedAccess
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The mint policy PDA to close
+    #[account(
+        mut,
+        seeds = [MINT_POLICY_SEED, mint_policy.mint.as_ref()],
+        bump = mint_policy.bump,
+        close = admin
+    )]
+    pub mint_policy: Account<'info, MintPolicy>,
+}
+
+/// Accrue custody points for a vault owner
+#[derive(Accounts)]
+pub struct AccruePoints<'info> {
+    /// The global config PDA
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The vault whose custody is credited
+    #[account(
+        seeds = [VAULT_SEED, vault.owner.as_ref()],
+        bump = vault.bump,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The owner's points ledger
+    #[account(
+        mut,
+        seeds = [POINTS_SEED, vault.owner.as_ref()],
+        bump = user_points.bump
+    )]
+    pub user_points: Account<'info, UserPoints>,
+}
+
+/// Backfill the entry of a wrapped domain deposited before entries existed
+#[derive(Accounts)]
+pub struct RegisterWrappedDomainEntry<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
 
     /// The SNS domain mint (NFT)
     pub domain_mint: InterfaceAccount<'info, Mint>,
 
-    /// User's token account holding the SNS domain
+    /// The tokenized domain's name registry, which the mint must be derived from
+    /// CHECK: Owned by the Name Service program; checked by check_wrapped_domain
+    #[account(owner = NAME_SERVICE_PROGRAM_ID)]
+    pub name_account: UncheckedAccount<'info>,
+
+    /// Vault's token account holding the SNS domain
+    #[account(
+        associated_token::mint = domain_mint,
+        associated_token::authority = vault,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The entry to create
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + DomainEntry::INIT_SPACE,
+        seeds = [ENTRY_SEED, vault.key().as_ref(), domain_mint.key().as_ref()],
+        bump
+    )]
+    pub domain_entry: Account<'info, DomainEntry>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Backfill the entry of an unwrapped domain deposited before entries existed
+#[derive(Accounts)]
+pub struct RegisterUnwrappedDomainEntry<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The SNS name account, which must be owned by the vault
+    /// CHECK: Owned by the Name Service program; registry owner is checked in the handler
+    #[account(owner = NAME_SERVICE_PROGRAM_ID)]
+    pub name_account: UncheckedAccount<'info>,
+
+    /// The entry to create
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + DomainEntry::INIT_SPACE,
+        seeds = [ENTRY_SEED, vault.key().as_ref(), name_account.key().as_ref()],
+        bump
+    )]
+    pub domain_entry: Account<'info, DomainEntry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Owner-signed update of a domain entry
+#[derive(Accounts)]
+pub struct UpdateDomainEntry<'info> {
+    /// The vault owner
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The domain entry to update
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED, vault.key().as_ref(), domain_entry.domain.as_ref()],
+        bump = domain_entry.bump,
+        has_one = vault
+    )]
+    pub domain_entry: Account<'info, DomainEntry>,
+}
+
+/// Start the unstake cooldown of a domain
+#[derive(Accounts)]
+pub struct RequestUnstake<'info> {
+    /// The vault owner
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The global config PDA
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The staked domain entry
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED, vault.key().as_ref(), domain_entry.domain.as_ref()],
+        bump = domain_entry.bump,
+        has_one = vault
+    )]
+    pub domain_entry: Account<'info, DomainEntry>,
+}
+
+/// Claim staking rewards of a domain
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    /// The vault owner, receiving the rewards
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The global config PDA
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The domain entry accruing rewards
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED, vault.key().as_ref(), domain_entry.domain.as_ref()],
+        bump = domain_entry.bump,
+        has_one = vault
+    )]
+    pub domain_entry: Account<'info, DomainEntry>,
+
+    /// The reward pool paying out
+    #[account(mut, seeds = [REWARD_POOL_SEED], bump = reward_pool.bump)]
+    pub reward_pool: Account<'info, RewardPool>,
+}
+
+/// Create the staking reward pool
+#[derive(Accounts)]
+pub struct InitializeRewardPool<'info> {
+    /// The current config admin
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// The global config PDA
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ VaultError::UnauthorizedAccess
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The reward pool PDA
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + RewardPool::INIT_SPACE,
+        seeds = [REWARD_POOL_SEED],
+        bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Fund the staking reward pool
+#[derive(Accounts)]
+pub struct FundRewardPool<'info> {
+    /// Anyone funding the pool
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    /// The reward pool PDA
+    #[account(mut, seeds = [REWARD_POOL_SEED], bump = reward_pool.bump)]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// CPI into an allowlisted airdrop claim program with the vault as signer
+#[derive(Accounts)]
+pub struct ClaimWithVaultSignature<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault, signing the claim
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The global config PDA holding the claim program allowlist
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The allowlisted claim program
+    /// CHECK: Checked against the config allowlist in the handler
+    #[account(executable)]
+    pub claim_program: UncheckedAccount<'info>,
+}
+
+/// Quarantine a vault token account
+#[derive(Accounts)]
+pub struct QuarantineTokenAccount<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// Mint of the unsolicited tokens
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// The vault token account to quarantine
+    #[account(
+        token::mint = token_mint,
+        token::authority = vault,
+        token::token_program = token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Domain entry PDA for this mint, which must not exist
+    /// CHECK: Only checked for emptiness
+    #[account(seeds = [ENTRY_SEED, vault.key().as_ref(), token_mint.key().as_ref()], bump)]
+    pub domain_entry: UncheckedAccount<'info>,
+
+    /// The quarantine flag PDA
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + QuarantinedTokenAccount::INIT_SPACE,
+        seeds = [QUARANTINE_SEED, vault_token_account.key().as_ref()],
+        bump
+    )]
+    pub quarantine: Account<'info, QuarantinedTokenAccount>,
+
+    /// The security log PDA, which may not exist
+    /// CHECK: Derived address; deserialized only when initialized
+    #[account(mut, seeds = [SECURITY_LOG_SEED], bump)]
+    pub security_log: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Lift the quarantine of a vault token account
+#[derive(Accounts)]
+pub struct ReleaseTokenAccount<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The quarantine flag PDA to close
+    #[account(
+        mut,
+        seeds = [QUARANTINE_SEED, quarantine.token_account.as_ref()],
+        bump = quarantine.bump,
+        has_one = vault,
+        close = owner
+    )]
+    pub quarantine: Account<'info, QuarantinedTokenAccount>,
+}
+
+/// Burn the balance of a quarantined vault token account
+#[derive(Accounts)]
+pub struct BurnDust<'info> {
+    /// The vault owner
+    pub owner: Signer<'info>,
+
+    /// The user's vault, authority of the token account
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The global protocol config holding the CPI allowlist
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// Mint of the dust tokens
+    #[account(mut)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// The quarantined vault token account
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = vault,
+        token::token_program = token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The quarantine flag of the token account
+    #[account(
+        seeds = [QUARANTINE_SEED, vault_token_account.key().as_ref()],
+        bump = quarantine.bump,
+        has_one = vault
+    )]
+    pub quarantine: Account<'info, QuarantinedTokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Strip delegates and close authorities from a vault token account
+#[derive(Accounts)]
+pub struct SanitizeVaultTokenAccount<'info> {
+    /// The vault owning the token account
+    #[account(
+        seeds = [VAULT_SEED, vault.owner.as_ref()],
+        bump = vault.bump,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The global protocol config holding the CPI allowlist
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The vault token account to sanitize
+    #[account(
+        mut,
+        token::authority = vault,
+        token::token_program = token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Close an empty quarantined vault token account
+#[derive(Accounts)]
+pub struct CloseDust<'info> {
+    /// The vault owner, receiving the rent
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault, authority of the token account
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The global protocol config holding the CPI allowlist
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The quarantined vault token account to close
+    #[account(
+        mut,
+        token::authority = vault,
+        token::token_program = token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The quarantine flag, closed along with the token account
+    #[account(
+        mut,
+        seeds = [QUARANTINE_SEED, vault_token_account.key().as_ref()],
+        bump = quarantine.bump,
+        has_one = vault,
+        close = owner
+    )]
+    pub quarantine: Account<'info, QuarantinedTokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Create or update a vault sweep policy
+#[derive(Accounts)]
+pub struct SetSweepPolicy<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The sweep policy PDA
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + SweepPolicy::INIT_SPACE,
+        seeds = [SWEEP_POLICY_SEED, vault.key().as_ref()],
+        bump
+    )]
+    pub sweep_policy: Account<'info, SweepPolicy>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Remove a vault sweep policy
+#[derive(Accounts)]
+pub struct RemoveSweepPolicy<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The sweep policy PDA to close
+    #[account(
+        mut,
+        seeds = [SWEEP_POLICY_SEED, vault.key().as_ref()],
+        bump = sweep_policy.bump,
+        close = owner
+    )]
+    pub sweep_policy: Account<'info, SweepPolicy>,
+}
+
+/// Create or update a mint's vault sweep buckets
+#[derive(Accounts)]
+#[instruction(mint: Pubkey)]
+pub struct SetSweepBuckets<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The sweep buckets PDA
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + SweepBuckets::INIT_SPACE,
+        seeds = [SWEEP_BUCKETS_SEED, vault.key().as_ref(), mint.as_ref()],
+        bump
+    )]
+    pub sweep_buckets: Account<'info, SweepBuckets>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Remove vault sweep buckets
+#[derive(Accounts)]
+pub struct RemoveSweepBuckets<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The sweep buckets PDA to close
+    #[account(
+        mut,
+        seeds = [SWEEP_BUCKETS_SEED, vault.key().as_ref(), sweep_buckets.mint.as_ref()],
+        bump = sweep_buckets.bump,
+        close = owner
+    )]
+    pub sweep_buckets: Account<'info, SweepBuckets>,
+}
+
+/// Permissionless sweep of a vault token balance
+#[derive(Accounts)]
+pub struct AutoSweep<'info> {
+    /// The vault holding the payment
+    #[account(
+        seeds = [VAULT_SEED, vault.owner.as_ref()],
+        bump = vault.bump,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The global protocol config holding the CPI allowlist
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The vault's sweep policy
+    #[account(
+        seeds = [SWEEP_POLICY_SEED, vault.key().as_ref()],
+        bump = sweep_policy.bump,
+        has_one = vault
+    )]
+    pub sweep_policy: Account<'info, SweepPolicy>,
+
+    /// The vault's sweep buckets for this mint, if any
+    /// CHECK: PDA seeds checked; only read and updated when initialized
+    #[account(
+        mut,
+        seeds = [SWEEP_BUCKETS_SEED, vault.key().as_ref(), token_mint.key().as_ref()],
+        bump
+    )]
+    pub sweep_buckets: UncheckedAccount<'info>,
+
+    /// Mint of the swept tokens
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// The vault token account being swept
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = vault,
+        token::token_program = token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Domain entry PDA for this mint, which must not exist
+    /// CHECK: Only checked for emptiness
+    #[account(seeds = [ENTRY_SEED, vault.key().as_ref(), token_mint.key().as_ref()], bump)]
+    pub domain_entry: UncheckedAccount<'info>,
+
+    /// Quarantine flag PDA of the vault token account, which must not exist
+    /// CHECK: Only checked for emptiness
+    #[account(seeds = [QUARANTINE_SEED, vault_token_account.key().as_ref()], bump)]
+    pub quarantine: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// The buckets' keeper; required only when the mint has sweep buckets
+    pub keeper: Option<Signer<'info>>,
+}
+
+/// Publish or update a domain payment endpoint
+#[derive(Accounts)]
+pub struct SetPaymentEndpoint<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// Entry of the vaulted domain being paid
+    #[account(
+        seeds = [ENTRY_SEED, vault.key().as_ref(), domain_entry.domain.as_ref()],
+        bump = domain_entry.bump,
+        has_one = vault @ VaultError::DomainNotInVault
+    )]
+    pub domain_entry: Account<'info, DomainEntry>,
+
+    /// Mint payments are made in
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+
+    /// The payment endpoint PDA
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + PaymentEndpoint::INIT_SPACE,
+        seeds = [PAYMENT_ENDPOINT_SEED, vault.key().as_ref(), domain_entry.domain.as_ref()],
+        bump
+    )]
+    pub payment_endpoint: Account<'info, PaymentEndpoint>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Close a domain payment endpoint
+#[derive(Accounts)]
+pub struct ClosePaymentEndpoint<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The payment endpoint to close
+    #[account(
+        mut,
+        seeds = [PAYMENT_ENDPOINT_SEED, vault.key().as_ref(), payment_endpoint.domain.as_ref()],
+        bump = payment_endpoint.bump,
+        has_one = vault,
+        close = owner
+    )]
+    pub payment_endpoint: Account<'info, PaymentEndpoint>,
+}
+
+/// Pay a vaulted domain
+#[derive(Accounts)]
+pub struct PayDomain<'info> {
+    /// The payer
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The vault receiving the payment
+    #[account(
+        seeds = [VAULT_SEED, vault.owner.as_ref()],
+        bump = vault.bump,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The global protocol config (payments are rejected while paused)
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The global protocol stats
+    #[account(mut, seeds = [STATS_SEED], bump = stats.bump)]
+    pub stats: Account<'info, ProtocolStats>,
+
+    /// Entry of the paid domain; payments stop once the domain leaves the vault
+    #[account(
+        seeds = [ENTRY_SEED, vault.key().as_ref(), payment_endpoint.domain.as_ref()],
+        bump = domain_entry.bump,
+        has_one = vault @ VaultError::DomainNotInVault
+    )]
+    pub domain_entry: Account<'info, DomainEntry>,
+
+    /// The domain's payment endpoint
+    #[account(
+        mut,
+        seeds = [PAYMENT_ENDPOINT_SEED, vault.key().as_ref(), payment_endpoint.domain.as_ref()],
+        bump = payment_endpoint.bump,
+        has_one = vault
+    )]
+    pub payment_endpoint: Account<'info, PaymentEndpoint>,
+
+    /// Mint of the payment
+    #[account(address = payment_endpoint.mint @ VaultError::InvalidPaymentMint)]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+
+    /// Payer's token account
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = payer,
+        token::token_program = token_program,
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Vault's token account receiving the payment
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = payment_mint,
+        associated_token::authority = vault,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Receipt of this payment
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + PaymentReceipt::INIT_SPACE,
+        seeds = [
+            RECEIPT_SEED,
+            payment_endpoint.key().as_ref(),
+            &payment_endpoint.next_receipt_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub receipt: Account<'info, PaymentReceipt>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Close a payment receipt
+#[derive(Accounts)]
+pub struct ClosePaymentReceipt<'info> {
+    /// The payer who funded the receipt
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The receipt to close
+    #[account(
+        mut,
+        seeds = [RECEIPT_SEED, receipt.endpoint.as_ref(), &receipt.id.to_le_bytes()],
+        bump = receipt.bump,
+        has_one = payer @ VaultError::UnauthorizedAccess,
+        close = payer
+    )]
+    pub receipt: Account<'info, PaymentReceipt>,
+}
+
+/// Snapshot a vaulted domain's records
+#[derive(Accounts)]
+pub struct SnapshotRecords<'info> {
+    /// The vault owner
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The domain's name registry account
+    /// CHECK: Matched against the entry by check_entry_name_account
+    #[account(owner = NAME_SERVICE_PROGRAM_ID)]
+    pub name_account: UncheckedAccount<'info>,
+
+    /// Entry of the vaulted domain
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED, vault.key().as_ref(), domain_entry.domain.as_ref()],
+        bump = domain_entry.bump,
+        has_one = vault @ VaultError::DomainNotInVault
+    )]
+    pub domain_entry: Account<'info, DomainEntry>,
+}
+
+/// Verify a vaulted domain's records against its snapshot
+#[derive(Accounts)]
+pub struct VerifyRecords<'info> {
+    /// The domain's name registry account
+    /// CHECK: Matched against the entry by check_entry_name_account
+    #[account(owner = NAME_SERVICE_PROGRAM_ID)]
+    pub name_account: UncheckedAccount<'info>,
+
+    /// Entry of the vaulted domain
+    #[account(
+        seeds = [ENTRY_SEED, domain_entry.vault.as_ref(), domain_entry.domain.as_ref()],
+        bump = domain_entry.bump
+    )]
+    pub domain_entry: Account<'info, DomainEntry>,
+}
+
+/// Write a record's ROA for a vaulted domain
+#[derive(Accounts)]
+pub struct WriteRecordRoa<'info> {
+    /// The vault owner (pays any record reallocation)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault, owner of the domain
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The global protocol config holding the CPI allowlist (writes are rejected while paused)
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The domain's name registry account
+    /// CHECK: Matched against the entry by check_entry_name_account
+    #[account(mut, owner = NAME_SERVICE_PROGRAM_ID)]
+    pub name_account: UncheckedAccount<'info>,
+
+    /// Entry of the vaulted domain
+    #[account(
+        seeds = [ENTRY_SEED, vault.key().as_ref(), name_account.key().as_ref()],
+        bump = domain_entry.bump,
+        has_one = vault @ VaultError::DomainNotInVault
+    )]
+    pub domain_entry: Account<'info, DomainEntry>,
+
+    /// The Record V2 account
+    /// CHECK: Checked to be a record of the domain by check_record_account
+    #[account(mut)]
+    pub record: UncheckedAccount<'info>,
+
+    /// The central state account for SNS Records V2
+    /// CHECK: Address is checked against the SNS Records V2 central state derivation
+    #[account(
+        seeds = [sns_records_program.key().as_ref()],
+        bump,
+        seeds::program = sns_records_program.key()
+    )]
+    pub central_state: UncheckedAccount<'info>,
+
+    /// The SNS Name Service program
+    /// CHECK: This is the official SNS Name Service program
+    #[account(address = NAME_SERVICE_PROGRAM_ID)]
+    pub name_service_program: UncheckedAccount<'info>,
+
+    /// The SNS Records V2 program
+    /// CHECK: This is the official SNS Records V2 program
+    #[account(address = SNS_RECORDS_PROGRAM_ID)]
+    pub sns_records_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Stage a SOL record update of a vaulted domain
+#[derive(Accounts)]
+pub struct StageRecordUpdate<'info> {
+    /// The vault owner, paying the fee, rent and escrow
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The global protocol config
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The protocol fee treasury
+    #[account(mut, seeds = [TREASURY_SEED], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    /// The domain's name registry account
+    /// CHECK: Matched against the entry by check_entry_name_account
+    #[account(owner = NAME_SERVICE_PROGRAM_ID)]
+    pub name_account: UncheckedAccount<'info>,
+
+    /// Entry of the vaulted domain
+    #[account(
+        seeds = [ENTRY_SEED, vault.key().as_ref(), name_account.key().as_ref()],
+        bump = domain_entry.bump,
+        has_one = vault @ VaultError::DomainNotInVault
+    )]
+    pub domain_entry: Account<'info, DomainEntry>,
+
+    /// The staged update PDA
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + StagedRecordUpdate::INIT_SPACE,
+        seeds = [STAGED_RECORD_SEED, name_account.key().as_ref()],
+        bump
+    )]
+    pub staged_record: Account<'info, StagedRecordUpdate>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Cancel a staged SOL record update
+#[derive(Accounts)]
+pub struct CancelStagedRecordUpdate<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The staged update to close
+    #[account(
+        mut,
+        seeds = [STAGED_RECORD_SEED, staged_record.name_account.as_ref()],
+        bump = staged_record.bump,
+        has_one = vault,
+        close = owner
+    )]
+    pub staged_record: Account<'info, StagedRecordUpdate>,
+}
+
+/// Complete a staged SOL record update
+#[derive(Accounts)]
+pub struct CompleteStagedRecordUpdate<'info> {
+    /// Anyone completing the update, paying record rent up front
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    /// The vault owning the domain
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault.owner.as_ref()],
+        bump = vault.bump,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The vault owner, receiving the staged account's remaining lamports
+    /// CHECK: Address is the vault owner
+    #[account(mut, address = vault.owner)]
+    pub owner: UncheckedAccount<'info>,
+
+    /// The global protocol config holding the CPI allowlist
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The domain's name registry account
+    /// CHECK: Address is the staged name account, whose entry below proves custody
+    #[account(mut, address = staged_record.name_account)]
+    pub name_account: UncheckedAccount<'info>,
+
+    /// Entry of the vaulted domain; completion fails once the domain left the vault
+    #[account(
+        seeds = [ENTRY_SEED, vault.key().as_ref(), name_account.key().as_ref()],
+        bump = domain_entry.bump,
+        has_one = vault @ VaultError::DomainNotInVault
+    )]
+    pub domain_entry: Account<'info, DomainEntry>,
+
+    /// The staged update, closed on completion
+    #[account(
+        mut,
+        seeds = [STAGED_RECORD_SEED, name_account.key().as_ref()],
+        bump = staged_record.bump,
+        has_one = vault,
+        close = owner
+    )]
+    pub staged_record: Account<'info, StagedRecordUpdate>,
+
+    /// The SOL record V2 account
+    /// CHECK: Address is checked against the SNS Records V2 derivation
+    #[account(
+        mut,
+        seeds = [
+            SOL_RECORD_V2_HASHED_NAME.as_ref(),
+            central_state.key().as_ref(),
+            name_account.key().as_ref(),
+        ],
+        bump,
+        seeds::program = name_service_program.key()
+    )]
+    pub sol_record_v2: UncheckedAccount<'info>,
+
+    /// The central state account for SNS Records V2
+    /// CHECK: Address is checked against the SNS Records V2 central state derivation
+    #[account(
+        seeds = [sns_records_program.key().as_ref()],
+        bump,
+        seeds::program = sns_records_program.key()
+    )]
+    pub central_state: UncheckedAccount<'info>,
+
+    /// The SNS Name Service program
+    /// CHECK: This is the official SNS Name Service program
+    #[account(address = NAME_SERVICE_PROGRAM_ID)]
+    pub name_service_program: UncheckedAccount<'info>,
+
+    /// The SNS Records V2 program
+    /// CHECK: This is the official SNS Records V2 program
+    #[account(address = SNS_RECORDS_PROGRAM_ID)]
+    pub sns_records_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Validate a vaulted domain record's ROA with an external verifier key
+#[derive(Accounts)]
+pub struct ValidateRoaExternal<'info> {
+    /// The record's roaId, signing as verifier (pays any record reallocation)
+    #[account(mut)]
+    pub verifier: Signer<'info>,
+
+    /// The vault owning the domain
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault.owner.as_ref()],
+        bump = vault.bump,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The global protocol config holding the CPI allowlist (validation is rejected while paused)
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The domain's name registry account
+    /// CHECK: Matched against the entry by check_entry_name_account
+    #[account(mut, owner = NAME_SERVICE_PROGRAM_ID)]
+    pub name_account: UncheckedAccount<'info>,
+
+    /// Entry of the vaulted domain
+    #[account(
+        seeds = [ENTRY_SEED, vault.key().as_ref(), name_account.key().as_ref()],
+        bump = domain_entry.bump,
+        has_one = vault @ VaultError::DomainNotInVault
+    )]
+    pub domain_entry: Account<'info, DomainEntry>,
+
+    /// The Record V2 account
+    /// CHECK: Checked to be a record of the domain by check_record_account
+    #[account(mut)]
+    pub record: UncheckedAccount<'info>,
+
+    /// The central state account for SNS Records V2
+    /// CHECK: Address is checked against the SNS Records V2 central state derivation
+    #[account(
+        seeds = [sns_records_program.key().as_ref()],
+        bump,
+        seeds::program = sns_records_program.key()
+    )]
+    pub central_state: UncheckedAccount<'info>,
+
+    /// The SNS Name Service program
+    /// CHECK: This is the official SNS Name Service program
+    #[account(address = NAME_SERVICE_PROGRAM_ID)]
+    pub name_service_program: UncheckedAccount<'info>,
+
+    /// The SNS Records V2 program
+    /// CHECK: This is the official SNS Records V2 program
+    #[account(address = SNS_RECORDS_PROGRAM_ID)]
+    pub sns_records_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Re-validate staleness of a vaulted domain's SOL record
+#[derive(Accounts)]
+pub struct RefreshSolRecordStaleness<'info> {
+    /// The vault owner (pays any record reallocation)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault, owner of the domain
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The global protocol config holding the CPI allowlist
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The domain's name registry account
+    /// CHECK: Its entry must exist under the vault; validated by the Name Service program
+    #[account(mut, owner = NAME_SERVICE_PROGRAM_ID)]
+    pub name_account: UncheckedAccount<'info>,
+
+    /// Entry of the vaulted (unwrapped) domain
+    #[account(
+        seeds = [ENTRY_SEED, vault.key().as_ref(), name_account.key().as_ref()],
+        bump = domain_entry.bump,
+        has_one = vault @ VaultError::DomainNotInVault
+    )]
+    pub domain_entry: Account<'info, DomainEntry>,
+
+    /// The SOL record V2 account
+    /// CHECK: Address is checked against the SNS Records V2 derivation; contents are managed by the SNS Records V2 program
+    #[account(
+        mut,
+        seeds = [
+            SOL_RECORD_V2_HASHED_NAME.as_ref(),
+            central_state.key().as_ref(),
+            name_account.key().as_ref(),
+        ],
+        bump,
+        seeds::program = name_service_program.key()
+    )]
+    pub sol_record_v2: UncheckedAccount<'info>,
+
+    /// The central state account for SNS Records V2
+    /// CHECK: Address is checked against the SNS Records V2 central state derivation
+    #[account(
+        seeds = [sns_records_program.key().as_ref()],
+        bump,
+        seeds::program = sns_records_program.key()
+    )]
+    pub central_state: UncheckedAccount<'info>,
+
+    /// The SNS Name Service program
+    /// CHECK: This is the official SNS Name Service program
+    #[account(address = NAME_SERVICE_PROGRAM_ID)]
+    pub name_service_program: UncheckedAccount<'info>,
+
+    /// The SNS Records V2 program
+    /// CHECK: This is the official SNS Records V2 program
+    #[account(address = SNS_RECORDS_PROGRAM_ID)]
+    pub sns_records_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create or replace a record template
+#[derive(Accounts)]
+#[instruction(template_id: u8)]
+pub struct SetRecordTemplate<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The record template PDA
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + RecordTemplate::INIT_SPACE,
+        seeds = [RECORD_TEMPLATE_SEED, vault.key().as_ref(), &[template_id]],
+        bump
+    )]
+    pub record_template: Account<'info, RecordTemplate>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Close a record template
+#[derive(Accounts)]
+pub struct CloseRecordTemplate<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The record template to close
+    #[account(
+        mut,
+        seeds = [RECORD_TEMPLATE_SEED, vault.key().as_ref(), &[record_template.id]],
+        bump = record_template.bump,
+        has_one = vault,
+        close = owner
+    )]
+    pub record_template: Account<'info, RecordTemplate>,
+}
+
+/// Apply a record template to vaulted domains
+#[derive(Accounts)]
+pub struct ApplyRecordTemplate<'info> {
+    /// The vault owner (pays record rent)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault, owner of the domains
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The global protocol config
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The protocol fee treasury
+    #[account(mut, seeds = [TREASURY_SEED], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    /// The template to apply
+    #[account(
+        seeds = [RECORD_TEMPLATE_SEED, vault.key().as_ref(), &[record_template.id]],
+        bump = record_template.bump,
+        has_one = vault
+    )]
+    pub record_template: Account<'info, RecordTemplate>,
+
+    /// The central state account for SNS Records V2
+    /// CHECK: Address is checked against the SNS Records V2 central state derivation
+    #[account(
+        seeds = [sns_records_program.key().as_ref()],
+        bump,
+        seeds::program = sns_records_program.key()
+    )]
+    pub central_state: UncheckedAccount<'info>,
+
+    /// The SNS Name Service program
+    /// CHECK: This is the official SNS Name Service program
+    #[account(address = NAME_SERVICE_PROGRAM_ID)]
+    pub name_service_program: UncheckedAccount<'info>,
+
+    /// The SNS Records V2 program
+    /// CHECK: This is the official SNS Records V2 program
+    #[account(address = SNS_RECORDS_PROGRAM_ID)]
+    pub sns_records_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Grant a record operator on a vaulted domain
+#[derive(Accounts)]
+#[instruction(operator: Pubkey)]
+pub struct GrantRecordOperator<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// Entry of the vaulted domain
+    #[account(
+        seeds = [ENTRY_SEED, vault.key().as_ref(), domain_entry.domain.as_ref()],
+        bump = domain_entry.bump,
+        has_one = vault @ VaultError::DomainNotInVault
+    )]
+    pub domain_entry: Account<'info, DomainEntry>,
+
+    /// The operator grant PDA
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + RecordOperatorGrant::INIT_SPACE,
+        seeds = [RECORD_OPERATOR_SEED, domain_entry.key().as_ref(), operator.as_ref()],
+        bump
+    )]
+    pub operator_grant: Account<'info, RecordOperatorGrant>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Revoke a record operator grant
+#[derive(Accounts)]
+pub struct RevokeRecordOperator<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The operator grant to close
+    #[account(
+        mut,
+        has_one = vault,
+        close = owner
+    )]
+    pub operator_grant: Account<'info, RecordOperatorGrant>,
+}
+
+/// Update a record of a vaulted domain as owner or operator
+#[derive(Accounts)]
+pub struct UpdateRecord<'info> {
+    /// The vault owner or a record operator of the domain (pays fees and record rent)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// The vault owning the domain
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault.owner.as_ref()],
+        bump = vault.bump,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The global protocol config
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The protocol fee treasury
+    #[account(mut, seeds = [TREASURY_SEED], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    /// The domain's name registry account
+    /// CHECK: Matched against the entry by check_entry_name_account
+    #[account(mut, owner = NAME_SERVICE_PROGRAM_ID)]
+    pub name_account: UncheckedAccount<'info>,
+
+    /// Entry of the vaulted domain
+    #[account(
+        seeds = [ENTRY_SEED, vault.key().as_ref(), name_account.key().as_ref()],
+        bump = domain_entry.bump,
+        has_one = vault @ VaultError::DomainNotInVault
+    )]
+    pub domain_entry: Account<'info, DomainEntry>,
+
+    /// The authority's operator grant; required unless the authority is the vault owner
+    #[account(
+        seeds = [RECORD_OPERATOR_SEED, domain_entry.key().as_ref(), authority.key().as_ref()],
+        bump = operator_grant.bump
+    )]
+    pub operator_grant: Option<Account<'info, RecordOperatorGrant>>,
+
+    /// The instructions sysvar, inspected for operators with isolated-only grants
+    /// CHECK: Address is the instructions sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    /// The Record V2 account to write
+    /// CHECK: Derivation from the record name is checked by the SNS Records program
+    #[account(mut)]
+    pub record: UncheckedAccount<'info>,
+
+    /// The central state account for SNS Records V2
+    /// CHECK: Address is checked against the SNS Records V2 central state derivation
+    #[account(
+        seeds = [sns_records_program.key().as_ref()],
+        bump,
+        seeds::program = sns_records_program.key()
+    )]
+    pub central_state: UncheckedAccount<'info>,
+
+    /// The SNS Name Service program
+    /// CHECK: This is the official SNS Name Service program
+    #[account(address = NAME_SERVICE_PROGRAM_ID)]
+    pub name_service_program: UncheckedAccount<'info>,
+
+    /// The SNS Records V2 program
+    /// CHECK: This is the official SNS Records V2 program
+    #[account(address = SNS_RECORDS_PROGRAM_ID)]
+    pub sns_records_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Approve a flash record session on a vaulted domain
+#[derive(Accounts)]
+pub struct ApproveRecordSession<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// Entry of the vaulted domain
+    #[account(
+        seeds = [ENTRY_SEED, vault.key().as_ref(), domain_entry.domain.as_ref()],
+        bump = domain_entry.bump,
+        has_one = vault @ VaultError::DomainNotInVault
+    )]
+    pub domain_entry: Account<'info, DomainEntry>,
+
+    /// The session policy PDA
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + RecordSessionPolicy::INIT_SPACE,
+        seeds = [RECORD_SESSION_SEED, domain_entry.key().as_ref()],
+        bump
+    )]
+    pub session_policy: Account<'info, RecordSessionPolicy>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Revoke an unused flash record session
+#[derive(Accounts)]
+pub struct RevokeRecordSession<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The session policy to close
+    #[account(
+        mut,
+        has_one = vault,
+        constraint = !session_policy.active @ VaultError::RecordSessionActive,
+        close = owner
+    )]
+    pub session_policy: Account<'info, RecordSessionPolicy>,
+}
+
+/// Open a flash record session
+#[derive(Accounts)]
+pub struct BeginRecordSession<'info> {
+    /// The approved delegate
+    pub delegate: Signer<'info>,
+
+    /// The vault holding the domain
+    #[account(
+        seeds = [VAULT_SEED, vault.owner.as_ref()],
+        bump = vault.bump,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// Entry of the vaulted domain
+    #[account(
+        seeds = [ENTRY_SEED, vault.key().as_ref(), domain_entry.domain.as_ref()],
+        bump = domain_entry.bump,
+        has_one = vault @ VaultError::DomainNotInVault
+    )]
+    pub domain_entry: Account<'info, DomainEntry>,
+
+    /// The session policy
+    #[account(
+        mut,
+        seeds = [RECORD_SESSION_SEED, domain_entry.key().as_ref()],
+        bump = session_policy.bump,
+        has_one = vault,
+        has_one = delegate @ VaultError::UnauthorizedAccess
+    )]
+    pub session_policy: Account<'info, RecordSessionPolicy>,
+
+    /// The instructions sysvar, used to find the matching end_record_session
+    /// CHECK: Address is the instructions sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+}
+
+/// Write a record inside an open flash record session
+#[derive(Accounts)]
+pub struct SessionUpdateRecord<'info> {
+    /// The approved delegate (pays fees and record rent)
+    #[account(mut)]
+    pub delegate: Signer<'info>,
+
+    /// The vault owning the domain
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault.owner.as_ref()],
+        bump = vault.bump,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The global protocol config
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The protocol fee treasury
+    #[account(mut, seeds = [TREASURY_SEED], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    /// The domain's name registry account
+    /// CHECK: Address is tied to the entry by its seeds
+    #[account(mut, owner = NAME_SERVICE_PROGRAM_ID)]
+    pub name_account: UncheckedAccount<'info>,
+
+    /// Entry of the vaulted domain
+    #[account(
+        seeds = [ENTRY_SEED, vault.key().as_ref(), name_account.key().as_ref()],
+        bump = domain_entry.bump,
+        has_one = vault @ VaultError::DomainNotInVault
+    )]
+    pub domain_entry: Account<'info, DomainEntry>,
+
+    /// The open session policy
+    #[account(
+        seeds = [RECORD_SESSION_SEED, domain_entry.key().as_ref()],
+        bump = session_policy.bump,
+        has_one = delegate @ VaultError::UnauthorizedAccess
+    )]
+    pub session_policy: Account<'info, RecordSessionPolicy>,
+
+    /// The Record V2 account to write
+    /// CHECK: Derivation from the record name is checked by the SNS Records program
+    #[account(mut)]
+    pub record: UncheckedAccount<'info>,
+
+    /// The central state account for SNS Records V2
+    /// CHECK: Address is checked against the SNS Records V2 central state derivation
+    #[account(
+        seeds = [sns_records_program.key().as_ref()],
+        bump,
+        seeds::program = sns_records_program.key()
+    )]
+    pub central_state: UncheckedAccount<'info>,
+
+    /// The SNS Name Service program
+    /// CHECK: This is the official SNS Name Service program
+    #[account(address = NAME_SERVICE_PROGRAM_ID)]
+    pub name_service_program: UncheckedAccount<'info>,
+
+    /// The SNS Records V2 program
+    /// CHECK: This is the official SNS Records V2 program
+    #[account(address = SNS_RECORDS_PROGRAM_ID)]
+    pub sns_records_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// End a flash record session
+#[derive(Accounts)]
+pub struct EndRecordSession<'info> {
+    /// The session policy; must stay the first account, begin_record_session looks for it here
+    #[account(
+        mut,
+        seeds = [RECORD_SESSION_SEED, domain_entry.key().as_ref()],
+        bump = session_policy.bump,
+        has_one = vault,
+        close = owner
+    )]
+    pub session_policy: Account<'info, RecordSessionPolicy>,
+
+    /// The vault that must still hold the domain
+    #[account(
+        seeds = [VAULT_SEED, vault.owner.as_ref()],
+        bump = vault.bump,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The vault owner, refunded the policy rent
+    /// CHECK: Address is the vault owner
+    #[account(mut, address = vault.owner)]
+    pub owner: UncheckedAccount<'info>,
+
+    /// The domain's name registry account
+    /// CHECK: Address is tied to the entry by its seeds; registry owner is checked in the handler
+    #[account(owner = NAME_SERVICE_PROGRAM_ID)]
+    pub name_account: UncheckedAccount<'info>,
+
+    /// Entry of the vaulted domain
+    #[account(
+        seeds = [ENTRY_SEED, vault.key().as_ref(), name_account.key().as_ref()],
+        bump = domain_entry.bump,
+        has_one = vault @ VaultError::DomainNotInVault
+    )]
+    pub domain_entry: Account<'info, DomainEntry>,
+}
+
+/// Reap a lapsed operator grant
+#[derive(Accounts)]
+pub struct ReapExpiredOperatorGrant<'info> {
+    /// The vault the grant belongs to
+    #[account(
+        seeds = [VAULT_SEED, vault.owner.as_ref()],
+        bump = vault.bump,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The vault owner, refunded the rent
+    /// CHECK: Address is the vault owner
+    #[account(mut, address = vault.owner)]
+    pub owner: UncheckedAccount<'info>,
+
+    /// The expired grant to close
+    #[account(mut, has_one = vault, close = owner)]
+    pub operator_grant: Account<'info, RecordOperatorGrant>,
+
+    /// The keeper bounty schedule; no bounty is paid without it
+    #[account(seeds = [KEEPER_BOUNTIES_SEED], bump = keeper_bounties.bump)]
+    pub keeper_bounties: Option<Account<'info, KeeperBounties>>,
+
+    /// The keeper running the crank, paid its bounty
+    #[account(mut)]
+    pub keeper: Option<Signer<'info>>,
+}
+
+/// Reap a lapsed record session policy
+#[derive(Accounts)]
+pub struct ReapExpiredRecordSession<'info> {
+    /// The vault the session belongs to
+    #[account(
+        seeds = [VAULT_SEED, vault.owner.as_ref()],
+        bump = vault.bump,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The vault owner, refunded the rent
+    /// CHECK: Address is the vault owner
+    #[account(mut, address = vault.owner)]
+    pub owner: UncheckedAccount<'info>,
+
+    /// The expired session policy to close
+    #[account(mut, has_one = vault, close = owner)]
+    pub session_policy: Account<'info, RecordSessionPolicy>,
+
+    /// The keeper bounty schedule; no bounty is paid without it
+    #[account(seeds = [KEEPER_BOUNTIES_SEED], bump = keeper_bounties.bump)]
+    pub keeper_bounties: Option<Account<'info, KeeperBounties>>,
+
+    /// The keeper running the crank, paid its bounty
+    #[account(mut)]
+    pub keeper: Option<Signer<'info>>,
+}
+
+/// Grow an old vault to the current layout
+#[derive(Accounts)]
+pub struct UpgradeVault<'info> {
+    /// The vault owner, paying the extra rent
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The vault to upgrade; may not deserialize with the current layout yet
+    /// CHECK: Address is the owner's vault PDA; discriminator and owner are checked in the handler
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump,
+        owner = crate::ID
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Migrate a legacy account layout in place (upgrade authority only)
+#[cfg(feature = "migrations")]
+#[derive(Accounts)]
+pub struct AdminMigrateAccount<'info> {
+    /// The program upgrade authority, paying any extra rent
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// This program, used to locate its program data account
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::SolanaProgram>,
+
+    /// The program data account holding the upgrade authority
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+            @ VaultError::UnauthorizedAccess
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    /// The account to migrate; may not deserialize with the current layout yet
+    /// CHECK: Owned by this program; the discriminator is checked in the handler
+    #[account(mut, owner = crate::ID)]
+    pub target: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Check the invariants of a vault (read-only, callable by anyone)
+#[derive(Accounts)]
+pub struct CheckVaultHealth<'info> {
+    /// The vault to check
+    #[account(seeds = [VAULT_SEED, vault.owner.as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, UserVault>,
+}
+
+/// Return every supplied vaulted domain to the owner (emergency exit)
+#[derive(Accounts)]
+pub struct EmergencyWithdrawAll<'info> {
+    /// The vault owner
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The global protocol stats, if initialized; exits never depend on admin setup
+    #[account(mut, seeds = [STATS_SEED], bump = stats.bump)]
+    pub stats: Option<Account<'info, ProtocolStats>>,
+
+    /// The vault's summary PDA, which may not exist
+    /// CHECK: Derived address; deserialized only when initialized
+    #[account(mut, seeds = [VAULT_SUMMARY_SEED, vault.key().as_ref()], bump)]
+    pub vault_summary: UncheckedAccount<'info>,
+
+    /// The outflow circuit breaker PDA, which may not exist
+    /// CHECK: Derived address; deserialized only when initialized
+    #[account(mut, seeds = [OUTFLOW_BREAKER_SEED], bump)]
+    pub outflow_breaker: UncheckedAccount<'info>,
+
+    /// The security log PDA, which may not exist
+    /// CHECK: Derived address; deserialized only when initialized
+    #[account(mut, seeds = [SECURITY_LOG_SEED], bump)]
+    pub security_log: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub token_2022_program: Program<'info, Token2022>,
+
+    /// The SNS Name Service program
+    /// CHECK: This is the official SNS Name Service program
+    #[account(address = NAME_SERVICE_PROGRAM_ID)]
+    pub name_service_program: UncheckedAccount<'info>,
+
+    /// The vault's withdrawal phrase PDA, which may not exist
+    /// CHECK: Derived address; deserialized only when initialized
+    #[account(seeds = [WITHDRAWAL_PHRASE_SEED, vault.key().as_ref()], bump)]
+    pub withdrawal_phrase: UncheckedAccount<'info>,
+
+    /// The vault's co-signer, required when the vault has one
+    pub co_signer: Option<Signer<'info>>,
+}
+
+/// Create or extend a domain bundle
+#[derive(Accounts)]
+#[instruction(bundle_id: u8)]
+pub struct SetBundle<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The bundle PDA
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + Bundle::INIT_SPACE,
+        seeds = [BUNDLE_SEED, vault.key().as_ref(), &[bundle_id]],
+        bump
+    )]
+    pub bundle: Account<'info, Bundle>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Change the contents of a domain bundle
+#[derive(Accounts)]
+pub struct UpdateBundle<'info> {
+    /// The vault owner
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The bundle to update
+    #[account(
+        mut,
+        seeds = [BUNDLE_SEED, vault.key().as_ref(), &[bundle.id]],
+        bump = bundle.bump,
+        has_one = vault
+    )]
+    pub bundle: Account<'info, Bundle>,
+}
+
+/// Close a domain bundle
+#[derive(Accounts)]
+pub struct CloseBundle<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The bundle to close
+    #[account(
+        mut,
+        seeds = [BUNDLE_SEED, vault.key().as_ref(), &[bundle.id]],
+        bump = bundle.bump,
+        has_one = vault,
+        close = owner
+    )]
+    pub bundle: Account<'info, Bundle>,
+}
+
+/// Withdraw a page of a bundle's domains to the owner
+#[derive(Accounts)]
+pub struct WithdrawBundle<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The global protocol stats, if initialized; exits never depend on admin setup
+    #[account(mut, seeds = [STATS_SEED], bump = stats.bump)]
+    pub stats: Option<Account<'info, ProtocolStats>>,
+
+    /// The vault's summary PDA, which may not exist
+    /// CHECK: Derived address; deserialized only when initialized
+    #[account(mut, seeds = [VAULT_SUMMARY_SEED, vault.key().as_ref()], bump)]
+    pub vault_summary: UncheckedAccount<'info>,
+
+    /// The outflow circuit breaker PDA, which may not exist
+    /// CHECK: Derived address; deserialized only when initialized
+    #[account(mut, seeds = [OUTFLOW_BREAKER_SEED], bump)]
+    pub outflow_breaker: UncheckedAccount<'info>,
+
+    /// The security log PDA, which may not exist
+    /// CHECK: Derived address; deserialized only when initialized
+    #[account(mut, seeds = [SECURITY_LOG_SEED], bump)]
+    pub security_log: UncheckedAccount<'info>,
+
+    /// The bundle being withdrawn
+    #[account(
+        mut,
+        seeds = [BUNDLE_SEED, vault.key().as_ref(), &[bundle.id]],
+        bump = bundle.bump,
+        has_one = vault
+    )]
+    pub bundle: Account<'info, Bundle>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub token_2022_program: Program<'info, Token2022>,
+
+    /// The SNS Name Service program
+    /// CHECK: This is the official SNS Name Service program
+    #[account(address = NAME_SERVICE_PROGRAM_ID)]
+    pub name_service_program: UncheckedAccount<'info>,
+
+    /// The vault's withdrawal phrase PDA, which may not exist
+    /// CHECK: Derived address; deserialized only when initialized
+    #[account(seeds = [WITHDRAWAL_PHRASE_SEED, vault.key().as_ref()], bump)]
+    pub withdrawal_phrase: UncheckedAccount<'info>,
+
+    /// The vault's co-signer, required when the vault has one
+    pub co_signer: Option<Signer<'info>>,
+}
+
+/// Create a vault's summary account
+#[derive(Accounts)]
+pub struct InitVaultSummary<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The vault summary PDA
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + VaultSummary::INIT_SPACE,
+        seeds = [VAULT_SUMMARY_SEED, vault.key().as_ref()],
+        bump
+    )]
+    pub vault_summary: Account<'info, VaultSummary>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create or replace a vault's backup blob
+#[derive(Accounts)]
+pub struct SetBackupBlob<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The backup blob PDA
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + BackupBlob::INIT_SPACE,
+        seeds = [BACKUP_BLOB_SEED, vault.key().as_ref()],
+        bump
+    )]
+    pub backup_blob: Account<'info, BackupBlob>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Delete a vault's backup blob
+#[derive(Accounts)]
+pub struct CloseBackupBlob<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The backup blob PDA to close
+    #[account(
+        mut,
+        seeds = [BACKUP_BLOB_SEED, vault.key().as_ref()],
+        bump = backup_blob.bump,
+        close = owner
+    )]
+    pub backup_blob: Account<'info, BackupBlob>,
+}
+
+/// Create or replace a vault's notification endpoints
+#[derive(Accounts)]
+pub struct SetNotificationEndpoints<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The notification endpoints PDA
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + NotificationEndpoints::INIT_SPACE,
+        seeds = [NOTIFICATION_ENDPOINTS_SEED, vault.key().as_ref()],
+        bump
+    )]
+    pub notification_endpoints: Account<'info, NotificationEndpoints>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Delete a vault's notification endpoints
+#[derive(Accounts)]
+pub struct CloseNotificationEndpoints<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The notification endpoints PDA to close
+    #[account(
+        mut,
+        seeds = [NOTIFICATION_ENDPOINTS_SEED, vault.key().as_ref()],
+        bump = notification_endpoints.bump,
+        close = owner
+    )]
+    pub notification_endpoints: Account<'info, NotificationEndpoints>,
+}
+
+/// Create or replace a vault's voting delegation
+#[derive(Accounts)]
+pub struct SetVotingDelegate<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The voting delegation PDA
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + VotingDelegation::INIT_SPACE,
+        seeds = [VOTING_DELEGATION_SEED, vault.key().as_ref()],
+        bump
+    )]
+    pub voting_delegation: Account<'info, VotingDelegation>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Revoke a vault's voting delegation
+#[derive(Accounts)]
+pub struct RevokeVotingDelegate<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The voting delegation PDA to close
+    #[account(
+        mut,
+        seeds = [VOTING_DELEGATION_SEED, vault.key().as_ref()],
+        bump = voting_delegation.bump,
+        close = owner
+    )]
+    pub voting_delegation: Account<'info, VotingDelegation>,
+}
+
+/// Queue a change of a vault's co-signer
+#[derive(Accounts)]
+pub struct QueueCoSignerChange<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The pending change PDA; one change may be pending at a time
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + CoSignerChange::INIT_SPACE,
+        seeds = [CO_SIGNER_CHANGE_SEED, vault.key().as_ref()],
+        bump
+    )]
+    pub co_signer_change: Account<'info, CoSignerChange>,
+
+    /// The security log PDA, which may not exist
+    /// CHECK: Derived address; deserialized only when initialized
+    #[account(mut, seeds = [SECURITY_LOG_SEED], bump)]
+    pub security_log: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Apply a queued co-signer change once due
+#[derive(Accounts)]
+pub struct ApplyCoSignerChange<'info> {
+    /// The vault whose co-signer changes
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault.owner.as_ref()],
+        bump = vault.bump,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The pending change, closed to the owner once applied
+    #[account(
+        mut,
+        seeds = [CO_SIGNER_CHANGE_SEED, vault.key().as_ref()],
+        bump = co_signer_change.bump,
+        close = owner
+    )]
+    pub co_signer_change: Account<'info, CoSignerChange>,
+
+    /// The vault owner receiving the rent refund
+    /// CHECK: Address is the vault's owner
+    #[account(mut, address = vault.owner)]
+    pub owner: UncheckedAccount<'info>,
+
+    /// The keeper bounty schedule; no bounty is paid without it
+    #[account(seeds = [KEEPER_BOUNTIES_SEED], bump = keeper_bounties.bump)]
+    pub keeper_bounties: Option<Account<'info, KeeperBounties>>,
+
+    /// The keeper running the crank, paid its bounty
+    #[account(mut)]
+    pub keeper: Option<Signer<'info>>,
+}
+
+/// Cancel a queued co-signer change
+#[derive(Accounts)]
+pub struct CancelCoSignerChange<'info> {
+    /// The vault owner or its current co-signer
+    #[account(
+        constraint = authority.key() == vault.owner || authority.key() == vault.co_signer
+            @ VaultError::UnauthorizedAccess
+    )]
+    pub authority: Signer<'info>,
+
+    /// The vault whose pending change is cancelled
+    #[account(
+        seeds = [VAULT_SEED, vault.owner.as_ref()],
+        bump = vault.bump,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The pending change, closed to the owner
+    #[account(
+        mut,
+        seeds = [CO_SIGNER_CHANGE_SEED, vault.key().as_ref()],
+        bump = co_signer_change.bump,
+        close = owner
+    )]
+    pub co_signer_change: Account<'info, CoSignerChange>,
+
+    /// The vault owner receiving the rent refund
+    /// CHECK: Address is the vault's owner
+    #[account(mut, address = vault.owner)]
+    pub owner: UncheckedAccount<'info>,
+}
+
+/// Set or replace a vault's withdrawal confirmation phrase
+#[derive(Accounts)]
+pub struct SetWithdrawalPhrase<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The withdrawal phrase PDA
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + WithdrawalPhrase::INIT_SPACE,
+        seeds = [WITHDRAWAL_PHRASE_SEED, vault.key().as_ref()],
+        bump
+    )]
+    pub withdrawal_phrase: Account<'info, WithdrawalPhrase>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Remove a vault's withdrawal confirmation phrase
+#[derive(Accounts)]
+pub struct RemoveWithdrawalPhrase<'info> {
+    /// The vault owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The user's vault
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The withdrawal phrase PDA to close
+    #[account(
+        mut,
+        seeds = [WITHDRAWAL_PHRASE_SEED, vault.key().as_ref()],
+        bump = withdrawal_phrase.bump,
+        close = owner
+    )]
+    pub withdrawal_phrase: Account<'info, WithdrawalPhrase>,
+}
+
+/// Set the content record (and optional gateway hint) of a vaulted domain
+#[derive(Accounts)]
+pub struct SetContentRecord<'info> {
+    /// The vault owner (pays fees and rent)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The vault owning the domain
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The global protocol config
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The protocol fee treasury
+    #[account(mut, seeds = [TREASURY_SEED], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    /// The domain's name registry account
+    /// CHECK: Matched against the entry by check_entry_name_account
+    #[account(mut, owner = NAME_SERVICE_PROGRAM_ID)]
+    pub name_account: UncheckedAccount<'info>,
+
+    /// Entry of the vaulted domain
+    #[account(
+        seeds = [ENTRY_SEED, vault.key().as_ref(), name_account.key().as_ref()],
+        bump = domain_entry.bump,
+        has_one = vault @ VaultError::DomainNotInVault
+    )]
+    pub domain_entry: Account<'info, DomainEntry>,
+
+    /// The IPFS or ARWV Record V2 account
+    /// CHECK: Address is checked against the content record's derivation
+    #[account(mut)]
+    pub record: UncheckedAccount<'info>,
+
+    /// The TXT Record V2 account; required with a gateway hint
+    /// CHECK: Address is checked against the TXT record's derivation
+    #[account(mut)]
+    pub txt_record: Option<UncheckedAccount<'info>>,
+
+    /// The central state account for SNS Records V2
+    /// CHECK: Address is checked against the SNS Records V2 central state derivation
+    #[account(
+        seeds = [sns_records_program.key().as_ref()],
+        bump,
+        seeds::program = sns_records_program.key()
+    )]
+    pub central_state: UncheckedAccount<'info>,
+
+    /// The SNS Name Service program
+    /// CHECK: This is the official SNS Name Service program
+    #[account(address = NAME_SERVICE_PROGRAM_ID)]
+    pub name_service_program: UncheckedAccount<'info>,
+
+    /// The SNS Records V2 program
+    /// CHECK: This is the official SNS Records V2 program
+    #[account(address = SNS_RECORDS_PROGRAM_ID)]
+    pub sns_records_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Post a social record of a vaulted domain for verification
+#[derive(Accounts)]
+pub struct PostSocialRecord<'info> {
+    /// The vault owner (pays fees and rent)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The vault owning the domain
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
+    )]
+    pub vault: Account<'info, UserVault>,
+
+    /// The global protocol config
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The protocol fee treasury
+    #[account(mut, seeds = [TREASURY_SEED], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    /// The domain's name registry account
+    /// CHECK: Matched against the entry by check_entry_name_account
+    #[account(mut, owner = NAME_SERVICE_PROGRAM_ID)]
+    pub name_account: UncheckedAccount<'info>,
+
+    /// Entry of the vaulted domain
+    #[account(
+        seeds = [ENTRY_SEED, vault.key().as_ref(), name_account.key().as_ref()],
+        bump = domain_entry.bump,
+        has_one = vault @ VaultError::DomainNotInVault
+    )]
+    pub domain_entry: Account<'info, DomainEntry>,
+
+    /// The platform's Record V2 account
+    /// CHECK: Address is checked against the platform's record derivation
+    #[account(mut)]
+    pub record: UncheckedAccount<'info>,
+
+    /// The record's verification status PDA
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + SocialVerification::INIT_SPACE,
+        seeds = [SOCIAL_VERIFICATION_SEED, domain_entry.key().as_ref(), record.key().as_ref()],
+        bump
+    )]
+    pub social_verification: Account<'info, SocialVerification>,
+
+    /// The central state account for SNS Records V2
+    /// CHECK: Address is checked against the SNS Records V2 central state derivation
+    #[account(
+        seeds = [sns_records_program.key().as_ref()],
+        bump,
+        seeds::program = sns_records_program.key()
+    )]
+    pub central_state: UncheckedAccount<'info>,
+
+    /// The SNS Name Service program
+    /// CHECK: This is the official SNS Name Service program
+    #[account(address = NAME_SERVICE_PROGRAM_ID)]
+    pub name_service_program: UncheckedAccount<'info>,
+
+    /// The SNS Records V2 program
+    /// CHECK: This is the official SNS Records V2 program
+    #[account(address = SNS_RECORDS_PROGRAM_ID)]
+    pub sns_records_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Finalize the external verification of a social record
+#[derive(Accounts)]
+pub struct FinalizeSocialVerification<'info> {
+    /// The config admin, acting as verification oracle
+    pub admin: Signer<'info>,
+
+    /// The global config PDA
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ VaultError::UnauthorizedAccess
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// Entry of the vaulted domain
+    pub domain_entry: Account<'info, DomainEntry>,
+
+    /// The record's verification status PDA
+    #[account(
+        mut,
+        seeds = [
+            SOCIAL_VERIFICATION_SEED,
+            domain_entry.key().as_ref(),
+            social_verification.record.as_ref()
+        ],
+        bump = social_verification.bump,
+        has_one = domain_entry
+    )]
+    pub social_verification: Account<'info, SocialVerification>,
+}
+
+/// Post the valuation table's floor prices
+#[derive(Accounts)]
+pub struct SetValuationFloors<'info> {
+    /// The current config admin
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// The global config PDA
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ VaultError::UnauthorizedAccess
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The valuation table PDA
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + ValuationTable::INIT_SPACE,
+        seeds = [VALUATION_TABLE_SEED],
+        bump
+    )]
+    pub valuation_table: Account<'info, ValuationTable>,
+
+    /// The queued change to apply, required while a timelock is set
+    #[account(
+        mut,
+        seeds = [PARAM_CHANGE_SEED, queued_change.id.to_le_bytes().as_ref()],
+        bump = queued_change.bump
+    )]
+    pub queued_change: Option<Account<'info, QueuedParamChange>>,
+
+    /// The queued change's payer receiving the rent refund
+    /// CHECK: Checked against queued_change.payer in the handler
+    #[account(mut)]
+    pub payer: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Quote a vaulted domain's floor price
+#[derive(Accounts)]
+pub struct QuoteFloorPrice<'info> {
+    /// The valuation table PDA
+    #[account(seeds = [VALUATION_TABLE_SEED], bump = valuation_table.bump)]
+    pub valuation_table: Account<'info, ValuationTable>,
+
+    /// Entry of the vaulted domain
+    pub domain_entry: Account<'info, DomainEntry>,
+}
+
+/// Create the global security log
+#[derive(Accounts)]
+pub struct InitializeSecurityLog<'info> {
+    /// The current config admin
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// The global config PDA
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ VaultError::UnauthorizedAccess
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The security log PDA
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + SecurityLog::INIT_SPACE,
+        seeds = [SECURITY_LOG_SEED],
+        bump
+    )]
+    pub security_log: Account<'info, SecurityLog>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Set a keeper bounty
+#[derive(Accounts)]
+pub struct SetKeeperBounty<'info> {
+    /// The current config admin
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// The global config PDA
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ VaultError::UnauthorizedAccess
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The keeper bounty schedule PDA
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + KeeperBounties::INIT_SPACE,
+        seeds = [KEEPER_BOUNTIES_SEED],
+        bump
+    )]
+    pub keeper_bounties: Account<'info, KeeperBounties>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Configure the outflow circuit breaker
+#[derive(Accounts)]
+pub struct ConfigureOutflowBreaker<'info> {
+    /// The current config admin
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// The global config PDA
     #[account(
-        mut,
-        associated_token::mint = domain_mint,
-        associated_token::authority = owner,
-        associated_token::token_program = token_program,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ VaultError::UnauthorizedAccess
     )]
-    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub config: Account<'info, ProtocolConfig>,
 
-    /// Vault's token account to receive the SNS domain
-    /// We use init_if_needed because the vault may not have a token account for this specific domain yet
+    /// The outflow breaker PDA
     #[account(
         init_if_needed,
-        payer = owner,
-        associated_token::mint = domain_mint,
-        associated_token::authority = vault,
-        associated_token::token_program = token_program,
+        payer = admin,
+        space = 8 + OutflowBreaker::INIT_SPACE,
+        seeds = [OUTFLOW_BREAKER_SEED],
+        bump
     )]
-    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub outflow_breaker: Account<'info, OutflowBreaker>,
 
-    pub token_program: Interface<'info, TokenInterface>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
+/// Reset a tripped outflow circuit breaker
 #[derive(Accounts)]
-pub struct WithdrawDomain<'info> {
-    /// The owner withdrawing a domain
+pub struct ResetOutflowBreaker<'info> {
+    /// The current config admin
+    pub admin: Signer<'info>,
+
+    /// The global config PDA
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ VaultError::UnauthorizedAccess
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The outflow breaker PDA
+    #[account(mut, seeds = [OUTFLOW_BREAKER_SEED], bump = outflow_breaker.bump)]
+    pub outflow_breaker: Account<'info, OutflowBreaker>,
+
+    /// The security log PDA, which may not exist
+    /// CHECK: Derived address; deserialized only when initialized
+    #[account(mut, seeds = [SECURITY_LOG_SEED], bump)]
+    pub security_log: UncheckedAccount<'info>,
+}
+
+/// Schedule a recurring crank on a vault
+#[derive(Accounts)]
+#[instruction(kind: ScheduledOperationKind)]
+pub struct ScheduleOperation<'info> {
+    /// The vault owner
     #[account(mut)]
     pub owner: Signer<'info>,
 
-    /// The user's vault (also acts as authority for vault token accounts)
+    /// The user's vault
     #[account(
-        mut,
         seeds = [VAULT_SEED, owner.key().as_ref()],
         bump = vault.bump,
-        has_one = owner @ VaultError::UnauthorizedAccess
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
     )]
     pub vault: Account<'info, UserVault>,
 
-    /// The SNS domain mint (NFT)
-    pub domain_mint: InterfaceAccount<'info, Mint>,
-
-    /// Vault's token account holding the SNS domain
-    #[account(
-        mut,
-        associated_token::mint = domain_mint,
-        associated_token::authority = vault,
-        associated_token::token_program = token_program,
-    )]
-    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
-
-    /// User's token account to receive the SNS domain
-    /// We use init_if_needed in case user closed their token account
+    /// The scheduled operation PDA
     #[account(
         init_if_needed,
         payer = owner,
-        associated_token::mint = domain_mint,
-        associated_token::authority = owner,
-        associated_token::token_program = token_program,
+        space = 8 + ScheduledOperation::INIT_SPACE,
+        seeds = [SCHEDULE_SEED, vault.key().as_ref(), &[kind as u8]],
+        bump
     )]
-    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub scheduled_operation: Account<'info, ScheduledOperation>,
 
-    pub token_program: Interface<'info, TokenInterface>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
+/// Cancel a scheduled operation
 #[derive(Accounts)]
-pub struct InitVaultTokenAccount<'info> {
-    /// The owner of the vault (payer for the ATA creation)
+pub struct CancelScheduledOperation<'info> {
+    /// The vault owner
     #[account(mut)]
     pub owner: Signer<'info>,
 
@@ -655,47 +10470,151 @@ pub struct InitVaultTokenAccount<'info> {
     #[account(
         seeds = [VAULT_SEED, owner.key().as_ref()],
         bump = vault.bump,
-        has_one = owner @ VaultError::UnauthorizedAccess
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
     )]
     pub vault: Account<'info, UserVault>,
 
-    /// The token mint for which to create an ATA
-    pub token_mint: InterfaceAccount<'info, Mint>,
+    /// The scheduled operation PDA to close
+    #[account(
+        mut,
+        seeds = [
+            SCHEDULE_SEED,
+            vault.key().as_ref(),
+            &[scheduled_operation.kind as u8]
+        ],
+        bump = scheduled_operation.bump,
+        has_one = vault,
+        close = owner
+    )]
+    pub scheduled_operation: Account<'info, ScheduledOperation>,
+}
 
-    /// The vault's token account (ATA) to be initialized
+/// Add bounty lamports to a scheduled operation
+#[derive(Accounts)]
+pub struct FundScheduledOperation<'info> {
+    /// Whoever funds the schedule, usually its owner
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    /// The scheduled operation PDA
+    #[account(
+        mut,
+        seeds = [
+            SCHEDULE_SEED,
+            scheduled_operation.vault.as_ref(),
+            &[scheduled_operation.kind as u8]
+        ],
+        bump = scheduled_operation.bump
+    )]
+    pub scheduled_operation: Account<'info, ScheduledOperation>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Record a run of a due scheduled operation
+#[derive(Accounts)]
+pub struct RunScheduledOperation<'info> {
+    /// The scheduled operation PDA
+    #[account(
+        mut,
+        seeds = [
+            SCHEDULE_SEED,
+            scheduled_operation.vault.as_ref(),
+            &[scheduled_operation.kind as u8]
+        ],
+        bump = scheduled_operation.bump
+    )]
+    pub scheduled_operation: Account<'info, ScheduledOperation>,
+
+    /// The instructions sysvar, used to find the scheduled crank
+    /// CHECK: Address is the instructions sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    /// The keeper bounty schedule; no bounty is paid without it
+    #[account(seeds = [KEEPER_BOUNTIES_SEED], bump = keeper_bounties.bump)]
+    pub keeper_bounties: Option<Account<'info, KeeperBounties>>,
+
+    /// The keeper running the crank, paid its bounty
+    #[account(mut)]
+    pub keeper: Option<Signer<'info>>,
+}
+
+/// Commit a custody snapshot root
+#[derive(Accounts)]
+#[instruction(slot: u64)]
+pub struct CommitCustodySnapshot<'info> {
+    /// The current config admin
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// The global config PDA
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ VaultError::UnauthorizedAccess
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The snapshot PDA for this slot; a slot's root can't be replaced once committed
     #[account(
         init,
-        payer = owner,
-        associated_token::mint = token_mint,
-        associated_token::authority = vault,
-        associated_token::token_program = token_program,
+        payer = admin,
+        space = 8 + CustodySnapshot::INIT_SPACE,
+        seeds = [CUSTODY_SNAPSHOT_SEED, &slot.to_le_bytes()],
+        bump
     )]
-    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub custody_snapshot: Account<'info, CustodySnapshot>,
 
-    pub token_program: Interface<'info, TokenInterface>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
+/// Issue a burner subdomain under a vaulted domain
 #[derive(Accounts)]
-pub struct DepositUnwrappedDomain<'info> {
-    /// The owner depositing a domain
+pub struct IssueBurnerSubdomain<'info> {
+    /// The vault owner, paying rent for both accounts
     #[account(mut)]
     pub owner: Signer<'info>,
 
     /// The user's vault
     #[account(
-        mut,
         seeds = [VAULT_SEED, owner.key().as_ref()],
         bump = vault.bump,
-        has_one = owner @ VaultError::UnauthorizedAccess
+        has_one = owner @ VaultError::UnauthorizedAccess,
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
     )]
     pub vault: Account<'info, UserVault>,
 
-    /// The SNS name account (domain registry)
-    /// CHECK: This account is validated by the Name Service program
+    /// The global protocol config
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The parent domain's name account
+    /// CHECK: Bound to the vault by parent_entry; validated by the Name Service program
+    pub parent_name_account: UncheckedAccount<'info>,
+
+    /// The entry proving the vault holds the parent
+    #[account(
+        seeds = [ENTRY_SEED, vault.key().as_ref(), parent_name_account.key().as_ref()],
+        bump = parent_entry.bump
+    )]
+    pub parent_entry: Account<'info, DomainEntry>,
+
+    /// The subdomain name account to create
+    /// CHECK: Address is checked against the label's derivation; created by the Name Service
     #[account(mut)]
-    pub name_account: UncheckedAccount<'info>,
+    pub subdomain_account: UncheckedAccount<'info>,
+
+    /// The burner record, deciding when the subdomain can be revoked
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + BurnerSubdomain::INIT_SPACE,
+        seeds = [BURNER_SUBDOMAIN_SEED, subdomain_account.key().as_ref()],
+        bump
+    )]
+    pub burner_subdomain: Account<'info, BurnerSubdomain>,
 
     /// The SNS Name Service program
     /// CHECK: This is the official SNS Name Service program
@@ -705,81 +10624,590 @@ pub struct DepositUnwrappedDomain<'info> {
     pub system_program: Program<'info, System>,
 }
 
+/// Revoke a burner subdomain
 #[derive(Accounts)]
-pub struct WithdrawUnwrappedDomain<'info> {
-    /// The owner withdrawing a domain
-    #[account(mut)]
-    pub owner: Signer<'info>,
+pub struct RevokeBurnerSubdomain<'info> {
+    /// The vault owner, or anyone once the burner expired
+    pub authority: Signer<'info>,
 
-    /// The user's vault (current owner of the domain)
+    /// The vault that issued the burner
     #[account(
-        mut,
-        seeds = [VAULT_SEED, owner.key().as_ref()],
+        seeds = [VAULT_SEED, vault.owner.as_ref()],
         bump = vault.bump,
-        has_one = owner @ VaultError::UnauthorizedAccess
+        constraint = !vault.in_flight @ VaultError::VaultInFlight
     )]
     pub vault: Account<'info, UserVault>,
 
-    /// The SNS name account (domain registry)
-    /// CHECK: This account is validated by the Name Service program
-    #[account(mut)]
-    pub name_account: UncheckedAccount<'info>,
+    /// The vault owner, refunded the rent of both accounts
+    /// CHECK: Address is the vault owner
+    #[account(mut, address = vault.owner)]
+    pub owner: UncheckedAccount<'info>,
+
+    /// The global protocol config
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The subdomain name account to delete
+    /// CHECK: Address is the burner's subdomain
+    #[account(mut, address = burner_subdomain.subdomain)]
+    pub subdomain_account: UncheckedAccount<'info>,
+
+    /// The burner record, closed with the subdomain
+    #[account(
+        mut,
+        seeds = [BURNER_SUBDOMAIN_SEED, subdomain_account.key().as_ref()],
+        bump = burner_subdomain.bump,
+        has_one = vault,
+        close = owner
+    )]
+    pub burner_subdomain: Account<'info, BurnerSubdomain>,
 
     /// The SNS Name Service program
     /// CHECK: This is the official SNS Name Service program
     #[account(address = NAME_SERVICE_PROGRAM_ID)]
     pub name_service_program: UncheckedAccount<'info>,
+}
+
+/// Publish the program version and features
+#[derive(Accounts)]
+pub struct SyncProgramInfo<'info> {
+    /// Anyone paying for the account on first sync
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The program info PDA
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + ProgramInfo::INIT_SPACE,
+        seeds = [PROGRAM_INFO_SEED],
+        bump
+    )]
+    pub program_info: Account<'info, ProgramInfo>,
 
     pub system_program: Program<'info, System>,
 }
 
-/// Deposit an unwrapped domain and update SOL record to point to vault PDA
+/// Create the protocol fee treasury
 #[derive(Accounts)]
-pub struct DepositDomainWithRecord<'info> {
-    /// The owner depositing a domain
+pub struct InitializeTreasury<'info> {
+    /// The current config admin
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub admin: Signer<'info>,
 
-    /// The user's vault
+    /// The global config PDA
     #[account(
-        mut,
-        seeds = [VAULT_SEED, owner.key().as_ref()],
-        bump = vault.bump,
-        has_one = owner @ VaultError::UnauthorizedAccess
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ VaultError::UnauthorizedAccess
     )]
-    pub vault: Account<'info, UserVault>,
+    pub config: Account<'info, ProtocolConfig>,
 
-    /// The SNS name account (domain registry)
-    /// CHECK: This account is validated by the Name Service program
+    /// The treasury PDA
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Treasury::INIT_SPACE,
+        seeds = [TREASURY_SEED],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the global protocol stats account
+#[derive(Accounts)]
+pub struct InitializeStats<'info> {
+    /// The current config admin
     #[account(mut)]
-    pub name_account: UncheckedAccount<'info>,
+    pub admin: Signer<'info>,
 
-    /// The SOL record V2 account (will be created/updated)
-    /// CHECK: This account is derived and validated by the SNS Records V2 program
+    /// The global config PDA
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ VaultError::UnauthorizedAccess
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The protocol stats PDA
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + ProtocolStats::INIT_SPACE,
+        seeds = [STATS_SEED],
+        bump
+    )]
+    pub stats: Account<'info, ProtocolStats>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Withdraw collected fees from the treasury
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    /// The current config admin
+    pub admin: Signer<'info>,
+
+    /// The global config PDA
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ VaultError::UnauthorizedAccess
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The treasury PDA
+    #[account(mut, seeds = [TREASURY_SEED], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Account receiving the withdrawn lamports
+    /// CHECK: Any account chosen by the admin
     #[account(mut)]
-    pub sol_record_v2: UncheckedAccount<'info>,
+    pub destination: UncheckedAccount<'info>,
+}
 
-    /// The central state account for SNS Records V2
-    /// CHECK: This is the SNS Records V2 central state PDA
-    pub central_state: UncheckedAccount<'info>,
+/// Queue a timelocked parameter change
+#[derive(Accounts)]
+pub struct QueueParamChange<'info> {
+    /// The current config admin
+    #[account(mut)]
+    pub admin: Signer<'info>,
 
-    /// The SNS Name Service program
-    /// CHECK: This is the official SNS Name Service program
-    #[account(address = NAME_SERVICE_PROGRAM_ID)]
-    pub name_service_program: UncheckedAccount<'info>,
+    /// The global config PDA
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ VaultError::UnauthorizedAccess
+    )]
+    pub config: Account<'info, ProtocolConfig>,
 
-    /// The SNS Records V2 program
-    /// CHECK: This is the official SNS Records V2 program
-    #[account(address = SNS_RECORDS_PROGRAM_ID)]
-    pub sns_records_program: UncheckedAccount<'info>,
+    /// The queued change PDA, keyed by the config's next change id
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + QueuedParamChange::INIT_SPACE,
+        seeds = [PARAM_CHANGE_SEED, config.next_param_change_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub queued_change: Account<'info, QueuedParamChange>,
 
     pub system_program: Program<'info, System>,
 }
 
+/// Execute a queued parameter change once its ETA has passed
+#[derive(Accounts)]
+pub struct ExecuteParamChange<'info> {
+    /// The global config PDA
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The queued change, closed after execution
+    #[account(
+        mut,
+        seeds = [PARAM_CHANGE_SEED, queued_change.id.to_le_bytes().as_ref()],
+        bump = queued_change.bump,
+        has_one = payer,
+        close = payer
+    )]
+    pub queued_change: Account<'info, QueuedParamChange>,
+
+    /// The original payer receiving the rent refund
+    /// CHECK: Checked against queued_change.payer
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+}
+
+/// Cancel a queued parameter change
+#[derive(Accounts)]
+pub struct CancelParamChange<'info> {
+    /// The current config admin
+    pub admin: Signer<'info>,
+
+    /// The global config PDA
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ VaultError::UnauthorizedAccess
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// The queued change, closed on cancellation
+    #[account(
+        mut,
+        seeds = [PARAM_CHANGE_SEED, queued_change.id.to_le_bytes().as_ref()],
+        bump = queued_change.bump,
+        has_one = payer,
+        close = payer
+    )]
+    pub queued_change: Account<'info, QueuedParamChange>,
+
+    /// The original payer receiving the rent refund
+    /// CHECK: Checked against queued_change.payer
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+}
+
+#[event]
+pub struct ParamChangeQueued {
+    pub id: u64,
+    pub change: ParamChange,
+    pub eta: i64,
+}
+
+#[event]
+pub struct ParamChangeExecuted {
+    pub id: u64,
+    pub change: ParamChange,
+}
+
+#[event]
+pub struct ParamChangeCancelled {
+    pub id: u64,
+    pub change: ParamChange,
+}
+
+#[event]
+pub struct DomainPaid {
+    pub endpoint: Pubkey,
+    pub domain: Pubkey,
+    pub receipt_id: u64,
+    pub payer: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub valid_until: i64,
+    pub memo_hash: [u8; 32],
+}
+
+/// Emitted when a domain enters a vault; `memo_hash` binds an owner's off-chain reference
+#[event]
+pub struct DomainDeposited {
+    pub vault: Pubkey,
+    pub domain: Pubkey,
+    pub kind: DomainKind,
+    pub memo_hash: Option<[u8; 32]>,
+}
+
+/// Emitted when a domain leaves a vault; `memo_hash` binds an owner's off-chain reference
+/// and `confirmation_phrase` lets monitors alert on withdrawals lacking the owner's phrase
+#[event]
+pub struct DomainWithdrawn {
+    pub vault: Pubkey,
+    pub domain: Pubkey,
+    pub kind: DomainKind,
+    pub memo_hash: Option<[u8; 32]>,
+    pub confirmation_phrase: Option<String>,
+}
+
+/// Emitted when a social record is posted for verification or its verification is finalized
+#[event]
+pub struct SocialVerificationChanged {
+    pub domain: Pubkey,
+    pub platform: SocialPlatform,
+    pub status: VerificationStatus,
+}
+
+/// Emitted when abnormal outflow trips the circuit breaker and halts withdrawals
+#[event]
+pub struct OutflowBreakerTripped {
+    pub window_outflow: u64,
+    pub threshold: u64,
+}
+
+/// Authority roles whose changes emit `AuthorityChanged`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuthorityRole {
+    ProtocolAdmin,
+    VaultCoSigner,
+}
+
+/// How an authority change came about
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuthorityChangeMethod {
+    /// First authority, set when the account is created
+    Initialization,
+    /// Nominated by the current authority and accepted by the new one
+    Transfer,
+    /// Queued by the owner and applied after a fixed delay
+    TimelockedChange,
+}
+
+/// Emitted on every authority change, for monitors to treat uniformly as high-sensitivity
+#[event]
+pub struct AuthorityChanged {
+    /// Account whose authority changed (config or vault)
+    pub subject: Pubkey,
+    pub role: AuthorityRole,
+    pub method: AuthorityChangeMethod,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+    /// When the change was nominated or queued
+    pub initiated_at: i64,
+    /// When the change took effect
+    pub effective_at: i64,
+}
+
+/// Emitted when the admin attests a custody snapshot root; consumers trust the admin for it
+#[event]
+pub struct CustodySnapshotCommitted {
+    pub slot: u64,
+    pub root: [u8; 32],
+    pub leaf_count: u64,
+}
+
+/// Emitted when a vault's voting delegate is set, replaced or revoked (default key)
+#[event]
+pub struct VotingDelegateChanged {
+    pub vault: Pubkey,
+    pub previous_delegate: Pubkey,
+    pub delegate: Pubkey,
+}
+
+/// Account types `admin_migrate_account` can migrate (layouts that only ever appended fields)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MigratableAccount {
+    UserVault,
+    DomainEntry,
+}
+
+/// Emitted for every account grown by `admin_migrate_account`
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub account_type: MigratableAccount,
+    pub layout_version: u8,
+    pub old_len: u32,
+    pub new_len: u32,
+}
+
+/// SNS CPI step reported by `SnsCpiFailed`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SnsCpiStep {
+    Transfer,
+    Allocate,
+    EditRecord,
+    WriteRoa,
+    Validate,
+    Create,
+    Delete,
+}
+
+/// Emitted (and set as return data) when a CPI into an SNS program returns an error
+#[event]
+pub struct SnsCpiFailed {
+    pub step: SnsCpiStep,
+    pub program: Pubkey,
+    pub account: Pubkey,
+}
+
 #[error_code]
 pub enum VaultError {
     #[msg("You are not authorized to access this vault")]
     UnauthorizedAccess,
     #[msg("No domains in the vault")]
     NoDomains,
+    #[msg("Timelock must be between 0 and 30 days")]
+    InvalidTimelock,
+    #[msg("No matching admin nomination is pending")]
+    NoPendingAdmin,
+    #[msg("Timelock has not elapsed yet")]
+    TimelockNotElapsed,
+    #[msg("This parameter change must go through the timelock")]
+    ParamChangeTimelocked,
+    #[msg("The protocol is paused")]
+    ProtocolPaused,
+    #[msg("Invalid SPL Governance realm or governance account")]
+    InvalidGovernanceAccount,
+    #[msg("This mint is denylisted and can't be deposited")]
+    MintDenied,
+    #[msg("This mint is not on the deposit allowlist")]
+    MintNotAllowed,
+    #[msg("Fee exceeds the maximum of 1 SOL")]
+    FeeTooHigh,
+    #[msg("Treasury balance is too low for this withdrawal")]
+    InsufficientTreasuryBalance,
+    #[msg("The domain is not held by this vault")]
+    DomainNotInVault,
+    #[msg("The domain is staked")]
+    DomainStaked,
+    #[msg("The domain is not staked")]
+    DomainNotStaked,
+    #[msg("Unstaking has already been requested")]
+    UnstakeAlreadyRequested,
+    #[msg("The unstake cooldown has not elapsed yet")]
+    UnstakeCooldownActive,
+    #[msg("Unstake cooldown must be between 0 and 30 days")]
+    InvalidCooldown,
+    #[msg("No rewards to claim")]
+    NoRewards,
+    #[msg("The reward pool can't cover this claim")]
+    RewardPoolDepleted,
+    #[msg("This program is not on the claim allowlist")]
+    ClaimProgramNotAllowed,
+    #[msg("The claim program allowlist is full")]
+    TooManyClaimPrograms,
+    #[msg("The call reduced assets held by the vault")]
+    VaultAssetsDecreased,
+    #[msg("Token accounts of vaulted domains can't be quarantined")]
+    CannotQuarantineDomain,
+    #[msg("The token account is empty")]
+    NothingToBurn,
+    #[msg("Sweep thresholds must be greater than zero")]
+    InvalidSweepThreshold,
+    #[msg("A sweep policy can list at most 8 mints")]
+    TooManySweepMints,
+    #[msg("This token account can't be swept")]
+    MintNotSweepable,
+    #[msg("Balance is below the sweep threshold")]
+    SweepThresholdNotMet,
+    #[msg("Destination token account doesn't belong to the sweep destination")]
+    InvalidSweepDestination,
+    #[msg("Sweep destinations must be 1 to 4 non-zero weights summing to 10000 bps")]
+    InvalidSweepDestinations,
+    #[msg("Payment amount must be greater than zero")]
+    InvalidPaymentAmount,
+    #[msg("Payment period can't be negative")]
+    InvalidPaymentPeriod,
+    #[msg("Memo is too long")]
+    MemoTooLong,
+    #[msg("Payment mint doesn't match the endpoint")]
+    InvalidPaymentMint,
+    #[msg("Record accounts must be the domain's Record V2 accounts in ascending key order")]
+    InvalidRecordAccount,
+    #[msg("No record snapshot has been taken for this domain")]
+    NoRecordSnapshot,
+    #[msg("Records have changed since the snapshot")]
+    RecordsChanged,
+    #[msg("This domain's records are locked")]
+    RecordsLocked,
+    #[msg("Records can only be managed for unwrapped domains held by the vault")]
+    RecordsRequireUnwrappedDomain,
+    #[msg("A record template needs 1 to 8 records with names up to 32 bytes and content up to 128 bytes")]
+    InvalidRecordTemplate,
+    #[msg("Operator grant expiry must be 0 or in the future")]
+    InvalidOperatorExpiry,
+    #[msg("The record operator grant has expired")]
+    OperatorGrantExpired,
+    #[msg("The record session is already open")]
+    RecordSessionActive,
+    #[msg("No open record session")]
+    RecordSessionNotActive,
+    #[msg("The transaction doesn't end the record session")]
+    RecordSessionNotEnded,
+    #[msg("The account hasn't been expired for the full grace period")]
+    NotReapable,
+    #[msg("This operation must run in a transaction with no other programs")]
+    TransactionNotIsolated,
+    #[msg("The vault is in the middle of an external call")]
+    VaultInFlight,
+    #[msg("This program is not on the CPI allowlist")]
+    CpiProgramNotAllowed,
+    #[msg("The CPI allowlist is full")]
+    TooManyCpiPrograms,
+    #[msg("Health check accounts don't follow the expected layout")]
+    InvalidHealthCheckAccounts,
+    #[msg("This kind of domain isn't supported by this instruction")]
+    UnsupportedDomainKind,
+    #[msg("Batch withdrawal accounts don't follow the expected layout")]
+    InvalidEmergencyAccounts,
+    #[msg("Migration layout version doesn't match the program")]
+    LayoutVersionMismatch,
+    #[msg("Account is not of the type being migrated")]
+    InvalidMigrationTarget,
+    #[msg("Backup blob must be between 1 and 1024 bytes")]
+    InvalidBackupBlob,
+    #[msg("This vault's withdrawals must be co-signed by its co-signer")]
+    CoSignerRequired,
+    #[msg("The vault already has this co-signer")]
+    CoSignerUnchanged,
+    #[msg("Withdrawal confirmation phrase is missing or wrong")]
+    ConfirmationPhraseMismatch,
+    #[msg("Vault token account has a close authority the vault can't clear")]
+    ForeignCloseAuthority,
+    #[msg("Token account is frozen")]
+    FrozenTokenAccount,
+    #[msg("Token account has an active delegate; revoke the approval first")]
+    DelegatedTokenAccount,
+    #[msg("Vault token account is frozen by the mint's freeze authority; ask the tokenizer to thaw it")]
+    VaultTokenAccountFrozen,
+    #[msg("Record content is malformed or of the wrong type for this record")]
+    InvalidRecordContent,
+    #[msg("Record content exceeds the maximum size for its type")]
+    RecordContentTooLarge,
+    #[msg("Verification is not pending")]
+    VerificationNotPending,
+    #[msg("Bundle needs a name up to 32 bytes and up to 16 distinct domains of the vault")]
+    InvalidBundle,
+    #[msg("Breaker window and trip multiple must be positive")]
+    InvalidBreakerParams,
+    #[msg("Withdrawals are halted by the outflow circuit breaker")]
+    OutflowHalted,
+    #[msg("Notification endpoints need a webhook or push program and known alert bits")]
+    InvalidNotificationEndpoints,
+    #[msg("Schedule interval is below the minimum")]
+    InvalidScheduleInterval,
+    #[msg("Scheduled operation is not due yet")]
+    ScheduledOperationNotDue,
+    #[msg("Scheduled crank for this vault must follow in the same transaction")]
+    ScheduledCrankMissing,
+    #[msg("Token account has the wrong mint or owner")]
+    TokenAccountMismatch,
+    #[msg("Native SOL can't be deposited as a domain")]
+    NativeMintDeposit,
+    #[msg("Domain mints must have 0 decimals")]
+    DomainMintHasDecimals,
+    #[msg("Domain mints must have a supply of exactly 1")]
+    DomainSupplyNotOne,
+    #[msg("Domain mint has a mint authority that could inflate its supply")]
+    InflatableDomainMint,
+    #[msg("Token program doesn't match the one the domain was deposited under")]
+    WrongTokenProgram,
+    #[msg("Summary needs every domain entry of the vault exactly once")]
+    InvalidSummaryEntries,
+    #[msg("Custody snapshot slot, leaf count or samples are invalid")]
+    InvalidCustodySnapshot,
+    #[msg("Sampled entry is not in the custody snapshot")]
+    CustodyProofMismatch,
+    #[msg("Voting delegate can't be the default key")]
+    InvalidVotingDelegate,
+    #[msg("Burner subdomain label, lifetime or address is invalid")]
+    InvalidBurnerSubdomain,
+    #[msg("Only the vault owner can revoke a burner subdomain before it expires")]
+    BurnerNotExpired,
+    #[msg("Sweep denominations must be non-zero, strictly increasing, and the delay bounded")]
+    InvalidSweepBuckets,
+    #[msg("The next sweep bucket is not released yet")]
+    SweepBucketNotReleased,
+    #[msg("Staged record update slot gap is out of range")]
+    InvalidRecordStage,
+    #[msg("The staged record update's slot gap has not passed yet")]
+    RecordStageNotDue,
+    #[msg("Withdrawals are fee-free")]
+    WithdrawalsAreFeeFree,
+    #[msg("Not a .sol domain: the mint or name registry doesn't derive from the .sol TLD")]
+    NotSolDomain,
+    #[msg("A vault token account's delegate, state or close authority changed during the claim")]
+    VaultAssetAuthorityChanged,
+    #[msg("Sweep policy lists a mint more than once")]
+    InvalidSweepMints,
+    #[msg("Payment endpoint price or mint differs from what the payer expected")]
+    PaymentPriceChanged,
+    #[msg("Receipt's subscription period hasn't ended yet")]
+    ReceiptStillValid,
+    #[msg("A record session must allow between one and the maximum number of records")]
+    InvalidSessionRecords,
+    #[msg("Record isn't one the session was approved to write")]
+    RecordNotInSession,
+    #[msg("Parameter change ETA or id overflowed")]
+    ParamChangeOverflow,
+    #[msg("This parameter change is applied by its setter, not execute_param_change")]
+    ParamChangeAppliedBySetter,
+    #[msg("The queued parameter change doesn't match this update")]
+    ParamChangeMismatch,
 }
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::Pubkey;
+use solana_program::{custody_snapshot_leaf, verify_custody_proof};
+use solana_sha256_hasher::hashv;
+
+/// Inner node the way the indexer builds the tree: sorted pair, 0x01 prefix
+fn node(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    let (a, b) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[&[0x01], &a, &b]).to_bytes()
+}
+
+fn leaves() -> Vec<[u8; 32]> {
+    (0..4)
+        .map(|_| custody_snapshot_leaf(&Pubkey::new_unique(), &Pubkey::new_unique()))
+        .collect()
+}
+
+#[test]
+fn every_leaf_proves_against_the_root() {
+    let l = leaves();
+    let (left, right) = (node(l[0], l[1]), node(l[2], l[3]));
+    let root = node(left, right);
+
+    assert!(verify_custody_proof(&root, &l[0], &[l[1], right]));
+    assert!(verify_custody_proof(&root, &l[1], &[l[0], right]));
+    assert!(verify_custody_proof(&root, &l[2], &[l[3], left]));
+    assert!(verify_custody_proof(&root, &l[3], &[l[2], left]));
+}
+
+#[test]
+fn foreign_leaf_and_wrong_sibling_are_rejected() {
+    let l = leaves();
+    let (left, right) = (node(l[0], l[1]), node(l[2], l[3]));
+    let root = node(left, right);
+    let outsider = custody_snapshot_leaf(&Pubkey::new_unique(), &Pubkey::new_unique());
+
+    assert!(!verify_custody_proof(&root, &outsider, &[l[1], right]));
+    assert!(!verify_custody_proof(&root, &l[0], &[l[2], right]));
+}
+
+#[test]
+fn leaf_binds_owner_and_domain() {
+    let (owner, domain) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    assert_ne!(
+        custody_snapshot_leaf(&owner, &domain),
+        custody_snapshot_leaf(&domain, &owner)
+    );
+}
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::Pubkey;
+use solana_program::{
+    check_sol_domain_registry, check_wrapped_domain_mint, get_domain_key, get_tokenized_mint_key,
+    VaultError, ROOT_DOMAIN_ACCOUNT,
+};
+
+fn registry(parent: &Pubkey) -> Vec<u8> {
+    let mut data = vec![0; 96];
+    data[..32].copy_from_slice(parent.as_ref());
+    data
+}
+
+#[test]
+fn arbitrary_zero_decimal_mint_is_rejected() {
+    let name_account = get_domain_key("bonfida");
+
+    assert_eq!(
+        check_wrapped_domain_mint(&Pubkey::new_unique(), &name_account).unwrap_err(),
+        VaultError::NotSolDomain.into()
+    );
+}
+
+#[test]
+fn tokenizer_mint_of_name_account_passes() {
+    let name_account = get_domain_key("bonfida");
+    let (mint, _) = get_tokenized_mint_key(&name_account);
+
+    assert!(check_wrapped_domain_mint(&mint, &name_account).is_ok());
+}
+
+#[test]
+fn sol_domain_registry_passes() {
+    assert!(check_sol_domain_registry(&registry(&ROOT_DOMAIN_ACCOUNT)).is_ok());
+}
+
+#[test]
+fn registry_outside_sol_tld_is_rejected() {
+    assert_eq!(
+        check_sol_domain_registry(&registry(&Pubkey::new_unique())).unwrap_err(),
+        VaultError::NotSolDomain.into()
+    );
+    assert_eq!(
+        check_sol_domain_registry(&[0; 32]).unwrap_err(),
+        VaultError::NotSolDomain.into()
+    );
+}
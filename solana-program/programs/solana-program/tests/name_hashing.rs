@@ -0,0 +1,46 @@
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use solana_program::{
+    get_domain_key, get_record_v2_key, get_sol_record_v2_key, hash_domain_name, hash_record_name,
+    NAME_SERVICE_PROGRAM_ID, ROOT_DOMAIN_ACCOUNT, SOL_RECORD_V2_HASHED_NAME,
+};
+
+/// SNS SDK `getNameAccountKeySync(hashed, class, parent)`
+fn name_account_key(hashed_name: &[u8; 32], parent: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[hashed_name, Pubkey::default().as_ref(), parent.as_ref()],
+        &NAME_SERVICE_PROGRAM_ID,
+    )
+    .0
+}
+
+#[test]
+fn sol_record_name_matches_precomputed_hash() {
+    assert_eq!(hash_record_name("SOL"), SOL_RECORD_V2_HASHED_NAME);
+
+    let domain = Pubkey::new_unique();
+    assert_eq!(
+        get_record_v2_key(&domain, "SOL"),
+        get_sol_record_v2_key(&domain)
+    );
+}
+
+#[test]
+fn domain_key_matches_sdk_vector() {
+    let bonfida = name_account_key(&hash_domain_name("bonfida"), &ROOT_DOMAIN_ACCOUNT);
+    assert_eq!(
+        bonfida,
+        Pubkey::from_str("Crf8hzfthWGbGbLTVCiqRqV5MVnbpHB1L9KQMd6gsinb").unwrap()
+    );
+    assert_eq!(get_domain_key("bonfida"), bonfida);
+}
+
+#[test]
+fn record_and_domain_names_hash_differently() {
+    for name in ["SOL", "url", "ETH", "", "🍍"] {
+        assert_ne!(hash_record_name(name), hash_domain_name(name));
+    }
+    assert_eq!(hash_record_name("SOL"), hash_domain_name("\u{2}SOL"));
+    assert_ne!(hash_record_name("SOL"), hash_record_name("sol"));
+}
@@ -0,0 +1,196 @@
+//! Canonical instruction data and PDA vectors shared with the web client.
+//!
+//! The fixture at `web/src/lib/__fixtures__/vault-parity.json` is checked by
+//! `web/scripts/check-vault-parity.ts`. Regenerate it after an intended layout change with
+//! `UPDATE_PARITY_FIXTURES=1 cargo test --test parity_vectors`.
+
+use std::{env, fs, path::PathBuf};
+
+use anchor_lang::{prelude::Pubkey, InstructionData};
+use anchor_spl::{token, token_2022};
+use solana_program::{
+    get_central_state_key, get_domain_entry_address, get_sol_record_v2_key, get_vault_address,
+    get_vault_token_account, hash_domain_name, hash_record_name, instruction, DnsRecord,
+};
+
+const MEMO_HASH: [u8; 32] = [7; 32];
+const CONFIRMATION_PHRASE: &str = "correct horse battery staple";
+
+fn fixture_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../../web/src/lib/__fixtures__/vault-parity.json")
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn pda((address, bump): (Pubkey, u8)) -> String {
+    format!("{{ \"address\": \"{address}\", \"bump\": {bump} }}")
+}
+
+fn entries(fields: &[(&str, String)]) -> String {
+    fields
+        .iter()
+        .map(|(name, value)| format!("    \"{name}\": {value}"))
+        .collect::<Vec<_>>()
+        .join(",\n")
+}
+
+fn render() -> String {
+    let owner = Pubkey::new_from_array([1; 32]);
+    let mint = Pubkey::new_from_array([2; 32]);
+    let name_account = Pubkey::new_from_array([3; 32]);
+    let (vault, _) = get_vault_address(&owner);
+    let phrase = Some(CONFIRMATION_PHRASE.to_string());
+
+    let inputs = entries(&[
+        ("owner", format!("\"{owner}\"")),
+        ("mint", format!("\"{mint}\"")),
+        ("nameAccount", format!("\"{name_account}\"")),
+        ("memoHash", format!("\"{}\"", hex(&MEMO_HASH))),
+        ("confirmationPhrase", format!("\"{CONFIRMATION_PHRASE}\"")),
+    ]);
+
+    let pdas = entries(&[
+        ("vault", pda(get_vault_address(&owner))),
+        ("wrappedEntry", pda(get_domain_entry_address(&vault, &mint))),
+        (
+            "unwrappedEntry",
+            pda(get_domain_entry_address(&vault, &name_account)),
+        ),
+        ("solRecordV2", pda(get_sol_record_v2_key(&name_account))),
+        ("snsRecordsCentralState", pda(get_central_state_key())),
+        (
+            "vaultTokenAccount",
+            format!("\"{}\"", get_vault_token_account(&owner, &mint, &token::ID)),
+        ),
+        (
+            "vaultTokenAccount2022",
+            format!(
+                "\"{}\"",
+                get_vault_token_account(&owner, &mint, &token_2022::ID)
+            ),
+        ),
+    ]);
+
+    let hashes = entries(&[
+        (
+            "domainBonfida",
+            format!("\"{}\"", hex(&hash_domain_name("bonfida"))),
+        ),
+        (
+            "recordSol",
+            format!("\"{}\"", hex(&hash_record_name("SOL"))),
+        ),
+        (
+            "recordUrl",
+            format!("\"{}\"", hex(&hash_record_name("url"))),
+        ),
+    ]);
+
+    let data = |bytes: Vec<u8>| format!("\"{}\"", hex(&bytes));
+    let instructions = entries(&[
+        (
+            "initializeVault",
+            data(instruction::InitializeVault {}.data()),
+        ),
+        ("upgradeVault", data(instruction::UpgradeVault {}.data())),
+        (
+            "initVaultTokenAccount",
+            data(instruction::InitVaultTokenAccount {}.data()),
+        ),
+        (
+            "depositDomain",
+            data(instruction::DepositDomain { memo_hash: None }.data()),
+        ),
+        (
+            "depositDomainWithMemo",
+            data(
+                instruction::DepositDomain {
+                    memo_hash: Some(MEMO_HASH),
+                }
+                .data(),
+            ),
+        ),
+        (
+            "withdrawDomain",
+            data(
+                instruction::WithdrawDomain {
+                    memo_hash: None,
+                    confirmation_phrase: None,
+                }
+                .data(),
+            ),
+        ),
+        (
+            "withdrawDomainWithMemoAndPhrase",
+            data(
+                instruction::WithdrawDomain {
+                    memo_hash: Some(MEMO_HASH),
+                    confirmation_phrase: phrase.clone(),
+                }
+                .data(),
+            ),
+        ),
+        (
+            "depositUnwrappedDomain",
+            data(
+                instruction::DepositUnwrappedDomain {
+                    memo_hash: Some(MEMO_HASH),
+                }
+                .data(),
+            ),
+        ),
+        (
+            "withdrawUnwrappedDomain",
+            data(
+                instruction::WithdrawUnwrappedDomain {
+                    memo_hash: None,
+                    confirmation_phrase: phrase,
+                }
+                .data(),
+            ),
+        ),
+        (
+            "depositDomainWithRecord",
+            data(instruction::DepositDomainWithRecord { memo_hash: None }.data()),
+        ),
+        (
+            "updateDnsRecordA",
+            data(
+                instruction::UpdateDnsRecord {
+                    record: DnsRecord::A("192.0.2.1".to_string()),
+                }
+                .data(),
+            ),
+        ),
+    ]);
+
+    format!(
+        "{{\n  \"inputs\": {{\n{}\n  }},\n  \"pdas\": {{\n{}\n  }},\n  \"hashes\": {{\n{}\n  }},\n  \"instructions\": {{\n{}\n  }}\n}}\n",
+        inputs,
+        pdas,
+        hashes,
+        instructions
+    )
+}
+
+#[test]
+fn web_client_fixture_is_current() {
+    let rendered = render();
+    let path = fixture_path();
+
+    if env::var_os("UPDATE_PARITY_FIXTURES").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, &rendered).unwrap();
+        return;
+    }
+
+    let checked_in = fs::read_to_string(&path).unwrap_or_default();
+    assert!(
+        checked_in == rendered,
+        "{} is stale; rerun with UPDATE_PARITY_FIXTURES=1 and update the web client",
+        path.display()
+    );
+}
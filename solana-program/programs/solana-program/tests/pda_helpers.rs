@@ -0,0 +1,67 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::{associated_token, token, token_2022};
+use solana_program::{
+    get_domain_entry_address, get_program_info_address, get_vault_address, get_vault_token_account,
+    get_voting_delegation_address, ENTRY_SEED, ID, PROGRAM_INFO_SEED, VAULT_SEED,
+    VOTING_DELEGATION_SEED,
+};
+
+#[test]
+fn vault_address_matches_seeds() {
+    let owner = Pubkey::new_unique();
+    let expected = Pubkey::find_program_address(&[VAULT_SEED, owner.as_ref()], &ID);
+
+    assert_eq!(get_vault_address(&owner), expected);
+    assert_ne!(
+        get_vault_address(&owner).0,
+        get_vault_address(&Pubkey::new_unique()).0
+    );
+}
+
+#[test]
+fn vault_token_account_is_vault_ata() {
+    let owner = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let (vault, _) = get_vault_address(&owner);
+
+    for token_program in [token::ID, token_2022::ID] {
+        let expected = Pubkey::find_program_address(
+            &[vault.as_ref(), token_program.as_ref(), mint.as_ref()],
+            &associated_token::ID,
+        )
+        .0;
+        assert_eq!(
+            get_vault_token_account(&owner, &mint, &token_program),
+            expected
+        );
+    }
+    assert_ne!(
+        get_vault_token_account(&owner, &mint, &token::ID),
+        get_vault_token_account(&owner, &mint, &token_2022::ID)
+    );
+}
+
+#[test]
+fn domain_entry_address_matches_seeds() {
+    let (vault, _) = get_vault_address(&Pubkey::new_unique());
+    let domain = Pubkey::new_unique();
+    let expected =
+        Pubkey::find_program_address(&[ENTRY_SEED, vault.as_ref(), domain.as_ref()], &ID);
+
+    assert_eq!(get_domain_entry_address(&vault, &domain), expected);
+}
+
+#[test]
+fn voting_delegation_address_matches_seeds() {
+    let (vault, _) = get_vault_address(&Pubkey::new_unique());
+    let expected = Pubkey::find_program_address(&[VOTING_DELEGATION_SEED, vault.as_ref()], &ID);
+
+    assert_eq!(get_voting_delegation_address(&vault), expected);
+}
+
+#[test]
+fn program_info_address_matches_seeds() {
+    let expected = Pubkey::find_program_address(&[PROGRAM_INFO_SEED], &ID);
+
+    assert_eq!(get_program_info_address(), expected);
+}
@@ -0,0 +1,66 @@
+use solana_program::{
+    write_program_info, ProgramInfo, ENABLED_FEATURES, FEATURE_AUTO_SWEEP, FEATURE_BUNDLES,
+    FEATURE_BURNER_SUBDOMAINS, FEATURE_CUSTODY_SNAPSHOTS, FEATURE_KEEPER_BOUNTIES,
+    FEATURE_OUTFLOW_BREAKER, FEATURE_PAYMENTS, FEATURE_RECORDS, FEATURE_RECORD_SESSIONS,
+    FEATURE_SCHEDULED_OPERATIONS, FEATURE_STAGED_RECORD_UPDATES, FEATURE_STAKING,
+    FEATURE_SWEEP_BUCKETS, FEATURE_UNWRAPPED_DOMAINS, FEATURE_VAULT_SIGNATURE_CLAIMS,
+    FEATURE_VOTING_DELEGATION, FEATURE_WRAPPED_DOMAINS, PROGRAM_VERSION,
+};
+
+const FEATURES: [u64; 17] = [
+    FEATURE_WRAPPED_DOMAINS,
+    FEATURE_UNWRAPPED_DOMAINS,
+    FEATURE_RECORDS,
+    FEATURE_RECORD_SESSIONS,
+    FEATURE_STAKING,
+    FEATURE_PAYMENTS,
+    FEATURE_AUTO_SWEEP,
+    FEATURE_SWEEP_BUCKETS,
+    FEATURE_BUNDLES,
+    FEATURE_SCHEDULED_OPERATIONS,
+    FEATURE_KEEPER_BOUNTIES,
+    FEATURE_OUTFLOW_BREAKER,
+    FEATURE_CUSTODY_SNAPSHOTS,
+    FEATURE_VOTING_DELEGATION,
+    FEATURE_BURNER_SUBDOMAINS,
+    FEATURE_STAGED_RECORD_UPDATES,
+    FEATURE_VAULT_SIGNATURE_CLAIMS,
+];
+
+#[test]
+fn program_version_matches_crate_version() {
+    let version = PROGRAM_VERSION.map(|part| part.to_string()).join(".");
+    assert_eq!(version, env!("CARGO_PKG_VERSION"));
+}
+
+#[test]
+fn feature_bits_are_distinct_single_bits() {
+    let mut seen = 0u64;
+    for feature in FEATURES {
+        assert_eq!(feature.count_ones(), 1);
+        assert_eq!(seen & feature, 0);
+        seen |= feature;
+    }
+}
+
+#[test]
+fn sync_writes_version_and_features() {
+    let mut program_info = ProgramInfo {
+        major: 0,
+        minor: 0,
+        patch: 0,
+        features: 0,
+        updated_slot: 0,
+        bump: 255,
+    };
+
+    write_program_info(&mut program_info, 42);
+
+    assert_eq!(
+        [program_info.major, program_info.minor, program_info.patch],
+        PROGRAM_VERSION
+    );
+    assert_eq!(program_info.features, ENABLED_FEATURES);
+    assert_eq!(program_info.updated_slot, 42);
+    assert_eq!(program_info.bump, 255);
+}
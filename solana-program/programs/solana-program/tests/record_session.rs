@@ -0,0 +1,36 @@
+use anchor_lang::prelude::Pubkey;
+use solana_program::{
+    check_session_record, get_domain_key, get_record_v2_key, RecordSessionPolicy, VaultError,
+};
+
+fn policy(allowed_records: Vec<Pubkey>) -> RecordSessionPolicy {
+    RecordSessionPolicy {
+        vault: Pubkey::new_unique(),
+        domain: get_domain_key("bonfida"),
+        delegate: Pubkey::new_unique(),
+        allowed_records,
+        expected_records_hash: [0; 32],
+        expires_at: 0,
+        active: true,
+        bump: 255,
+    }
+}
+
+#[test]
+fn approved_record_can_be_written() {
+    let (url, _) = get_record_v2_key(&get_domain_key("bonfida"), "url");
+
+    assert!(check_session_record(&policy(vec![url]), &url).is_ok());
+}
+
+#[test]
+fn record_outside_approved_set_is_rejected() {
+    let name_account = get_domain_key("bonfida");
+    let (url, _) = get_record_v2_key(&name_account, "url");
+    let (sol, _) = get_record_v2_key(&name_account, "SOL");
+
+    assert_eq!(
+        check_session_record(&policy(vec![url]), &sol).unwrap_err(),
+        VaultError::RecordNotInSession.into()
+    );
+}
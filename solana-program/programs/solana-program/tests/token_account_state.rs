@@ -0,0 +1,133 @@
+use anchor_lang::prelude::{AccountDeserialize, Pubkey};
+use anchor_lang::solana_program::{program_option::COption, program_pack::Pack};
+use anchor_spl::token_2022::spl_token_2022::state::{Account, AccountState};
+use anchor_spl::token_interface::TokenAccount;
+use solana_program::{check_token_account_state, check_vault_destination_account, VaultError};
+
+fn token_account(mint: Pubkey, owner: Pubkey, tweak: impl FnOnce(&mut Account)) -> TokenAccount {
+    let mut account = Account {
+        mint,
+        owner,
+        amount: 0,
+        delegate: COption::None,
+        state: AccountState::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    };
+    tweak(&mut account);
+
+    let mut data = vec![0; Account::LEN];
+    Account::pack(account, &mut data).unwrap();
+    TokenAccount::try_deserialize(&mut &data[..]).unwrap()
+}
+
+#[test]
+fn clean_account_passes() {
+    let (mint, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let account = token_account(mint, owner, |_| {});
+
+    assert!(check_token_account_state(&account, &mint, &owner).is_ok());
+}
+
+#[test]
+fn close_authority_held_by_owner_passes() {
+    let (mint, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let account = token_account(mint, owner, |a| a.close_authority = COption::Some(owner));
+
+    assert!(check_token_account_state(&account, &mint, &owner).is_ok());
+}
+
+#[test]
+fn wrong_mint_is_rejected() {
+    let (mint, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let account = token_account(Pubkey::new_unique(), owner, |_| {});
+
+    assert_eq!(
+        check_token_account_state(&account, &mint, &owner).unwrap_err(),
+        VaultError::TokenAccountMismatch.into()
+    );
+}
+
+#[test]
+fn wrong_owner_is_rejected() {
+    let (mint, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let account = token_account(mint, Pubkey::new_unique(), |_| {});
+
+    assert_eq!(
+        check_token_account_state(&account, &mint, &owner).unwrap_err(),
+        VaultError::TokenAccountMismatch.into()
+    );
+}
+
+#[test]
+fn pre_approved_delegate_is_rejected() {
+    let (mint, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let account = token_account(mint, owner, |a| {
+        a.delegate = COption::Some(Pubkey::new_unique());
+        a.delegated_amount = 1;
+    });
+
+    assert_eq!(
+        check_token_account_state(&account, &mint, &owner).unwrap_err(),
+        VaultError::DelegatedTokenAccount.into()
+    );
+}
+
+#[test]
+fn foreign_close_authority_is_rejected() {
+    let (mint, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let account = token_account(mint, owner, |a| {
+        a.close_authority = COption::Some(Pubkey::new_unique())
+    });
+
+    assert_eq!(
+        check_token_account_state(&account, &mint, &owner).unwrap_err(),
+        VaultError::ForeignCloseAuthority.into()
+    );
+}
+
+#[test]
+fn frozen_account_is_rejected() {
+    let (mint, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let account = token_account(mint, owner, |a| a.state = AccountState::Frozen);
+
+    assert_eq!(
+        check_token_account_state(&account, &mint, &owner).unwrap_err(),
+        VaultError::FrozenTokenAccount.into()
+    );
+}
+
+#[test]
+fn vault_destination_with_delegate_passes() {
+    let (mint, vault) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let account = token_account(mint, vault, |a| {
+        a.delegate = COption::Some(Pubkey::new_unique());
+        a.delegated_amount = 1;
+        a.close_authority = COption::Some(vault);
+    });
+
+    assert!(check_vault_destination_account(&account, &mint, &vault).is_ok());
+}
+
+#[test]
+fn foreign_owned_vault_destination_is_rejected() {
+    let (mint, vault) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let account = token_account(mint, Pubkey::new_unique(), |_| {});
+
+    assert_eq!(
+        check_vault_destination_account(&account, &mint, &vault).unwrap_err(),
+        VaultError::TokenAccountMismatch.into()
+    );
+}
+
+#[test]
+fn frozen_vault_destination_is_rejected() {
+    let (mint, vault) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let account = token_account(mint, vault, |a| a.state = AccountState::Frozen);
+
+    assert_eq!(
+        check_vault_destination_account(&account, &mint, &vault).unwrap_err(),
+        VaultError::FrozenTokenAccount.into()
+    );
+}
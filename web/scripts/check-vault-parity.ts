@@ -0,0 +1,108 @@
+/**
+ * Check the web client against the vectors generated by the vault program's
+ * `parity_vectors` test (src/lib/__fixtures__/vault-parity.json)
+ * Run with: npm run check:parity
+ */
+
+import { readFileSync } from 'fs';
+import { join } from 'path';
+import { PublicKey } from '@solana/web3.js';
+import { TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import {
+  DEPOSIT_DOMAIN_DISCRIMINATOR,
+  DEPOSIT_DOMAIN_WITH_RECORD_DISCRIMINATOR,
+  DEPOSIT_UNWRAPPED_DOMAIN_DISCRIMINATOR,
+  INITIALIZE_VAULT_DISCRIMINATOR,
+  INIT_VAULT_TOKEN_ACCOUNT_DISCRIMINATOR,
+  UPGRADE_VAULT_DISCRIMINATOR,
+  WITHDRAW_DOMAIN_DISCRIMINATOR,
+  WITHDRAW_UNWRAPPED_DOMAIN_DISCRIMINATOR,
+  createUpdateDnsRecordInstruction,
+  encodeCustodyInstructionData,
+  encodeWithdrawInstructionData,
+  getDomainEntryPDA,
+  hashDomainName,
+  hashRecordName,
+  getSnsRecordsCentralState,
+  getSolRecordV2PDA,
+  getVaultPDA,
+  getVaultTokenAccount,
+} from '../src/lib/vault-service';
+
+const fixture = JSON.parse(
+  readFileSync(join(__dirname, '../src/lib/__fixtures__/vault-parity.json'), 'utf8')
+);
+
+const owner = new PublicKey(fixture.inputs.owner);
+const mint = new PublicKey(fixture.inputs.mint);
+const nameAccount = new PublicKey(fixture.inputs.nameAccount);
+const memoHash = Buffer.from(fixture.inputs.memoHash, 'hex');
+const phrase: string = fixture.inputs.confirmationPhrase;
+
+const pda = ([address, bump]: [PublicKey, number]) => ({ address: address.toBase58(), bump });
+const [vault] = getVaultPDA(owner);
+
+const actual = {
+  pdas: {
+    vault: pda(getVaultPDA(owner)),
+    wrappedEntry: pda(getDomainEntryPDA(vault, mint)),
+    unwrappedEntry: pda(getDomainEntryPDA(vault, nameAccount)),
+    solRecordV2: pda(getSolRecordV2PDA(nameAccount)),
+    snsRecordsCentralState: pda(getSnsRecordsCentralState()),
+    vaultTokenAccount: getVaultTokenAccount(owner, mint, TOKEN_PROGRAM_ID).toBase58(),
+    vaultTokenAccount2022: getVaultTokenAccount(owner, mint, TOKEN_2022_PROGRAM_ID).toBase58(),
+  },
+  hashes: {
+    domainBonfida: hashDomainName('bonfida').toString('hex'),
+    recordSol: hashRecordName('SOL').toString('hex'),
+    recordUrl: hashRecordName('url').toString('hex'),
+  } as Record<string, string>,
+  instructions: {
+    initializeVault: INITIALIZE_VAULT_DISCRIMINATOR,
+    upgradeVault: UPGRADE_VAULT_DISCRIMINATOR,
+    initVaultTokenAccount: INIT_VAULT_TOKEN_ACCOUNT_DISCRIMINATOR,
+    depositDomain: encodeCustodyInstructionData(DEPOSIT_DOMAIN_DISCRIMINATOR),
+    depositDomainWithMemo: encodeCustodyInstructionData(DEPOSIT_DOMAIN_DISCRIMINATOR, memoHash),
+    withdrawDomain: encodeWithdrawInstructionData(WITHDRAW_DOMAIN_DISCRIMINATOR),
+    withdrawDomainWithMemoAndPhrase: encodeWithdrawInstructionData(
+      WITHDRAW_DOMAIN_DISCRIMINATOR,
+      memoHash,
+      phrase
+    ),
+    depositUnwrappedDomain: encodeCustodyInstructionData(
+      DEPOSIT_UNWRAPPED_DOMAIN_DISCRIMINATOR,
+      memoHash
+    ),
+    withdrawUnwrappedDomain: encodeWithdrawInstructionData(
+      WITHDRAW_UNWRAPPED_DOMAIN_DISCRIMINATOR,
+      undefined,
+      phrase
+    ),
+    depositDomainWithRecord: encodeCustodyInstructionData(DEPOSIT_DOMAIN_WITH_RECORD_DISCRIMINATOR),
+    updateDnsRecordA: createUpdateDnsRecordInstruction(owner, nameAccount, 'A', '192.0.2.1').data,
+  } as Record<string, Buffer>,
+};
+
+let failures = 0;
+const check = (name: string, got: unknown, expected: unknown) => {
+  if (JSON.stringify(got) !== JSON.stringify(expected)) {
+    console.error(`✗ ${name}\n  expected ${JSON.stringify(expected)}\n  got      ${JSON.stringify(got)}`);
+    failures++;
+  }
+};
+
+for (const [name, expected] of Object.entries(fixture.pdas)) {
+  check(`pdas.${name}`, actual.pdas[name as keyof typeof actual.pdas], expected);
+}
+for (const [name, expected] of Object.entries(fixture.hashes)) {
+  check(`hashes.${name}`, actual.hashes[name], expected);
+}
+for (const [name, expected] of Object.entries(fixture.instructions)) {
+  check(`instructions.${name}`, actual.instructions[name]?.toString('hex'), expected);
+}
+
+if (failures > 0) {
+  console.error(`${failures} vector(s) differ from the program`);
+  process.exit(1);
+}
+console.log('Web client matches the vault program vectors');
@@ -630,7 +630,13 @@ function DomainItem({ domain, pubkey, isWrapped, isSubdomain, parentDomain, mint
       if (isWrapped && mintAddress) {
         // For wrapped (NFT) domains, use the NFT deposit
         const mintPubkey = new PublicKey(mintAddress);
-        transaction = await buildDepositTransaction(connection, ownerPubkey, mintPubkey);
+        const nameAccountPubkey = new PublicKey(pubkey);
+        transaction = await buildDepositTransaction(
+          connection,
+          ownerPubkey,
+          mintPubkey,
+          nameAccountPubkey
+        );
       } else {
         // For unwrapped domains, use deposit with SOL record update
         // This automatically sets the SOL record to point to the vault PDA
@@ -697,11 +703,11 @@ function DomainItem({ domain, pubkey, isWrapped, isSubdomain, parentDomain, mint
       if (isWrapped && mintAddress) {
         // For wrapped (NFT) domains, use the NFT withdraw
         const mintPubkey = new PublicKey(mintAddress);
-        transaction = await buildWithdrawTransaction(ownerPubkey, mintPubkey);
+        transaction = await buildWithdrawTransaction(connection, ownerPubkey, mintPubkey);
       } else {
         // For unwrapped domains, use the name account withdraw
         const nameAccountPubkey = new PublicKey(pubkey);
-        transaction = buildWithdrawUnwrappedTransaction(ownerPubkey, nameAccountPubkey);
+        transaction = await buildWithdrawUnwrappedTransaction(connection, ownerPubkey, nameAccountPubkey);
       }
       
       transaction.feePayer = ownerPubkey;
@@ -1319,7 +1325,7 @@ function VaultBalanceCard({ endpoint, ownerAddress }: { endpoint: string; ownerA
       }
       
       // Import vault service functions
-      const { getVaultPDA, createInitVaultTokenAccountInstruction, vaultExists, createInitializeVaultInstruction, getTokenProgramForMint } = await import('@/lib/vault-service');
+      const { getVaultPDA, createInitVaultTokenAccountInstruction, addVaultSetupInstructions, getTokenProgramForMint } = await import('@/lib/vault-service');
       const { getAssociatedTokenAddressSync, ASSOCIATED_TOKEN_PROGRAM_ID } = await import('@solana/spl-token');
       
       const [vaultPDA] = getVaultPDA(ownerPubkey);
@@ -1348,11 +1354,8 @@ function VaultBalanceCard({ endpoint, ownerAddress }: { endpoint: string; ownerA
       
       const transaction = new Transaction();
       
-      // Check if vault exists, if not initialize it first
-      const hasVault = await vaultExists(connection, ownerPubkey);
-      if (!hasVault) {
-        transaction.add(createInitializeVaultInstruction(ownerPubkey));
-      }
+      // Initialize the vault if it doesn't exist, or upgrade it if it's outdated
+      await addVaultSetupInstructions(connection, transaction, ownerPubkey);
       
       // Create ATA through smart contract with correct token program
       const initAtaIx = createInitVaultTokenAccountInstruction(ownerPubkey, mintPubkey, tokenProgramId);
@@ -1,4 +1,11 @@
-import { PublicKey, Transaction, TransactionInstruction, Connection, SystemProgram } from '@solana/web3.js';
+import {
+  PublicKey,
+  Transaction,
+  TransactionInstruction,
+  Connection,
+  SystemProgram,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+} from '@solana/web3.js';
 import { 
   TOKEN_PROGRAM_ID, 
   TOKEN_2022_PROGRAM_ID,
@@ -19,6 +26,42 @@ export const SNS_RECORDS_PROGRAM_ID = new PublicKey('HP3D4D1ZCmohQGFVms2SS4LCANg
 // Seed for vault PDA
 const VAULT_SEED = Buffer.from('vault');
 
+// Seed for the global protocol config PDA
+const CONFIG_SEED = Buffer.from('config');
+
+// Seed for the protocol fee treasury PDA
+const TREASURY_SEED = Buffer.from('treasury');
+
+// Seed for the global protocol stats PDA
+const STATS_SEED = Buffer.from('stats');
+
+// Seed for vault withdrawal confirmation phrase PDAs
+const WITHDRAWAL_PHRASE_SEED = Buffer.from('withdrawal_phrase');
+
+// Seed for the outflow circuit breaker PDA
+const OUTFLOW_BREAKER_SEED = Buffer.from('outflow_breaker');
+
+// Seed for vault summary PDAs
+const VAULT_SUMMARY_SEED = Buffer.from('vault_summary');
+
+// Seed for the program info PDA
+const PROGRAM_INFO_SEED = Buffer.from('program_info');
+
+// Seed for the global security log PDA
+const SECURITY_LOG_SEED = Buffer.from('security_log');
+
+// Seed for per-user points ledger PDAs
+const POINTS_SEED = Buffer.from('points');
+
+// Seed for per-domain entry PDAs
+const ENTRY_SEED = Buffer.from('entry');
+
+// Seed for quarantined vault token account flags
+const QUARANTINE_SEED = Buffer.from('quarantine');
+
+// Seed for per-mint deposit policy PDAs
+const MINT_POLICY_SEED = Buffer.from('mint_policy');
+
 /**
  * Get the token program ID for a given mint
  * Returns TOKEN_2022_PROGRAM_ID if the mint is owned by Token-2022, otherwise TOKEN_PROGRAM_ID
@@ -39,18 +82,29 @@ export async function getTokenProgramForMint(
 }
 
 // Instruction discriminators from the IDL
-const INITIALIZE_VAULT_DISCRIMINATOR = Buffer.from([48, 191, 163, 44, 71, 129, 63, 164]);
-const DEPOSIT_DOMAIN_DISCRIMINATOR = Buffer.from([171, 18, 52, 229, 2, 180, 77, 114]);
-const WITHDRAW_DOMAIN_DISCRIMINATOR = Buffer.from([147, 201, 125, 224, 211, 146, 142, 245]);
+export const INITIALIZE_VAULT_DISCRIMINATOR = Buffer.from([48, 191, 163, 44, 71, 129, 63, 164]);
+export const DEPOSIT_DOMAIN_DISCRIMINATOR = Buffer.from([171, 18, 52, 229, 2, 180, 77, 114]);
+export const WITHDRAW_DOMAIN_DISCRIMINATOR = Buffer.from([147, 201, 125, 224, 211, 146, 142, 245]);
 // Discriminators for unwrapped domains
-const DEPOSIT_UNWRAPPED_DOMAIN_DISCRIMINATOR = Buffer.from([96, 225, 213, 173, 90, 111, 128, 32]);
-const WITHDRAW_UNWRAPPED_DOMAIN_DISCRIMINATOR = Buffer.from([170, 189, 73, 129, 207, 42, 120, 91]);
+export const DEPOSIT_UNWRAPPED_DOMAIN_DISCRIMINATOR = Buffer.from([96, 225, 213, 173, 90, 111, 128, 32]);
+export const WITHDRAW_UNWRAPPED_DOMAIN_DISCRIMINATOR = Buffer.from([170, 189, 73, 129, 207, 42, 120, 91]);
 // Discriminator for init_vault_token_account (sha256("global:init_vault_token_account")[0..8])
 // Hex: a57a8af03703c554 -> [165, 122, 138, 240, 55, 3, 197, 84]
-const INIT_VAULT_TOKEN_ACCOUNT_DISCRIMINATOR = Buffer.from([165, 122, 138, 240, 55, 3, 197, 84]);
+export const INIT_VAULT_TOKEN_ACCOUNT_DISCRIMINATOR = Buffer.from([165, 122, 138, 240, 55, 3, 197, 84]);
+// Discriminator for prepare_deposit (sha256("global:prepare_deposit")[0..8])
+export const PREPARE_DEPOSIT_DISCRIMINATOR = Buffer.from([82, 200, 18, 85, 227, 49, 29, 196]);
 // Discriminator for deposit_domain_with_record (sha256("global:deposit_domain_with_record")[0..8])
 // This instruction deposits domain AND sets the SOL record to the vault PDA
-const DEPOSIT_DOMAIN_WITH_RECORD_DISCRIMINATOR = Buffer.from([160, 246, 38, 202, 11, 144, 113, 240]);
+export const DEPOSIT_DOMAIN_WITH_RECORD_DISCRIMINATOR = Buffer.from([160, 246, 38, 202, 11, 144, 113, 240]);
+// Discriminator for upgrade_vault (sha256("global:upgrade_vault")[0..8])
+// Hex: 34bda7adbddf74a1 -> [52, 189, 167, 173, 189, 223, 116, 161]
+export const UPGRADE_VAULT_DISCRIMINATOR = Buffer.from([52, 189, 167, 173, 189, 223, 116, 161]);
+// Discriminator for update_dns_record (sha256("global:update_dns_record")[0..8])
+export const UPDATE_DNS_RECORD_DISCRIMINATOR = Buffer.from([13, 195, 13, 25, 216, 174, 11, 61]);
+
+// Current size of the vault account: discriminator (8) + owner (32) + bump (1) + domains_count (8)
+// + in_flight (1) + reserved (64)
+const VAULT_ACCOUNT_SIZE = 114;
 
 /**
  * Get the vault PDA for a user
@@ -63,24 +117,182 @@ export function getVaultPDA(owner: PublicKey): [PublicKey, number] {
 }
 
 /**
- * Get the SOL record V2 PDA for a domain name account
- * Uses the same derivation as the SNS SDK:
- * 1. recordName = 0x02 byte + "SOL" 
- * 2. hashedName = sha256(HASH_PREFIX + recordName) where HASH_PREFIX = 'SPL Name Service'
- * 3. PDA = findProgramAddressSync([hashedName, centralState, domainKey], NAME_PROGRAM_ID)
+ * Get the global protocol config PDA
  */
-export function getSolRecordV2PDA(domainNameAccount: PublicKey): [PublicKey, number] {
+export function getConfigPDA(): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [CONFIG_SEED],
+    VAULT_PROGRAM_ID
+  );
+}
+
+/**
+ * Get the protocol fee treasury PDA
+ */
+export function getTreasuryPDA(): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [TREASURY_SEED],
+    VAULT_PROGRAM_ID
+  );
+}
+
+/**
+ * Get the global protocol stats PDA
+ */
+export function getStatsPDA(): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [STATS_SEED],
+    VAULT_PROGRAM_ID
+  );
+}
+
+/**
+ * Get the outflow circuit breaker PDA (may not exist)
+ */
+export function getOutflowBreakerPDA(): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [OUTFLOW_BREAKER_SEED],
+    VAULT_PROGRAM_ID
+  );
+}
+
+/**
+ * Get the summary PDA of a vault (may not exist)
+ */
+export function getVaultSummaryPDA(vault: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [VAULT_SUMMARY_SEED, vault.toBuffer()],
+    VAULT_PROGRAM_ID
+  );
+}
+
+/**
+ * Get the program info PDA (may not exist until someone syncs it)
+ */
+export function getProgramInfoPDA(): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [PROGRAM_INFO_SEED],
+    VAULT_PROGRAM_ID
+  );
+}
+
+/**
+ * Get the global security log PDA (may not exist)
+ */
+export function getSecurityLogPDA(): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [SECURITY_LOG_SEED],
+    VAULT_PROGRAM_ID
+  );
+}
+
+/**
+ * Get the withdrawal confirmation phrase PDA of a vault (may not exist)
+ */
+export function getWithdrawalPhrasePDA(vault: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [WITHDRAWAL_PHRASE_SEED, vault.toBuffer()],
+    VAULT_PROGRAM_ID
+  );
+}
+
+/**
+ * Get the points ledger PDA for a vault owner
+ */
+export function getUserPointsPDA(owner: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [POINTS_SEED, owner.toBuffer()],
+    VAULT_PROGRAM_ID
+  );
+}
+
+/**
+ * Get the entry PDA tracking a domain in a vault
+ * @param domain - The domain mint (wrapped) or name account (unwrapped)
+ */
+export function getDomainEntryPDA(vault: PublicKey, domain: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [ENTRY_SEED, vault.toBuffer(), domain.toBuffer()],
+    VAULT_PROGRAM_ID
+  );
+}
+
+/**
+ * Get the vault's associated token account for a mint
+ * Mirrors `get_vault_token_account` in the program crate
+ */
+export function getVaultTokenAccount(
+  owner: PublicKey,
+  mint: PublicKey,
+  tokenProgramId: PublicKey = TOKEN_PROGRAM_ID
+): PublicKey {
+  const [vaultPDA] = getVaultPDA(owner);
+  return getAssociatedTokenAddressSync(
+    mint,
+    vaultPDA,
+    true, // allowOwnerOffCurve - required for PDAs
+    tokenProgramId,
+    ASSOCIATED_TOKEN_PROGRAM_ID
+  );
+}
+
+/**
+ * Get the quarantine flag PDA of a vault token account (exists only if quarantined)
+ */
+export function getQuarantinePDA(tokenAccount: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [QUARANTINE_SEED, tokenAccount.toBuffer()],
+    VAULT_PROGRAM_ID
+  );
+}
+
+/**
+ * Get the deposit policy PDA for a mint (may not exist)
+ */
+export function getMintPolicyPDA(mint: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [MINT_POLICY_SEED, mint.toBuffer()],
+    VAULT_PROGRAM_ID
+  );
+}
+
+const SNS_HASH_PREFIX = 'SPL Name Service';
+
+/**
+ * Hash a domain name the way SNS does: sha256("SPL Name Service" + name)
+ * Mirrors `hash_domain_name` in the program crate; `name` excludes the TLD
+ */
+export function hashDomainName(name: string): Buffer {
   // eslint-disable-next-line @typescript-eslint/no-require-imports
   const crypto = require('crypto');
-  const HASH_PREFIX = 'SPL Name Service';
-  
-  // Record name with V2 prefix (0x02 byte prepended)
-  const recordNameWithPrefix = Buffer.from([0x02]).toString() + 'SOL';
-  
-  // Hash: sha256(HASH_PREFIX + recordNameWithPrefix)
-  const hashedName = crypto.createHash('sha256')
-    .update(Buffer.from(HASH_PREFIX + recordNameWithPrefix, 'utf8'))
+  return crypto.createHash('sha256')
+    .update(Buffer.from(SNS_HASH_PREFIX + name, 'utf8'))
     .digest();
+}
+
+/**
+ * Hash a Record V2 name (e.g. "SOL"): sha256("SPL Name Service" + 0x02 + record)
+ * Mirrors `hash_record_name` in the program crate
+ */
+export function hashRecordName(record: string): Buffer {
+  return hashDomainName(String.fromCharCode(0x02) + record);
+}
+
+/**
+ * Get the SOL record V2 PDA for a domain name account
+ */
+export function getSolRecordV2PDA(domainNameAccount: PublicKey): [PublicKey, number] {
+  return getRecordV2PDA(domainNameAccount, 'SOL');
+}
+
+/**
+ * Get the Record V2 PDA of any record on a domain name account
+ * Uses the same derivation as the SNS SDK:
+ * 1. hashedName = hashRecordName(record)
+ * 2. PDA = findProgramAddressSync([hashedName, centralState, domainKey], NAME_PROGRAM_ID)
+ */
+export function getRecordV2PDA(domainNameAccount: PublicKey, record: string): [PublicKey, number] {
+  const hashedName = hashRecordName(record);
   
   // Get central state
   const [centralState] = getSnsRecordsCentralState();
@@ -107,6 +319,33 @@ export function getSnsRecordsCentralState(): [PublicKey, number] {
   );
 }
 
+// Maximum record content sizes accepted by the vault program, per content type
+export const MAX_RECORD_CONTENT_LEN = {
+  pubkey: 32,
+  ethAddress: 20,
+  utf8: 256,
+  ipfsCid: 128,
+} as const;
+
+// Record V2 account layout: name registry header (parent + owner + class) + record header
+// (staleness validation u16 + roa validation u16 + content length u32)
+const NAME_REGISTRY_HEADER_LEN = 96;
+const RECORD_V2_HEADER_LEN = 8;
+
+/**
+ * Estimate the lamports needed to allocate a Record V2 account
+ * @param contentLength - Encoded content size in bytes
+ * @param validationIdLength - Bytes of staleness/ROA validation ids (32 per Solana validation)
+ */
+export async function estimateRecordRent(
+  connection: Connection,
+  contentLength: number,
+  validationIdLength: number = 32
+): Promise<number> {
+  const size = NAME_REGISTRY_HEADER_LEN + RECORD_V2_HEADER_LEN + validationIdLength + contentLength;
+  return connection.getMinimumBalanceForRentExemption(size);
+}
+
 /**
  * Check if a user's vault exists
  */
@@ -119,6 +358,128 @@ export async function vaultExists(
   return accountInfo !== null;
 }
 
+/**
+ * Check if a user's vault was created by an older program version and must be
+ * upgraded before the program accepts it
+ */
+export async function vaultNeedsUpgrade(
+  connection: Connection,
+  owner: PublicKey
+): Promise<boolean> {
+  const [vaultPDA] = getVaultPDA(owner);
+  const accountInfo = await connection.getAccountInfo(vaultPDA);
+  return accountInfo !== null && accountInfo.data.length < VAULT_ACCOUNT_SIZE;
+}
+
+/**
+ * How a vaulted domain is held (mirrors the program's DomainKind enum)
+ * The kind decides which withdraw instruction applies
+ */
+export enum DomainKind {
+  Unwrapped = 0,
+  WrappedSplToken = 1,
+  WrappedToken2022 = 2,
+  Compressed = 3,
+  CoreAsset = 4,
+  AltTld = 5,
+}
+
+/**
+ * Get the kind of a vaulted domain from its domain entry, or null if the vault has no entry for it
+ * `domain` is the domain mint (wrapped) or name registry account (unwrapped)
+ */
+export async function fetchDomainKind(
+  connection: Connection,
+  owner: PublicKey,
+  domain: PublicKey
+): Promise<DomainKind | null> {
+  const [vaultPDA] = getVaultPDA(owner);
+  const [domainEntryPDA] = getDomainEntryPDA(vaultPDA, domain);
+  const accountInfo = await connection.getAccountInfo(domainEntryPDA);
+  // DomainEntry layout: discriminator (8) + vault (32) + domain (32) + kind (1) + ...
+  if (!accountInfo || accountInfo.data.length < 73) {
+    return null;
+  }
+  return accountInfo.data[72] as DomainKind;
+}
+
+/**
+ * Get the token program a wrapped domain was deposited under, from its entry kind
+ * (mirrors the program's `domain_token_program`)
+ */
+export function getDomainKindTokenProgram(kind: DomainKind): PublicKey {
+  if (kind === DomainKind.WrappedToken2022) {
+    return TOKEN_2022_PROGRAM_ID;
+  }
+  if (kind === DomainKind.WrappedSplToken) {
+    return TOKEN_PROGRAM_ID;
+  }
+  throw new Error(`Domain kind ${DomainKind[kind]} is not held as a token`);
+}
+
+/**
+ * Compact vault counters (mirrors the program's VaultSummary account)
+ */
+export interface VaultSummary {
+  totalDomains: bigint;
+  wrappedCount: bigint;
+  unwrappedCount: bigint;
+  lastActivityAt: bigint;
+}
+
+/**
+ * Fetch a vault's summary in a single account read, or null if the owner hasn't created one
+ */
+export async function fetchVaultSummary(
+  connection: Connection,
+  owner: PublicKey
+): Promise<VaultSummary | null> {
+  const [vaultPDA] = getVaultPDA(owner);
+  const [vaultSummaryPDA] = getVaultSummaryPDA(vaultPDA);
+  const accountInfo = await connection.getAccountInfo(vaultSummaryPDA);
+  // VaultSummary layout: discriminator (8) + vault (32) + 3 x u64 counters + last_activity_at (i64) + bump
+  if (!accountInfo || accountInfo.data.length < 72) {
+    return null;
+  }
+  const data = accountInfo.data;
+  return {
+    totalDomains: data.readBigUInt64LE(40),
+    wrappedCount: data.readBigUInt64LE(48),
+    unwrappedCount: data.readBigUInt64LE(56),
+    lastActivityAt: data.readBigInt64LE(64),
+  };
+}
+
+/**
+ * Deployed program version and feature bits (mirrors the program's ProgramInfo account)
+ */
+export interface ProgramInfo {
+  version: string;
+  features: bigint;
+  updatedSlot: bigint;
+}
+
+/**
+ * Fetch the deployed program's version and feature bits, or null if never synced
+ * Feature bits match the program's FEATURE_* constants
+ */
+export async function fetchProgramInfo(
+  connection: Connection
+): Promise<ProgramInfo | null> {
+  const [programInfoPDA] = getProgramInfoPDA();
+  const accountInfo = await connection.getAccountInfo(programInfoPDA);
+  // ProgramInfo layout: discriminator (8) + 3 x u16 version + features (u64) + updated_slot (u64) + bump
+  if (!accountInfo || accountInfo.data.length < 31) {
+    return null;
+  }
+  const data = accountInfo.data;
+  return {
+    version: `${data.readUInt16LE(8)}.${data.readUInt16LE(10)}.${data.readUInt16LE(12)}`,
+    features: data.readBigUInt64LE(14),
+    updatedSlot: data.readBigUInt64LE(22),
+  };
+}
+
 /**
  * Check if a domain is secured (in the vault)
  * We check if the vault's token account for this domain mint has a balance of 1
@@ -160,6 +521,73 @@ export async function isDomainSecured(
   }
 }
 
+/**
+ * Instruction data for deposits/withdrawals: discriminator + Option<[u8; 32]> memo hash
+ * The memo hash binds an off-chain (e.g. compliance ticket) reference to the operation
+ */
+export function encodeCustodyInstructionData(discriminator: Buffer, memoHash?: Uint8Array): Buffer {
+  if (!memoHash) {
+    return Buffer.concat([discriminator, Buffer.from([0])]);
+  }
+  if (memoHash.length !== 32) {
+    throw new Error('Memo hash must be 32 bytes');
+  }
+  return Buffer.concat([discriminator, Buffer.from([1]), Buffer.from(memoHash)]);
+}
+
+/**
+ * Instruction data for withdrawals: custody data + Option<String> confirmation phrase
+ * The phrase is required when the vault has set one
+ */
+export function encodeWithdrawInstructionData(
+  discriminator: Buffer,
+  memoHash?: Uint8Array,
+  confirmationPhrase?: string
+): Buffer {
+  const custodyData = encodeCustodyInstructionData(discriminator, memoHash);
+  if (confirmationPhrase === undefined) {
+    return Buffer.concat([custodyData, Buffer.from([0])]);
+  }
+  const phrase = Buffer.from(confirmationPhrase, 'utf8');
+  const length = Buffer.alloc(4);
+  length.writeUInt32LE(phrase.length);
+  return Buffer.concat([custodyData, Buffer.from([1]), length, phrase]);
+}
+
+/**
+ * Account meta for the optional vault co-signer of withdrawals
+ * Anchor reads the program ID in an optional account's slot as "not provided"
+ */
+function optionalCoSignerKey(coSigner?: PublicKey) {
+  return coSigner
+    ? { pubkey: coSigner, isSigner: true, isWritable: false }
+    : { pubkey: VAULT_PROGRAM_ID, isSigner: false, isWritable: false };
+}
+
+/**
+ * Account metas for the protocol config and stats slots of a withdrawal
+ * Exits never depend on admin setup, so a PDA that isn't initialized is passed as "not provided"
+ */
+export async function fetchWithdrawProtocolKeys(connection: Connection) {
+  const [configPDA] = getConfigPDA();
+  const [statsPDA] = getStatsPDA();
+  const [config, stats] = await connection.getMultipleAccountsInfo([configPDA, statsPDA]);
+  return [
+    { pubkey: config ? configPDA : VAULT_PROGRAM_ID, isSigner: false, isWritable: false },
+    { pubkey: stats ? statsPDA : VAULT_PROGRAM_ID, isSigner: false, isWritable: stats !== null },
+  ];
+}
+
+/**
+ * Withdrawal protocol account metas assuming the config and stats PDAs are initialized
+ */
+function defaultWithdrawProtocolKeys() {
+  return [
+    { pubkey: getConfigPDA()[0], isSigner: false, isWritable: false },
+    { pubkey: getStatsPDA()[0], isSigner: false, isWritable: true },
+  ];
+}
+
 /**
  * Create initialize vault instruction
  */
@@ -167,10 +595,12 @@ export function createInitializeVaultInstruction(
   owner: PublicKey
 ): TransactionInstruction {
   const [vaultPDA] = getVaultPDA(owner);
+  const [statsPDA] = getStatsPDA();
   
   const keys = [
     { pubkey: owner, isSigner: true, isWritable: true },
     { pubkey: vaultPDA, isSigner: false, isWritable: true },
+    { pubkey: statsPDA, isSigner: false, isWritable: true },
     { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
   ];
   
@@ -181,12 +611,52 @@ export function createInitializeVaultInstruction(
   });
 }
 
+/**
+ * Create upgrade vault instruction
+ * Grows a vault created by an older program version to the current layout
+ */
+export function createUpgradeVaultInstruction(
+  owner: PublicKey
+): TransactionInstruction {
+  const [vaultPDA] = getVaultPDA(owner);
+  
+  const keys = [
+    { pubkey: owner, isSigner: true, isWritable: true },
+    { pubkey: vaultPDA, isSigner: false, isWritable: true },
+    { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+  ];
+  
+  return new TransactionInstruction({
+    keys,
+    programId: VAULT_PROGRAM_ID,
+    data: UPGRADE_VAULT_DISCRIMINATOR,
+  });
+}
+
+/**
+ * Add vault initialization or upgrade instructions to a transaction as needed
+ */
+export async function addVaultSetupInstructions(
+  connection: Connection,
+  transaction: Transaction,
+  owner: PublicKey
+): Promise<void> {
+  const hasVault = await vaultExists(connection, owner);
+  if (!hasVault) {
+    transaction.add(createInitializeVaultInstruction(owner));
+  } else if (await vaultNeedsUpgrade(connection, owner)) {
+    transaction.add(createUpgradeVaultInstruction(owner));
+  }
+}
+
 /**
  * Create deposit domain instruction
  */
 export async function createDepositDomainInstruction(
   owner: PublicKey,
-  domainMint: PublicKey
+  domainMint: PublicKey,
+  nameAccount: PublicKey, // The tokenized domain's name registry, which domainMint derives from
+  memoHash?: Uint8Array
 ): Promise<TransactionInstruction> {
   const [vaultPDA] = getVaultPDA(owner);
   
@@ -203,12 +673,28 @@ export async function createDepositDomainInstruction(
     true // allowOwnerOffCurve - required for PDAs
   );
   
+  const [configPDA] = getConfigPDA();
+  const [treasuryPDA] = getTreasuryPDA();
+  const [statsPDA] = getStatsPDA();
+  const [vaultSummaryPDA] = getVaultSummaryPDA(vaultPDA);
+  const [userPointsPDA] = getUserPointsPDA(owner);
+  const [domainEntryPDA] = getDomainEntryPDA(vaultPDA, domainMint);
+  const [mintPolicyPDA] = getMintPolicyPDA(domainMint);
+  
   const keys = [
     { pubkey: owner, isSigner: true, isWritable: true },
     { pubkey: vaultPDA, isSigner: false, isWritable: true },
+    { pubkey: configPDA, isSigner: false, isWritable: false },
+    { pubkey: treasuryPDA, isSigner: false, isWritable: true },
+    { pubkey: statsPDA, isSigner: false, isWritable: true },
+    { pubkey: vaultSummaryPDA, isSigner: false, isWritable: true },
+    { pubkey: userPointsPDA, isSigner: false, isWritable: true },
     { pubkey: domainMint, isSigner: false, isWritable: false },
+    { pubkey: nameAccount, isSigner: false, isWritable: false },
+    { pubkey: mintPolicyPDA, isSigner: false, isWritable: false },
     { pubkey: userTokenAccount, isSigner: false, isWritable: true },
     { pubkey: vaultTokenAccount, isSigner: false, isWritable: true },
+    { pubkey: domainEntryPDA, isSigner: false, isWritable: true },
     { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
     { pubkey: ASSOCIATED_TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
     { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
@@ -217,7 +703,7 @@ export async function createDepositDomainInstruction(
   return new TransactionInstruction({
     keys,
     programId: VAULT_PROGRAM_ID,
-    data: DEPOSIT_DOMAIN_DISCRIMINATOR,
+    data: encodeCustodyInstructionData(DEPOSIT_DOMAIN_DISCRIMINATOR, memoHash),
   });
 }
 
@@ -226,38 +712,59 @@ export async function createDepositDomainInstruction(
  */
 export async function createWithdrawDomainInstruction(
   owner: PublicKey,
-  domainMint: PublicKey
+  domainMint: PublicKey,
+  rentPayer: PublicKey = owner, // Key that paid the domain entry's rent (the owner for deposits made here)
+  memoHash?: Uint8Array,
+  coSigner?: PublicKey, // Vault co-signer, required when the vault has one (must also sign the transaction)
+  confirmationPhrase?: string, // Withdrawal confirmation phrase, required when the vault has one
+  tokenProgramId: PublicKey = TOKEN_PROGRAM_ID, // Token program the domain was deposited under
+  protocolKeys = defaultWithdrawProtocolKeys() // Config and stats slots (see fetchWithdrawProtocolKeys)
 ): Promise<TransactionInstruction> {
   const [vaultPDA] = getVaultPDA(owner);
   
   // Vault's token account (where the domain NFT currently is)
-  const vaultTokenAccount = await getAssociatedTokenAddress(
-    domainMint,
-    vaultPDA,
-    true // allowOwnerOffCurve - required for PDAs
-  );
+  const vaultTokenAccount = getVaultTokenAccount(owner, domainMint, tokenProgramId);
   
   // User's token account (where the domain will be withdrawn to)
   const userTokenAccount = await getAssociatedTokenAddress(
     domainMint,
-    owner
+    owner,
+    false,
+    tokenProgramId
   );
   
+  const [vaultSummaryPDA] = getVaultSummaryPDA(vaultPDA);
+  const [outflowBreakerPDA] = getOutflowBreakerPDA();
+  const [securityLogPDA] = getSecurityLogPDA();
+  const [userPointsPDA] = getUserPointsPDA(owner);
+  const [domainEntryPDA] = getDomainEntryPDA(vaultPDA, domainMint);
+  const [withdrawalPhrasePDA] = getWithdrawalPhrasePDA(vaultPDA);
+  
   const keys = [
     { pubkey: owner, isSigner: true, isWritable: true },
     { pubkey: vaultPDA, isSigner: false, isWritable: true },
+    // Withdrawals are fee-free and work before the admin initializes config or stats
+    ...protocolKeys,
+    { pubkey: vaultSummaryPDA, isSigner: false, isWritable: true },
+    { pubkey: outflowBreakerPDA, isSigner: false, isWritable: true },
+    { pubkey: securityLogPDA, isSigner: false, isWritable: true },
+    { pubkey: userPointsPDA, isSigner: false, isWritable: true },
     { pubkey: domainMint, isSigner: false, isWritable: false },
     { pubkey: vaultTokenAccount, isSigner: false, isWritable: true },
     { pubkey: userTokenAccount, isSigner: false, isWritable: true },
-    { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+    { pubkey: domainEntryPDA, isSigner: false, isWritable: true },
+    { pubkey: rentPayer, isSigner: false, isWritable: true },
+    { pubkey: tokenProgramId, isSigner: false, isWritable: false },
     { pubkey: ASSOCIATED_TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
     { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+    { pubkey: withdrawalPhrasePDA, isSigner: false, isWritable: false },
+    optionalCoSignerKey(coSigner),
   ];
   
   return new TransactionInstruction({
     keys,
     programId: VAULT_PROGRAM_ID,
-    data: WITHDRAW_DOMAIN_DISCRIMINATOR,
+    data: encodeWithdrawInstructionData(WITHDRAW_DOMAIN_DISCRIMINATOR, memoHash, confirmationPhrase),
   });
 }
 
@@ -268,18 +775,16 @@ export async function createWithdrawDomainInstruction(
 export async function buildDepositTransaction(
   connection: Connection,
   owner: PublicKey,
-  domainMint: PublicKey
+  domainMint: PublicKey,
+  nameAccount: PublicKey
 ): Promise<Transaction> {
   const transaction = new Transaction();
   
-  // Check if vault exists, if not add initialization instruction
-  const hasVault = await vaultExists(connection, owner);
-  if (!hasVault) {
-    transaction.add(createInitializeVaultInstruction(owner));
-  }
+  // Initialize the vault if it doesn't exist, or upgrade it if it's outdated
+  await addVaultSetupInstructions(connection, transaction, owner);
   
   // Add deposit instruction
-  const depositIx = await createDepositDomainInstruction(owner, domainMint);
+  const depositIx = await createDepositDomainInstruction(owner, domainMint, nameAccount);
   transaction.add(depositIx);
   
   return transaction;
@@ -289,13 +794,30 @@ export async function buildDepositTransaction(
  * Build a complete withdraw transaction
  */
 export async function buildWithdrawTransaction(
+  connection: Connection,
   owner: PublicKey,
   domainMint: PublicKey
 ): Promise<Transaction> {
   const transaction = new Transaction();
   
-  // Add withdraw instruction
-  const withdrawIx = await createWithdrawDomainInstruction(owner, domainMint);
+  // Upgrade the vault first if it's outdated
+  if (await vaultNeedsUpgrade(connection, owner)) {
+    transaction.add(createUpgradeVaultInstruction(owner));
+  }
+  
+  // Withdraw under the token program recorded in the domain's entry
+  const kind = await fetchDomainKind(connection, owner, domainMint);
+  const tokenProgramId = getDomainKindTokenProgram(kind ?? DomainKind.WrappedSplToken);
+  const withdrawIx = await createWithdrawDomainInstruction(
+    owner,
+    domainMint,
+    owner,
+    undefined,
+    undefined,
+    undefined,
+    tokenProgramId,
+    await fetchWithdrawProtocolKeys(connection)
+  );
   transaction.add(withdrawIx);
   
   return transaction;
@@ -306,14 +828,27 @@ export async function buildWithdrawTransaction(
  */
 export function createDepositUnwrappedDomainInstruction(
   owner: PublicKey,
-  nameAccount: PublicKey
+  nameAccount: PublicKey,
+  memoHash?: Uint8Array
 ): TransactionInstruction {
   const [vaultPDA] = getVaultPDA(owner);
+  const [configPDA] = getConfigPDA();
+  const [treasuryPDA] = getTreasuryPDA();
+  const [statsPDA] = getStatsPDA();
+  const [vaultSummaryPDA] = getVaultSummaryPDA(vaultPDA);
+  const [userPointsPDA] = getUserPointsPDA(owner);
+  const [domainEntryPDA] = getDomainEntryPDA(vaultPDA, nameAccount);
   
   const keys = [
     { pubkey: owner, isSigner: true, isWritable: true },
     { pubkey: vaultPDA, isSigner: false, isWritable: true },
+    { pubkey: configPDA, isSigner: false, isWritable: false },
+    { pubkey: treasuryPDA, isSigner: false, isWritable: true },
+    { pubkey: statsPDA, isSigner: false, isWritable: true },
+    { pubkey: vaultSummaryPDA, isSigner: false, isWritable: true },
+    { pubkey: userPointsPDA, isSigner: false, isWritable: true },
     { pubkey: nameAccount, isSigner: false, isWritable: true },
+    { pubkey: domainEntryPDA, isSigner: false, isWritable: true },
     { pubkey: NAME_SERVICE_PROGRAM_ID, isSigner: false, isWritable: false },
     { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
   ];
@@ -321,7 +856,7 @@ export function createDepositUnwrappedDomainInstruction(
   return new TransactionInstruction({
     keys,
     programId: VAULT_PROGRAM_ID,
-    data: DEPOSIT_UNWRAPPED_DOMAIN_DISCRIMINATOR,
+    data: encodeCustodyInstructionData(DEPOSIT_UNWRAPPED_DOMAIN_DISCRIMINATOR, memoHash),
   });
 }
 
@@ -332,25 +867,44 @@ export function createDepositUnwrappedDomainInstruction(
  */
 export function createDepositDomainWithRecordInstruction(
   owner: PublicKey,
-  nameAccount: PublicKey
+  nameAccount: PublicKey,
+  memoHash?: Uint8Array
 ): TransactionInstruction {
   const [vaultPDA] = getVaultPDA(owner);
   const [solRecordV2] = getSolRecordV2PDA(nameAccount);
   const [centralState] = getSnsRecordsCentralState();
+  const [configPDA] = getConfigPDA();
+  const [treasuryPDA] = getTreasuryPDA();
+  const [statsPDA] = getStatsPDA();
+  const [vaultSummaryPDA] = getVaultSummaryPDA(vaultPDA);
+  const [userPointsPDA] = getUserPointsPDA(owner);
+  const [domainEntryPDA] = getDomainEntryPDA(vaultPDA, nameAccount);
   
   // Accounts must match the order in DepositDomainWithRecord struct:
   // 1. owner (signer, writable)
   // 2. vault (writable)
-  // 3. name_account (writable)
-  // 4. sol_record_v2 (writable)
-  // 5. central_state (readonly)
-  // 6. name_service_program (readonly)
-  // 7. sns_records_program (readonly)
-  // 8. system_program (readonly)
+  // 3. config (readonly)
+  // 4. treasury (writable)
+  // 5. stats (writable)
+  // 6. vault_summary (writable)
+  // 7. user_points (writable)
+  // 8. name_account (writable)
+  // 9. domain_entry (writable)
+  // 10. sol_record_v2 (writable)
+  // 11. central_state (readonly)
+  // 12. name_service_program (readonly)
+  // 13. sns_records_program (readonly)
+  // 14. system_program (readonly)
   const keys = [
     { pubkey: owner, isSigner: true, isWritable: true },
     { pubkey: vaultPDA, isSigner: false, isWritable: true },
+    { pubkey: configPDA, isSigner: false, isWritable: false },
+    { pubkey: treasuryPDA, isSigner: false, isWritable: true },
+    { pubkey: statsPDA, isSigner: false, isWritable: true },
+    { pubkey: vaultSummaryPDA, isSigner: false, isWritable: true },
+    { pubkey: userPointsPDA, isSigner: false, isWritable: true },
     { pubkey: nameAccount, isSigner: false, isWritable: true },
+    { pubkey: domainEntryPDA, isSigner: false, isWritable: true },
     { pubkey: solRecordV2, isSigner: false, isWritable: true },
     { pubkey: centralState, isSigner: false, isWritable: false },
     { pubkey: NAME_SERVICE_PROGRAM_ID, isSigner: false, isWritable: false },
@@ -361,7 +915,7 @@ export function createDepositDomainWithRecordInstruction(
   return new TransactionInstruction({
     keys,
     programId: VAULT_PROGRAM_ID,
-    data: DEPOSIT_DOMAIN_WITH_RECORD_DISCRIMINATOR,
+    data: encodeCustodyInstructionData(DEPOSIT_DOMAIN_WITH_RECORD_DISCRIMINATOR, memoHash),
   });
 }
 
@@ -370,22 +924,104 @@ export function createDepositDomainWithRecordInstruction(
  */
 export function createWithdrawUnwrappedDomainInstruction(
   owner: PublicKey,
-  nameAccount: PublicKey
+  nameAccount: PublicKey,
+  rentPayer: PublicKey = owner, // Key that paid the domain entry's rent (the owner for deposits made here)
+  memoHash?: Uint8Array,
+  coSigner?: PublicKey, // Vault co-signer, required when the vault has one (must also sign the transaction)
+  confirmationPhrase?: string, // Withdrawal confirmation phrase, required when the vault has one
+  protocolKeys = defaultWithdrawProtocolKeys() // Config and stats slots (see fetchWithdrawProtocolKeys)
 ): TransactionInstruction {
   const [vaultPDA] = getVaultPDA(owner);
+  const [vaultSummaryPDA] = getVaultSummaryPDA(vaultPDA);
+  const [outflowBreakerPDA] = getOutflowBreakerPDA();
+  const [securityLogPDA] = getSecurityLogPDA();
+  const [userPointsPDA] = getUserPointsPDA(owner);
+  const [domainEntryPDA] = getDomainEntryPDA(vaultPDA, nameAccount);
+  const [withdrawalPhrasePDA] = getWithdrawalPhrasePDA(vaultPDA);
   
   const keys = [
     { pubkey: owner, isSigner: true, isWritable: true },
     { pubkey: vaultPDA, isSigner: false, isWritable: true },
+    // Withdrawals are fee-free and work before the admin initializes config or stats
+    ...protocolKeys,
+    { pubkey: vaultSummaryPDA, isSigner: false, isWritable: true },
+    { pubkey: outflowBreakerPDA, isSigner: false, isWritable: true },
+    { pubkey: securityLogPDA, isSigner: false, isWritable: true },
+    { pubkey: userPointsPDA, isSigner: false, isWritable: true },
     { pubkey: nameAccount, isSigner: false, isWritable: true },
+    { pubkey: domainEntryPDA, isSigner: false, isWritable: true },
+    { pubkey: rentPayer, isSigner: false, isWritable: true },
     { pubkey: NAME_SERVICE_PROGRAM_ID, isSigner: false, isWritable: false },
     { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+    { pubkey: withdrawalPhrasePDA, isSigner: false, isWritable: false },
+    optionalCoSignerKey(coSigner),
   ];
   
   return new TransactionInstruction({
     keys,
     programId: VAULT_PROGRAM_ID,
-    data: WITHDRAW_UNWRAPPED_DOMAIN_DISCRIMINATOR,
+    data: encodeWithdrawInstructionData(
+      WITHDRAW_UNWRAPPED_DOMAIN_DISCRIMINATOR,
+      memoHash,
+      confirmationPhrase
+    ),
+  });
+}
+
+// DNS-style records accepted by update_dns_record, in the program's enum order
+const DNS_RECORD_TYPES = ['A', 'AAAA', 'CNAME', 'TXT'] as const;
+export type DnsRecordType = (typeof DNS_RECORD_TYPES)[number];
+
+/**
+ * Create an instruction setting a DNS-style record (A, AAAA, CNAME, TXT) of a vaulted
+ * unwrapped domain; the value is the human-readable form (e.g. "192.0.2.1"), parsed on-chain
+ * @param authority - The vault owner, or a record operator of the domain
+ * @param operatorGrant - The authority's operator grant PDA when it isn't the owner
+ */
+export function createUpdateDnsRecordInstruction(
+  owner: PublicKey,
+  nameAccount: PublicKey,
+  recordType: DnsRecordType,
+  value: string,
+  authority: PublicKey = owner,
+  operatorGrant?: PublicKey
+): TransactionInstruction {
+  const [vaultPDA] = getVaultPDA(owner);
+  const [configPDA] = getConfigPDA();
+  const [treasuryPDA] = getTreasuryPDA();
+  const [domainEntryPDA] = getDomainEntryPDA(vaultPDA, nameAccount);
+  const [recordPDA] = getRecordV2PDA(nameAccount, recordType);
+  const [centralState] = getSnsRecordsCentralState();
+
+  const keys = [
+    { pubkey: authority, isSigner: true, isWritable: true },
+    { pubkey: vaultPDA, isSigner: false, isWritable: true },
+    { pubkey: configPDA, isSigner: false, isWritable: false },
+    { pubkey: treasuryPDA, isSigner: false, isWritable: true },
+    { pubkey: nameAccount, isSigner: false, isWritable: true },
+    { pubkey: domainEntryPDA, isSigner: false, isWritable: false },
+    { pubkey: operatorGrant ?? VAULT_PROGRAM_ID, isSigner: false, isWritable: false },
+    { pubkey: SYSVAR_INSTRUCTIONS_PUBKEY, isSigner: false, isWritable: false },
+    { pubkey: recordPDA, isSigner: false, isWritable: true },
+    { pubkey: centralState, isSigner: false, isWritable: false },
+    { pubkey: NAME_SERVICE_PROGRAM_ID, isSigner: false, isWritable: false },
+    { pubkey: SNS_RECORDS_PROGRAM_ID, isSigner: false, isWritable: false },
+    { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+  ];
+
+  const valueBytes = Buffer.from(value, 'utf8');
+  const length = Buffer.alloc(4);
+  length.writeUInt32LE(valueBytes.length);
+
+  return new TransactionInstruction({
+    keys,
+    programId: VAULT_PROGRAM_ID,
+    data: Buffer.concat([
+      UPDATE_DNS_RECORD_DISCRIMINATOR,
+      Buffer.from([DNS_RECORD_TYPES.indexOf(recordType)]),
+      length,
+      valueBytes,
+    ]),
   });
 }
 
@@ -400,11 +1036,8 @@ export async function buildDepositUnwrappedTransaction(
 ): Promise<Transaction> {
   const transaction = new Transaction();
   
-  // Check if vault exists, if not add initialization instruction
-  const hasVault = await vaultExists(connection, owner);
-  if (!hasVault) {
-    transaction.add(createInitializeVaultInstruction(owner));
-  }
+  // Initialize the vault if it doesn't exist, or upgrade it if it's outdated
+  await addVaultSetupInstructions(connection, transaction, owner);
   
   // Add deposit instruction
   const depositIx = createDepositUnwrappedDomainInstruction(owner, nameAccount);
@@ -426,11 +1059,8 @@ export async function buildDepositWithRecordTransaction(
 ): Promise<Transaction> {
   const transaction = new Transaction();
   
-  // Check if vault exists, if not add initialization instruction
-  const hasVault = await vaultExists(connection, owner);
-  if (!hasVault) {
-    transaction.add(createInitializeVaultInstruction(owner));
-  }
+  // Initialize the vault if it doesn't exist, or upgrade it if it's outdated
+  await addVaultSetupInstructions(connection, transaction, owner);
   
   // Add deposit with record instruction
   const depositIx = createDepositDomainWithRecordInstruction(owner, nameAccount);
@@ -442,14 +1072,28 @@ export async function buildDepositWithRecordTransaction(
 /**
  * Build a complete withdraw transaction for unwrapped domain
  */
-export function buildWithdrawUnwrappedTransaction(
+export async function buildWithdrawUnwrappedTransaction(
+  connection: Connection,
   owner: PublicKey,
   nameAccount: PublicKey
-): Transaction {
+): Promise<Transaction> {
   const transaction = new Transaction();
   
+  // Upgrade the vault first if it's outdated
+  if (await vaultNeedsUpgrade(connection, owner)) {
+    transaction.add(createUpgradeVaultInstruction(owner));
+  }
+  
   // Add withdraw instruction
-  const withdrawIx = createWithdrawUnwrappedDomainInstruction(owner, nameAccount);
+  const withdrawIx = createWithdrawUnwrappedDomainInstruction(
+    owner,
+    nameAccount,
+    owner,
+    undefined,
+    undefined,
+    undefined,
+    await fetchWithdrawProtocolKeys(connection)
+  );
   transaction.add(withdrawIx);
   
   return transaction;
@@ -470,13 +1114,7 @@ export function createInitVaultTokenAccountInstruction(
   const [vaultPDA] = getVaultPDA(owner);
   
   // Get the vault's ATA for this token mint (using the correct token program)
-  const vaultTokenAccount = getAssociatedTokenAddressSync(
-    tokenMint,
-    vaultPDA,
-    true, // allowOwnerOffCurve - required for PDAs
-    tokenProgramId,
-    ASSOCIATED_TOKEN_PROGRAM_ID
-  );
+  const vaultTokenAccount = getVaultTokenAccount(owner, tokenMint, tokenProgramId);
   
   const keys = [
     { pubkey: owner, isSigner: true, isWritable: true },
@@ -495,6 +1133,39 @@ export function createInitVaultTokenAccountInstruction(
   });
 }
 
+/**
+ * Create instruction to pre-create the accounts of an upcoming deposit (vault ATA and the
+ * owner's points ledger), so the owner's deposit transaction stays small.
+ * Any payer can send it; the vault owner doesn't need to sign.
+ */
+export function createPrepareDepositInstruction(
+  payer: PublicKey,
+  owner: PublicKey,
+  domainMint: PublicKey,
+  tokenProgramId: PublicKey = TOKEN_PROGRAM_ID
+): TransactionInstruction {
+  const [vaultPDA] = getVaultPDA(owner);
+  const [userPointsPDA] = getUserPointsPDA(owner);
+  const vaultTokenAccount = getVaultTokenAccount(owner, domainMint, tokenProgramId);
+
+  const keys = [
+    { pubkey: payer, isSigner: true, isWritable: true },
+    { pubkey: vaultPDA, isSigner: false, isWritable: false },
+    { pubkey: userPointsPDA, isSigner: false, isWritable: true },
+    { pubkey: domainMint, isSigner: false, isWritable: false },
+    { pubkey: vaultTokenAccount, isSigner: false, isWritable: true },
+    { pubkey: tokenProgramId, isSigner: false, isWritable: false },
+    { pubkey: ASSOCIATED_TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+    { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+  ];
+
+  return new TransactionInstruction({
+    keys,
+    programId: VAULT_PROGRAM_ID,
+    data: PREPARE_DEPOSIT_DISCRIMINATOR,
+  });
+}
+
 /**
  * Build a transaction to initialize vault with multiple token accounts
  * Creates the vault (if needed) and ATAs for all specified token mints
@@ -506,13 +1177,9 @@ export async function buildInitVaultWithTokensTransaction(
   tokenMints: PublicKey[]
 ): Promise<Transaction> {
   const transaction = new Transaction();
-  const [vaultPDA] = getVaultPDA(owner);
   
-  // Check if vault exists, if not add initialization instruction
-  const hasVault = await vaultExists(connection, owner);
-  if (!hasVault) {
-    transaction.add(createInitializeVaultInstruction(owner));
-  }
+  // Initialize the vault if it doesn't exist, or upgrade it if it's outdated
+  await addVaultSetupInstructions(connection, transaction, owner);
   
   // Add init token account instructions for each mint (skip existing ATAs)
   for (const tokenMint of tokenMints) {
@@ -526,13 +1193,7 @@ export async function buildInitVaultWithTokensTransaction(
     }
     
     // Get ATA address using the correct token program
-    const vaultTokenAccount = getAssociatedTokenAddressSync(
-      tokenMint,
-      vaultPDA,
-      true, // allowOwnerOffCurve - required for PDAs
-      tokenProgramId,
-      ASSOCIATED_TOKEN_PROGRAM_ID
-    );
+    const vaultTokenAccount = getVaultTokenAccount(owner, tokenMint, tokenProgramId);
     
     // Check if ATA already exists
     const ataInfo = await connection.getAccountInfo(vaultTokenAccount);
@@ -568,9 +1229,16 @@ export async function fetchVaultNFTMints(
       programId: TOKEN_PROGRAM_ID,
     });
     
+    // Quarantined dust accounts are not vault holdings
+    const quarantineInfos = await connection.getMultipleAccountsInfo(
+      tokenAccounts.value.map(({ pubkey }) => getQuarantinePDA(pubkey)[0])
+    );
+    
     const nftMints: string[] = [];
     
-    for (const { account } of tokenAccounts.value) {
+    for (const [index, { account }] of tokenAccounts.value.entries()) {
+      if (quarantineInfos[index]) continue;
+      
       const data = account.data;
       
       // SPL Token account data layout: